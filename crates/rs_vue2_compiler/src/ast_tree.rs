@@ -1,3 +1,4 @@
+use crate::diagnostics::{CompilerDiagnostic, DiagnosticCode};
 use crate::directives_model::gen_assignment_code;
 use crate::filter_parser::parse_filters;
 use crate::helpers::{is_some_and_ref, to_camel, to_hyphen_case};
@@ -8,28 +9,56 @@ use crate::util::{
 };
 use crate::warn_logger::WarnLogger;
 use crate::web::attrs::must_use_prop;
+use crate::web::compiler::directives::get_platform_directive;
+use crate::web::element::is_reserved_tag;
 use crate::{
     CompilerOptions, ARG_RE, BIND_RE, DIR_RE, DIR_RE_VBIND_SHORT_HAND, DYNAMIC_ARG_RE,
     FOR_ALIAS_RE, FOR_ITERATOR_RE, MODIFIER_RE, ON_RE, PROP_BIND_RE, SLOT_RE, STRIP_PARENS_RE,
 };
+use indexmap::IndexMap;
 use regex::Regex;
 use rs_html_parser_tokenizer_tokens::QuoteType;
-use rs_html_parser_tokens::Token;
+use rs_html_parser_tokens::TokenKind;
 use rs_html_parser_tokens::TokenKind::{OpenTag, ProcessingInstruction};
 use std::cell::{Cell, RefCell};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::rc::{Rc, Weak};
-use unicase_collections::unicase_btree_map::UniCaseBTreeMap;
-use unicase_collections::unicase_btree_set::UniCaseBTreeSet;
-
-pub const EMPTY_SLOT_SCOPE_TOKEN: &'static str = "_empty_";
+use unicase_collections::unicase_index_map::UniCaseIndexMap;
+use unicase_collections::unicase_index_set::UniCaseIndexSet;
+
+#[cfg(feature = "serde")]
+mod serialize;
+
+#[cfg(feature = "serde")]
+pub use serialize::SerializableNode;
+
+pub const EMPTY_SLOT_SCOPE_TOKEN: &str = "_empty_";
+
+// value and quotes of an attribute as stored in the token, `None` without a value
+pub type TokenAttrValue = Option<(Box<str>, QuoteType)>;
+
+/// Start tag, end tag, text or comment emitted by the html parser.
+#[derive(Debug, Clone)]
+pub struct Token {
+    pub data: Box<str>,
+    pub attrs: Option<TokenAttrs>,
+    pub kind: TokenKind,
+    pub is_implied: bool,
+    // source range, byte offsets into the template, `None` for tokens the parser made up
+    pub start: Option<usize>,
+    pub end: Option<usize>,
+    // attributes of a start tag as written, including duplicates
+    pub raw_attrs: Vec<RawAttr>,
+}
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct AttrItem {
     pub name: String,
     pub value: Option<String>,
     pub dynamic: bool,
+    // value is the raw attribute text instead of an expression
+    pub literal: bool,
     pub quote_type: QuoteType,
 }
 
@@ -37,7 +66,8 @@ pub struct AttrItem {
 pub struct Handler {
     pub value: String,
     pub dynamic: bool,
-    pub modifiers: UniCaseBTreeSet,
+    // `None` without modifiers, empty when all of them were consumed by the event name
+    pub modifiers: Option<UniCaseIndexSet>,
 }
 
 #[derive(Debug)]
@@ -47,7 +77,32 @@ pub struct Directive {
     pub value: Option<String>,
     pub arg: Option<String>,
     pub is_dynamic_arg: bool,
-    pub modifiers: UniCaseBTreeSet,
+    pub modifiers: UniCaseIndexSet,
+    // false for compile-time directives without a runtime counterpart
+    pub needs_runtime: bool,
+}
+
+// v-model on components, generated as model:{value,callback,expression}
+#[derive(Debug)]
+pub struct ComponentModel {
+    pub value: String,
+    pub callback: String,
+    pub expression: String,
+}
+
+// how much of a subtree the ssr codegen can render to plain strings
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SSROptimizability {
+    // whole subtree has to be rendered to vnodes
+    Unoptimizable,
+    // whole subtree can be rendered to a string
+    Full,
+    // the element itself can be rendered to a string, but not all of its children
+    SelfOnly,
+    // the element has to be a vnode, its children can be rendered to a string
+    Children,
+    // the element has to be a vnode and some of its children too
+    Partial,
 }
 
 #[derive(Debug)]
@@ -69,6 +124,158 @@ pub struct AttrEntry {
     pub name: String,
     pub value: Option<String>,
     pub quote_type: QuoteType,
+    pub start: Option<usize>,
+    pub end: Option<usize>,
+}
+
+/// Attribute exactly as written in the template, with its byte range.
+#[derive(Debug, Clone)]
+pub struct RawAttr {
+    pub name: String,
+    pub value: Option<String>,
+    pub start: usize,
+    pub end: usize,
+    /// start of the value, after the opening quote
+    pub value_start: Option<usize>,
+    pub quote_type: QuoteType,
+}
+
+/**
+ * Attributes as written in the template, in source order and with the exact case of their
+ * names, so `viewBox` and `viewbox` or `:fooBar` and `:foobar` are kept apart. Lookups prefer an
+ * exact match and fall back to ignoring case like html does.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct RawAttrsMap {
+    attrs: Vec<RawAttr>,
+}
+
+impl RawAttrsMap {
+    pub fn new() -> RawAttrsMap {
+        RawAttrsMap::default()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&RawAttr> {
+        self.get_exact(name).or_else(|| {
+            self.attrs
+                .iter()
+                .find(|attr| attr.name.eq_ignore_ascii_case(name))
+        })
+    }
+
+    pub fn get_exact(&self, name: &str) -> Option<&RawAttr> {
+        self.attrs.iter().find(|attr| attr.name == name)
+    }
+
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Adds `raw_attr` unless an attribute with the exact same name exists, returns whether
+    /// it was added.
+    pub fn insert(&mut self, raw_attr: RawAttr) -> bool {
+        if self.get_exact(&raw_attr.name).is_some() {
+            return false;
+        }
+
+        self.attrs.push(raw_attr);
+        true
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.attrs.iter().map(|attr| attr.name.as_str())
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &RawAttr> {
+        self.attrs.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.attrs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.attrs.is_empty()
+    }
+}
+
+/**
+ * Attributes of a start tag in the order they were written or added by transforms, keyed by
+ * their name as written. Like `RawAttrsMap`, lookups prefer an exact match and fall back to
+ * ignoring case like html does.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct TokenAttrs {
+    attrs: Vec<(String, TokenAttrValue)>,
+}
+
+impl TokenAttrs {
+    pub fn new() -> TokenAttrs {
+        TokenAttrs::default()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&TokenAttrValue> {
+        self.get_key_value(name).map(|(_, value)| value)
+    }
+
+    /// Like `get`, also returning the name the attribute was written with.
+    pub fn get_key_value(&self, name: &str) -> Option<(&str, &TokenAttrValue)> {
+        self.attrs
+            .iter()
+            .find(|(attr_name, _)| attr_name == name)
+            .or_else(|| {
+                self.attrs
+                    .iter()
+                    .find(|(attr_name, _)| attr_name.eq_ignore_ascii_case(name))
+            })
+            .map(|(attr_name, value)| (attr_name.as_str(), value))
+    }
+
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Sets the value of the attribute with the exact name `name`, adding it after the others if
+    /// there is none.
+    pub fn insert(&mut self, name: impl Into<String>, value: TokenAttrValue) {
+        let name = name.into();
+
+        match self
+            .attrs
+            .iter_mut()
+            .find(|(attr_name, _)| *attr_name == name)
+        {
+            Some((_, existing)) => *existing = value,
+            None => self.attrs.push((name, value)),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &TokenAttrValue)> {
+        self.attrs
+            .iter()
+            .map(|(name, value)| (name.as_str(), value))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.attrs.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.attrs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.attrs.is_empty()
+    }
+}
+
+impl IntoIterator for TokenAttrs {
+    type Item = (String, TokenAttrValue);
+    type IntoIter = std::vec::IntoIter<(String, TokenAttrValue)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.attrs.into_iter()
+    }
 }
 
 #[derive(Debug)]
@@ -82,11 +289,17 @@ pub struct ASTElement {
     pub static_class: Option<String>,
     pub class_binding: Option<String>,
 
+    // source range, byte offsets into the template
+    pub start: Option<usize>,
+    pub end: Option<usize>,
+    pub raw_attrs_map: RawAttrsMap,
+
     // extra
     pub forbidden: bool,
     pub pre: bool,
     pub plain: bool,
-    pub ignored: UniCaseBTreeSet,
+    // exact names of the attributes consumed by earlier processing steps
+    pub ignored: HashSet<String>,
     pub processed: bool,
     pub ref_val: Option<String>,
     pub ref_in_for: bool,
@@ -100,9 +313,11 @@ pub struct ASTElement {
     pub props: Vec<AttrItem>,
 
     pub directives: Option<Vec<Directive>>,
+    pub model: Option<ComponentModel>,
 
-    pub events: Option<UniCaseBTreeMap<Vec<Handler>>>,
-    pub native_events: Option<UniCaseBTreeMap<Vec<Handler>>>,
+    // in the order the handlers were added
+    pub events: Option<UniCaseIndexMap<Vec<Handler>>>,
+    pub native_events: Option<UniCaseIndexMap<Vec<Handler>>>,
 
     pub key: Option<String>,
 
@@ -114,18 +329,24 @@ pub struct ASTElement {
 
     // if
     pub if_val: Option<String>,
-    pub if_processed: bool,
     pub else_if_val: Option<String>,
     pub is_else: bool,
     pub if_conditions: Option<Vec<IfCondition>>,
 
     pub once: bool,
 
+    // optimizer
+    pub is_static: bool,
+    pub static_root: bool,
+    pub static_in_for: bool,
+    pub ssr_optimizability: Option<SSROptimizability>,
+
     pub slot_name: Option<String>,
     pub slot_target: Option<String>,
     pub slot_target_dynamic: bool,
     pub slot_scope: Option<String>,
-    pub scoped_slots: Option<UniCaseBTreeMap<Rc<RefCell<ASTNode>>>>,
+    // slot containers by name, in the order the slots are written
+    pub scoped_slots: Option<IndexMap<String, Rc<RefCell<ASTNode>>>>,
     pub has_bindings: bool,
     pub kind: ASTElementKind,
     pub is_comment: bool,
@@ -138,6 +359,9 @@ pub fn create_ast_element(token: Token, kind: ASTElementKind) -> ASTElement {
         kind,
         token,
         expression: None,
+        start: None,
+        end: None,
+        raw_attrs_map: RawAttrsMap::new(),
         forbidden: false,
         pre: false,
         plain: false,
@@ -149,11 +373,14 @@ pub fn create_ast_element(token: Token, kind: ASTElementKind) -> ASTElement {
         iterator1: None,
         iterator2: None,
         if_val: None,
-        if_processed: false,
         else_if_val: None,
         is_else: false,
         if_conditions: None,
         once: false,
+        is_static: false,
+        static_root: false,
+        static_in_for: false,
+        ssr_optimizability: None,
         slot_name: None,
         slot_target: None,
         key: None,
@@ -170,6 +397,7 @@ pub fn create_ast_element(token: Token, kind: ASTElementKind) -> ASTElement {
         has_bindings: false,
         props: vec![],
         directives: None,
+        model: None,
         events: None,
         native_events: None,
         tokens: None,
@@ -222,6 +450,9 @@ impl ASTTree {
                     data: "".into(),
                     attrs: None,
                     is_implied: false,
+                    start: None,
+                    end: None,
+                    raw_attrs: vec![],
                 },
                 ASTElementKind::Root,
             ),
@@ -240,7 +471,7 @@ impl ASTTree {
 
         tree.nodes.insert(0, Rc::clone(&node));
 
-        return tree;
+        tree
     }
 
     pub fn create(
@@ -254,7 +485,7 @@ impl ASTTree {
         self.counter.set(new_id);
         let parent = self.get(parent_id).cloned().unwrap();
 
-        let new_node = Rc::new(RefCell::new(ASTNode {
+        Rc::new(RefCell::new(ASTNode {
             id: new_id,
             el: element,
             parent: Some(Rc::downgrade(&parent)),
@@ -262,9 +493,7 @@ impl ASTTree {
             children: vec![],
             is_dev,
             warn,
-        }));
-
-        new_node
+        }))
     }
 
     pub fn get(&self, id: usize) -> Option<&Rc<RefCell<ASTNode>>> {
@@ -274,6 +503,31 @@ impl ASTTree {
     pub fn set(&mut self, id: usize, node: Rc<RefCell<ASTNode>>) {
         self.nodes.insert(id, node);
     }
+
+    /// All nodes of the tree ordered by id, the root wrapper included.
+    pub fn nodes(&self) -> Vec<&Rc<RefCell<ASTNode>>> {
+        let mut nodes: Vec<_> = self.nodes.iter().collect();
+        nodes.sort_by_key(|(id, _)| **id);
+
+        nodes.into_iter().map(|(_, node)| node).collect()
+    }
+
+    /// Adds the attribute of scoped styles, e.g. `data-v-7ba5bd90`, to every rendered element.
+    pub fn add_scope_id(&self, scope_id: &str) {
+        for node_rc in self.nodes.values() {
+            let mut node = node_rc.borrow_mut();
+            let tag = &node.el.token.data;
+
+            if node.el.kind != ASTElementKind::Element
+                || tag.eq_ignore_ascii_case("template")
+                || tag.eq_ignore_ascii_case("slot")
+            {
+                continue;
+            }
+
+            node.insert_into_attrs(scope_id, None, QuoteType::NoValue, false, true);
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -285,6 +539,33 @@ pub struct ForParseResult {
 }
 
 impl ASTNode {
+    /// Fills in the element tag and the source range of the offending attribute,
+    /// or of the whole element, unless the diagnostic already has them.
+    pub fn locate(&self, mut diagnostic: CompilerDiagnostic) -> CompilerDiagnostic {
+        if diagnostic.tag.is_none() && self.el.kind == ASTElementKind::Element {
+            diagnostic.tag = Some(self.el.token.data.to_string());
+        }
+        if diagnostic.start.is_none() {
+            let raw_attr = diagnostic
+                .attr
+                .as_ref()
+                .and_then(|attr| self.el.raw_attrs_map.get(attr.as_str()));
+
+            if let Some(raw_attr) = raw_attr {
+                diagnostic = diagnostic.range(raw_attr.start, raw_attr.end);
+            } else if let Some(start) = self.el.start {
+                diagnostic = diagnostic.range(start, self.el.end.unwrap_or(start));
+            }
+        }
+
+        diagnostic
+    }
+
+    pub fn report(&mut self, diagnostic: CompilerDiagnostic) {
+        let diagnostic = self.locate(diagnostic);
+        self.warn.diagnostic(diagnostic);
+    }
+
     pub fn process_raw_attributes(&mut self) {
         // processing attributes should not be needed
         if self.el.token.attrs.is_none() {
@@ -294,32 +575,70 @@ impl ASTNode {
             return;
         }
 
-        let attrs_opt = &self.el.token.attrs;
-        let mut attributes: Vec<(String, Option<String>, QuoteType)> = vec![];
+        let attributes = self.el.token.attrs.clone().unwrap_or_default();
+        if attributes.is_empty() {
+            // non root node in pre blocks with no attributes
+            self.el.plain = true;
 
-        if let Some(attrs) = attrs_opt {
-            if attrs.is_empty() {
-                // non root node in pre blocks with no attributes
-                self.el.plain = true;
+            return;
+        }
 
-                return;
-            }
+        for (attr_name, attr_value_quote) in attributes.iter() {
+            let (attr_value, attr_quotes) = match attr_value_quote {
+                Some((value, quote_type)) => (Some(value.to_string()), *quote_type),
+                None => (None, QuoteType::NoValue),
+            };
+            self.insert_into_attrs(attr_name, attr_value, attr_quotes, false, true);
+        }
+    }
 
-            for (attr_name, attr_value_quote) in attrs.iter() {
-                attributes.push(if let Some(attr_entry) = attr_value_quote {
-                    (
-                        attr_name.to_string(),
-                        Some(attr_entry.0.to_string()),
-                        attr_entry.1,
-                    )
-                } else {
-                    (attr_name.to_string(), None, QuoteType::NoValue)
-                });
+    /// Reports `raw_attr` if it was already written on the element, either with the exact same
+    /// name or as another spelling of the same binding, e.g. `:foo` and `v-bind:foo`.
+    pub fn check_duplicate_attr(&mut self, raw_attr: &RawAttr, v_bind_prop_short_hand: bool) {
+        let binding = binding_name(&raw_attr.name, v_bind_prop_short_hand);
+        let duplicate = self.el.raw_attrs_map.values().find(|existing| {
+            existing.name == raw_attr.name
+                || binding.is_some()
+                    && binding_name(&existing.name, v_bind_prop_short_hand) == binding
+        });
+
+        if let Some(duplicate) = duplicate {
+            let diagnostic = CompilerDiagnostic::new(DiagnosticCode::DuplicateAttribute)
+                .arg(raw_attr.name.as_str())
+                .attr(raw_attr.name.as_str())
+                .range(raw_attr.start, raw_attr.end)
+                .related(duplicate.start, duplicate.end);
+            self.report(diagnostic);
+        }
+    }
+
+    /// Warns about attributes differing only in case, e.g. `:fooBar` and `:foobar`. Browsers
+    /// lowercase attribute names of in-DOM templates, so one of them is likely a mismatch.
+    pub fn check_case_insensitive_attrs(&mut self) {
+        let mut diagnostics = vec![];
+        for (index, raw_attr) in self.el.raw_attrs_map.values().enumerate() {
+            let earlier = self
+                .el
+                .raw_attrs_map
+                .values()
+                .take(index)
+                .find(|earlier| earlier.name.eq_ignore_ascii_case(&raw_attr.name));
+
+            if let Some(earlier) = earlier {
+                diagnostics.push(
+                    CompilerDiagnostic::new(DiagnosticCode::AttributeCaseMismatch)
+                        .arg(raw_attr.name.as_str())
+                        .arg(self.el.token.data.as_ref())
+                        .arg(earlier.name.as_str())
+                        .arg(to_hyphen_case(&earlier.name))
+                        .attr(raw_attr.name.as_str())
+                        .range(raw_attr.start, raw_attr.end),
+                );
             }
         }
 
-        for (attr_name, attr_value, attr_quotes) in attributes {
-            self.insert_into_attrs(&attr_name, attr_value, attr_quotes, false);
+        for diagnostic in diagnostics {
+            self.report(diagnostic);
         }
     }
 
@@ -339,7 +658,9 @@ impl ASTNode {
                 }
             }
 
-            self.warn.call("Invalid v-for expression");
+            self.report(
+                CompilerDiagnostic::new(DiagnosticCode::InvalidVForExpression).attr("v-for"),
+            );
         }
     }
 
@@ -389,7 +710,9 @@ impl ASTNode {
                     block_id: self.id,
                 });
             } else {
-                self.warn.call("Missing v-if expression.");
+                self.report(
+                    CompilerDiagnostic::new(DiagnosticCode::MissingVIfExpression).attr("v-if"),
+                );
             }
         } else {
             let v_else_optional = self.get_and_remove_attr(&UC_V_ELSE, false);
@@ -406,7 +729,10 @@ impl ASTNode {
                 if let Some(v_else_if_value) = v_else_if_val.value {
                     self.el.else_if_val = Some(v_else_if_value);
                 } else {
-                    self.warn.call("Missing v-else-if expression.");
+                    self.report(
+                        CompilerDiagnostic::new(DiagnosticCode::MissingVElseIfExpression)
+                            .attr("v-else-if"),
+                    );
                 }
             }
         }
@@ -422,15 +748,20 @@ impl ASTNode {
 
     pub fn get_and_remove_attr_by_regex(&mut self, regex: &Regex) -> Option<AttrEntry> {
         if let Some(attributes) = &self.el.token.attrs {
-            for (attr_name, attr_value) in attributes {
+            for (attr_name, attr_value) in attributes.iter() {
                 if regex.is_match(attr_name) {
-                    self.el.ignored.insert(attr_name.clone());
+                    self.el.ignored.insert(attr_name.to_string());
+
+                    let raw_attr = self.el.raw_attrs_map.get(attr_name);
+                    let (start, end) = (raw_attr.map(|a| a.start), raw_attr.map(|a| a.end));
 
                     if let Some((attr_value, _attr_quote)) = attr_value {
                         return Some(AttrEntry {
                             name: attr_name.to_string(),
                             value: Some(attr_value.to_string()),
                             quote_type: *_attr_quote,
+                            start,
+                            end,
                         });
                     }
 
@@ -438,12 +769,14 @@ impl ASTNode {
                         name: attr_name.to_string(),
                         value: None,
                         quote_type: QuoteType::NoValue,
+                        start,
+                        end,
                     });
                 }
             }
         }
 
-        return None;
+        None
     }
 
     pub fn has_raw_attr(&self, name: &str) -> bool {
@@ -451,33 +784,36 @@ impl ASTNode {
             return attrs.contains_key(name);
         }
 
-        return false;
+        false
     }
 
-    pub fn get_raw_attr_value(&self, name: &str) -> Option<&Box<str>> {
+    pub fn get_raw_attr_value(&self, name: &str) -> Option<&str> {
         if let Some(ref attrs) = self.el.token.attrs {
-            if let Some(attr_value) = attrs.get(name) {
-                if let Some((attr_value, _attr_quote)) = attr_value {
-                    return Some(attr_value);
-                }
+            if let Some(Some((attr_value, _attr_quote))) = attrs.get(name) {
+                return Some(attr_value);
             }
         }
 
-        return None;
+        None
     }
 
     pub fn get_and_remove_attr(&mut self, name: &str, fully_remove: bool) -> Option<AttrEntry> {
         if let Some(ref mut attrs) = self.el.token.attrs {
-            if let Some(attr_value) = attrs.get(name) {
+            if let Some((attr_name, attr_value)) = attrs.get_key_value(name) {
                 if !fully_remove {
-                    self.el.ignored.insert(name);
+                    self.el.ignored.insert(attr_name.to_string());
                 }
 
+                let raw_attr = self.el.raw_attrs_map.get(name);
+                let (start, end) = (raw_attr.map(|a| a.start), raw_attr.map(|a| a.end));
+
                 if let Some((attr_value, _attr_quote)) = attr_value {
                     return Some(AttrEntry {
                         name: name.to_string(),
                         value: Some(attr_value.to_string()),
                         quote_type: *_attr_quote,
+                        start,
+                        end,
                     });
                 }
 
@@ -485,11 +821,13 @@ impl ASTNode {
                     name: name.to_string(),
                     value: None,
                     quote_type: QuoteType::NoValue,
+                    start,
+                    end,
                 });
             }
         }
 
-        return None;
+        None
     }
 
     pub fn get_and_remove_attr_including_quotes(
@@ -498,16 +836,16 @@ impl ASTNode {
         fully_remove: bool,
     ) -> &Option<(Box<str>, QuoteType)> {
         if let Some(ref mut attrs) = self.el.token.attrs {
-            if let Some(attr_value_option) = attrs.get(name) {
+            if let Some((attr_name, attr_value_option)) = attrs.get_key_value(name) {
                 if !fully_remove {
-                    self.el.ignored.insert(name);
+                    self.el.ignored.insert(attr_name.to_string());
                 }
 
                 return attr_value_option;
             }
         }
 
-        return &None;
+        &None
     }
 
     pub fn get_binding_attr(&mut self, name: &'static str, get_static: bool) -> String {
@@ -519,10 +857,10 @@ impl ASTNode {
                 self.get_and_remove_attr_including_quotes(&("v-bind:".to_string() + name), false);
         }
         if let Some(found_dynamic_value) = dynamic_value {
-            return parse_filters(&found_dynamic_value);
+            return parse_filters(&found_dynamic_value.0);
         }
         if get_static {
-            let static_value = self.get_and_remove_attr(&name, false);
+            let static_value = self.get_and_remove_attr(name, false);
             if let Some(found_static_value) = static_value {
                 if let Some(value) = found_static_value.value {
                     return value.to_string();
@@ -532,10 +870,10 @@ impl ASTNode {
             }
         }
 
-        return String::new();
+        String::new()
     }
 
-    pub fn get_raw_binding_attr(&self, name: &'static str) -> Option<&Box<str>> {
+    pub fn get_raw_binding_attr(&self, name: &'static str) -> Option<&str> {
         let mut val = self.get_raw_attr_value(&(":".to_string() + name));
 
         if val.is_some() {
@@ -548,7 +886,15 @@ impl ASTNode {
             return val;
         }
 
-        return self.get_raw_attr_value(&name);
+        self.get_raw_attr_value(name)
+    }
+
+    /// Whether `name` was given as a plain attribute instead of a `:name` binding,
+    /// in which case values read through `get_binding_attr` are raw text.
+    pub fn is_literal_binding(&self, name: &str) -> bool {
+        !self.has_raw_attr(&format!(":{}", name))
+            && !self.has_raw_attr(&format!("v-bind:{}", name))
+            && self.get_raw_attr_value(name).is_some()
     }
 
     pub fn add_if_condition(&mut self, if_condition: IfCondition) {
@@ -564,7 +910,7 @@ impl ASTNode {
         self_ptr: &Rc<RefCell<ASTNode>>,
         children: &'a mut Vec<Rc<RefCell<ASTNode>>>,
     ) -> Option<&'a Rc<RefCell<ASTNode>>> {
-        if children.len() == 0 {
+        if children.is_empty() {
             return None;
         }
 
@@ -583,16 +929,16 @@ impl ASTNode {
 
             // do not warn about single whitespace text nodes
             if is_dev && children[i].borrow().el.token.data.as_ref() != " " {
-                self.warn.call(&format!(
-                    "text \"{}\" between v-if and v-else(-if) will be ignored.",
-                    &children[i].borrow().el.token.data.trim()
-                ));
+                self.report(
+                    CompilerDiagnostic::new(DiagnosticCode::TextBetweenIfElse)
+                        .arg(children[i].borrow().el.token.data.trim()),
+                );
             }
 
             children.remove(i);
         }
 
-        return None;
+        None
     }
 
     pub fn process_if_conditions(
@@ -609,14 +955,17 @@ impl ASTNode {
                 });
             }
         } else if self.is_dev {
-            self.warn.call(&format!(
-                "v-{} used on element <{}> without corresponding v-if.",
-                match &self.el.else_if_val {
-                    Some(else_if_val) => format!("else-if=\"{}\"", else_if_val),
-                    None => "else".to_string(),
-                },
-                self.el.token.data
-            ));
+            let (directive, attr) = match &self.el.else_if_val {
+                Some(else_if_val) => (format!("else-if=\"{}\"", else_if_val), "v-else-if"),
+                None => ("else".to_string(), "v-else"),
+            };
+
+            self.report(
+                CompilerDiagnostic::new(DiagnosticCode::ElseWithoutIf)
+                    .arg(directive)
+                    .arg(self.el.token.data.as_ref())
+                    .attr(attr),
+            );
         }
     }
 
@@ -627,7 +976,12 @@ impl ASTNode {
         // removing structural attributes
         self.el.plain = self.el.key.is_none()
             && self.el.scoped_slots.is_none()
-            && self.el.token.attrs.is_none();
+            && self
+                .el
+                .token
+                .attrs
+                .as_ref()
+                .is_none_or(|attrs| attrs.keys().all(|name| self.el.ignored.contains(name)));
 
         self.process_ref();
         self.process_slot_content(tree, options);
@@ -636,11 +990,27 @@ impl ASTNode {
 
         if let Some(registered_modules) = &options.modules {
             for module in registered_modules.iter() {
-                module.transform_node(self, &options);
+                module.transform_node(self, options);
             }
         }
 
         self.process_attrs(options);
+        self.process_platform_directives();
+    }
+
+    // compile-time directives like v-model add their props and handlers to the element
+    fn process_platform_directives(&mut self) {
+        let Some(mut directives) = self.el.directives.take() else {
+            return;
+        };
+
+        for dir in directives.iter_mut() {
+            if let Some(transform) = get_platform_directive(&dir.name) {
+                dir.needs_runtime = transform(self, dir);
+            }
+        }
+
+        self.el.directives = Some(directives);
     }
 
     // handle <slot/> outlets
@@ -653,12 +1023,7 @@ impl ASTNode {
             }
 
             if self.is_dev && self.el.key.is_some() {
-                println!(
-                    "`key` does not work on <slot> because slots are abstract outlets \
-                and can possibly expand into multiple elements. \
-                Use the key on a wrapping element instead. {}",
-                    self.get_raw_binding_attr("key").unwrap_or(&"".into())
-                );
+                self.report(CompilerDiagnostic::new(DiagnosticCode::KeyOnSlot).attr("key"));
             }
         }
     }
@@ -683,10 +1048,10 @@ impl ASTNode {
                 if self.el.token.data.eq_ignore_ascii_case("template") {
                     let opt = self.get_raw_binding_attr(&UC_KEY);
 
-                    self.warn.call(
-                        &format!("<template> cannot be keyed. Place the key on real elements instead. key was {}",
-                            if opt.is_some() { opt.as_deref().unwrap() } else { "" }
-                        ),
+                    self.report(
+                        CompilerDiagnostic::new(DiagnosticCode::KeyOnTemplate)
+                            .arg(opt.unwrap_or(""))
+                            .attr("key"),
                     );
                 }
 
@@ -695,22 +1060,22 @@ impl ASTNode {
                 let has_iterator_2 =
                     self.el.iterator2.is_some() && self.el.iterator2.as_ref().unwrap().eq(&exp);
 
-                if self.el.for_value.is_some() {
-                    if has_iterator_1 || has_iterator_2 {
-                        {
-                            if let Some(parent) = self.parent.as_ref().unwrap().upgrade() {
-                                if parent
-                                    .borrow()
-                                    .el
-                                    .token
-                                    .data
-                                    .eq_ignore_ascii_case("transition-group")
-                                {
-                                    // getRawBindingAttr(el, 'key'),
-                                    self.warn.call(
-                                        "Do not use v-for index as key on <transition-group> children,\nthis is the same as not using keys.",
-                                    );
-                                }
+                if self.el.for_value.is_some() && (has_iterator_1 || has_iterator_2) {
+                    {
+                        if let Some(parent) = self.parent.as_ref().unwrap().upgrade() {
+                            if parent
+                                .borrow()
+                                .el
+                                .token
+                                .data
+                                .eq_ignore_ascii_case("transition-group")
+                            {
+                                self.report(
+                                    CompilerDiagnostic::new(
+                                        DiagnosticCode::IndexAsKeyInTransitionGroup,
+                                    )
+                                    .attr("key"),
+                                );
                             }
                         }
                     }
@@ -720,13 +1085,11 @@ impl ASTNode {
         }
     }
     fn process_ref(&mut self) {
-        let ref_option = self.get_and_remove_attr("ref", false);
+        let ref_value = self.get_binding_attr("ref", true);
 
-        if let Some(ref_entry) = ref_option {
-            if let Some(ref_value) = ref_entry.value {
-                self.el.ref_val = Some(ref_value);
-                self.el.ref_in_for = self.check_in_for();
-            }
+        if !ref_value.is_empty() {
+            self.el.ref_val = Some(ref_value);
+            self.el.ref_in_for = self.check_in_for();
         }
     }
 
@@ -739,7 +1102,10 @@ impl ASTNode {
 
             if let Some(slot_scope_val) = slot_scope {
                 if is_dev {
-                    self.warn.call("the \"scope\" attribute for scoped slots have been deprecated and replaced by \"slot-scope\" since 2.5. The new \"slot-scope\" attribute can also be used on plain elements in addition to <template> to denote scoped slots.");
+                    self.report(
+                        CompilerDiagnostic::new(DiagnosticCode::DeprecatedScopeAttribute)
+                            .attr("scope"),
+                    );
                 }
 
                 slot_scope_entry_value = slot_scope_val.value;
@@ -759,32 +1125,39 @@ impl ASTNode {
                 self.el.slot_scope = slot_scope_entry.value;
 
                 if is_dev && self.has_raw_attr("v-for") {
-                    self.warn.call("Ambiguous combined usage of slot-scope and v-for on <{TODO}> (v-for takes higher priority). Use a wrapper <template> for the scoped slot to make it clearer.");
+                    self.report(
+                        CompilerDiagnostic::new(DiagnosticCode::AmbiguousSlotScopeAndVFor)
+                            .arg(self.el.token.data.as_ref())
+                            .attr("slot-scope"),
+                    );
                 }
             }
         }
 
         // slot="xxx"
-        let slot_target = self.get_and_remove_attr("slot", false);
-        if let Some(ref slot_target_entry) = slot_target {
-            self.el.slot_target = if let Some(slot_target_value) = &slot_target_entry.value {
-                Some(slot_target_value.to_string())
-            } else {
+        let slot_target = self.get_binding_attr("slot", true);
+        if !slot_target.is_empty() || self.has_raw_attr("slot") {
+            let is_literal = self.is_literal_binding("slot");
+            self.el.slot_target = if slot_target.is_empty() {
                 Some("\"default\"".to_string())
+            } else {
+                Some(slot_target)
             };
 
             self.el.slot_target_dynamic =
-                self.has_raw_attr("slot") || self.has_raw_attr("v-bind:slot");
+                self.has_raw_attr(":slot") || self.has_raw_attr("v-bind:slot");
             // preserve slot as an attribute for native shadow DOM compat
             // only for non-scoped slots.
-            if !self.el.token.data.eq_ignore_ascii_case("template") && !self.el.slot_scope.is_some()
+            if !self.el.token.data.eq_ignore_ascii_case("template") && self.el.slot_scope.is_none()
             {
-                self.insert_into_attrs(
-                    "slot",
-                    slot_target_entry.value.clone(),
-                    QuoteType::NoValue,
-                    false,
-                );
+                self.el.plain = false;
+                self.el.attrs.push(AttrItem {
+                    name: "slot".to_string(),
+                    value: self.el.slot_target.clone(),
+                    dynamic: false,
+                    literal: is_literal,
+                    quote_type: QuoteType::NoValue,
+                });
             }
         }
 
@@ -799,26 +1172,32 @@ impl ASTNode {
                         let slot_scope = self.el.slot_scope.clone();
 
                         if slot_target.is_some() || slot_scope.is_some() {
-                            self.warn.call("Unexpected mixed usage of different slot syntaxes. (slot-target, slot-scope)");
+                            self.report(
+                                CompilerDiagnostic::new(DiagnosticCode::MixedSlotSyntax)
+                                    .attr(slot_binding_attr.name.as_str()),
+                            );
                         }
                         if let Some(parent) = self
                             .parent
                             .as_ref()
                             .and_then(|parent_weak| parent_weak.upgrade())
                         {
-                            if parent.borrow().is_maybe_component() {
-                                self.warn.call("<template v-slot> can only appear at the root level inside the receiving component.");
+                            if !parent.borrow().is_maybe_component() {
+                                self.report(
+                                    CompilerDiagnostic::new(DiagnosticCode::TemplateVSlotNotAtRoot)
+                                        .attr(slot_binding_attr.name.as_str()),
+                                );
                             }
                         }
                     }
-                    let slot_name = get_slot_name(&slot_binding_attr);
+                    let slot_name = self.get_slot_name(&slot_binding_attr);
                     self.el.slot_target = Some(slot_name.name);
                     self.el.slot_target_dynamic = slot_name.dynamic;
-                    self.el.slot_scope = Some(if slot_binding_attr.value.is_some() {
-                        slot_binding_attr.value.unwrap()
-                    } else {
-                        EMPTY_SLOT_SCOPE_TOKEN.to_string()
-                    });
+                    self.el.slot_scope = Some(
+                        slot_binding_attr
+                            .value
+                            .unwrap_or_else(|| EMPTY_SLOT_SCOPE_TOKEN.to_string()),
+                    );
                 }
             } else {
                 let slot_binding = self.get_and_remove_attr_by_regex(&SLOT_RE);
@@ -826,24 +1205,27 @@ impl ASTNode {
                 if let Some(slot_binding_attr) = slot_binding {
                     if is_dev {
                         if !self.is_maybe_component() {
-                            self.warn
-                                .call("v-slot can only be used on components or <template>.")
+                            self.report(
+                                CompilerDiagnostic::new(DiagnosticCode::VSlotOnNonComponent)
+                                    .attr(slot_binding_attr.name.as_str()),
+                            )
                         }
                         if self.el.slot_scope.is_some() || self.el.slot_target.is_some() {
-                            self.warn.call("Unexpected mixed usage of different slot syntaxes. (slot-scope, slot)");
+                            self.report(
+                                CompilerDiagnostic::new(DiagnosticCode::MixedSlotSyntax)
+                                    .attr(slot_binding_attr.name.as_str()),
+                            );
                         }
                         if self.el.scoped_slots.is_some() {
-                            self.warn.call("To avoid scope ambiguity, the default slot should also use <template> syntax when there are other named slots.");
+                            self.report(
+                                CompilerDiagnostic::new(DiagnosticCode::DefaultSlotAmbiguity)
+                                    .attr(slot_binding_attr.name.as_str()),
+                            );
                         }
                     }
-                    let slots = if self.el.scoped_slots.is_some() {
-                        self.el.scoped_slots.as_mut().unwrap()
-                    } else {
-                        self.el.scoped_slots = Some(UniCaseBTreeMap::new());
-                        self.el.scoped_slots.as_mut().unwrap()
-                    };
-
-                    let slot_name = get_slot_name(&slot_binding_attr);
+                    let slot_name = self.get_slot_name(&slot_binding_attr);
+                    let slots = self.el.scoped_slots.get_or_insert_with(IndexMap::new);
+
                     let slot_container = tree.create(
                         create_ast_element(
                             Token {
@@ -851,6 +1233,9 @@ impl ASTNode {
                                 data: "template".into(),
                                 attrs: None,
                                 is_implied: false,
+                                start: None,
+                                end: None,
+                                raw_attrs: vec![],
                             },
                             ASTElementKind::Element,
                         ),
@@ -863,17 +1248,14 @@ impl ASTNode {
                     slot_container_node.el.slot_target = Some(slot_name.name.to_string());
                     slot_container_node.el.slot_target_dynamic = slot_name.dynamic;
 
-                    // Convert self to a Weak reference
-                    let parent = tree.get(self.id).cloned().unwrap();
-
                     slot_container_node.children = self
                         .children
                         .iter()
-                        .map(|child| Rc::clone(child))
+                        .map(Rc::clone)
                         .filter_map(|child_rc| {
                             let mut child = child_rc.borrow_mut();
                             if child.el.slot_scope.is_none() {
-                                child.parent = Some(Rc::downgrade(&parent));
+                                child.parent = Some(Rc::downgrade(&slot_container));
                                 Some(Rc::clone(&child_rc))
                             } else {
                                 None
@@ -881,8 +1263,8 @@ impl ASTNode {
                         })
                         .collect::<Vec<_>>();
                     slot_container_node.el.slot_scope =
-                        Some(if slot_binding_attr.value.is_some() {
-                            slot_binding_attr.value.unwrap().to_string()
+                        Some(if let Some(slot_scope) = slot_binding_attr.value {
+                            slot_scope
                         } else {
                             EMPTY_SLOT_SCOPE_TOKEN.to_string()
                         });
@@ -904,6 +1286,7 @@ impl ASTNode {
         value: Option<String>,
         quote_type: QuoteType,
         is_dynamic: bool,
+        is_literal: bool,
     ) {
         if self.el.ignored.contains(key) {
             return;
@@ -915,18 +1298,16 @@ impl ASTNode {
             name: key.to_string(),
             value: value.clone(),
             dynamic: is_dynamic,
+            literal: is_literal,
             quote_type,
         };
 
-        if value.is_some() {
+        if let Some(value) = value {
             self.el
                 .token
                 .attrs
-                .get_or_insert(UniCaseBTreeMap::new())
-                .insert(
-                    key.to_string(),
-                    Some((value.unwrap().into_boxed_str(), quote_type)),
-                );
+                .get_or_insert_with(TokenAttrs::new)
+                .insert(key, Some((value.into_boxed_str(), quote_type)));
         }
 
         if is_dynamic {
@@ -936,7 +1317,7 @@ impl ASTNode {
         }
     }
 
-    fn insert_into_props(
+    pub(crate) fn insert_into_props(
         &mut self,
         key: &str,
         value: Option<String>,
@@ -949,6 +1330,7 @@ impl ASTNode {
             name: key.to_string(),
             value,
             dynamic: is_dynamic,
+            literal: false,
             quote_type,
         };
         self.el.props.push(item);
@@ -978,22 +1360,49 @@ impl ASTNode {
         false
     }
 
+    fn get_slot_name(&mut self, binding: &AttrEntry) -> SlotName {
+        let mut name = SLOT_RE.replace_all(&binding.name, "").to_string();
+
+        if name.is_empty() {
+            if !binding.name.starts_with('#') {
+                name = "default".to_string();
+            } else if self.is_dev {
+                self.report(
+                    CompilerDiagnostic::new(DiagnosticCode::VSlotShorthandWithoutName)
+                        .attr(binding.name.as_str()),
+                );
+            }
+        }
+
+        if DYNAMIC_ARG_RE.is_match(&name) {
+            // dynamic [name]
+            SlotName {
+                name: name[1..name.len() - 1].to_string(),
+                dynamic: true,
+            }
+        } else {
+            // static name
+            SlotName {
+                name: format!("\"{}\"", name),
+                dynamic: false,
+            }
+        }
+    }
+
     pub fn check_for_alias_model(&mut self, val: &str) {
         if !self.is_dev {
             return;
         }
 
-        // TODO: Finish variables in warning
-        let warn_text = r#""<{el.tag} v-model="${value}">
-You are binding v-model directly to a v-for iteration alias.
-This will not be able to modify the v-for source array because
-writing to the alias is like modifying a function local variable.
-Consider using an array of objects and use v-model on an object property instead.""#;
+        let diagnostic = CompilerDiagnostic::new(DiagnosticCode::VModelOnForAlias)
+            .arg(self.el.token.data.as_ref())
+            .arg(val)
+            .attr("v-model");
 
         if self.el.for_value.is_some()
             && is_some_and_ref(&self.el.alias, |alias| alias.eq_ignore_ascii_case(val))
         {
-            self.warn.call(warn_text);
+            self.report(diagnostic);
             return;
         }
 
@@ -1008,7 +1417,7 @@ Consider using an array of objects and use v-model on an object property instead
                     alias.eq_ignore_ascii_case(val)
                 })
             {
-                self.warn.call(warn_text);
+                self.report(diagnostic.clone());
             }
             current_node = node
                 .borrow()
@@ -1018,29 +1427,34 @@ Consider using an array of objects and use v-model on an object property instead
         }
     }
 
-    // TODO: Finish this
+    /// Whether the element can resolve to a component: custom tags like `<my-button>`, dynamic
+    /// components and elements whose static `is` isn't a reserved tag, e.g. `<tr is="row">`.
     pub fn is_maybe_component(&self) -> bool {
-        self.el.component.is_some() || self.has_raw_attr("is") || self.has_raw_attr("v-bind:is")
-        // !(self.el.token.attrs.attrsMap.is ? isReservedTag(el.attrsMap.is) : isReservedTag(el.tag))
+        if self.el.component.is_some() || self.has_raw_attr(":is") || self.has_raw_attr("v-bind:is")
+        {
+            return true;
+        }
+
+        match self.get_raw_attr_value("is").filter(|is| !is.is_empty()) {
+            Some(is) => !is_reserved_tag(is),
+            None => !is_reserved_tag(&self.el.token.data),
+        }
     }
     pub fn process_attrs(&mut self, options: &CompilerOptions) {
-        if self.el.token.attrs.is_none() {
+        let Some(attrs) = self.el.token.attrs.clone() else {
             return;
-        }
+        };
 
-        // TODO: Get rid off this clone
-        let attrs = self.el.token.attrs.clone().unwrap();
-        for (orig_name, orig_val) in attrs.iter().rev() {
-            self.process_attr(&orig_name, &orig_val, options);
+        for (orig_name, orig_val) in attrs.iter() {
+            // attributes consumed by earlier processing steps
+            if self.el.ignored.contains(orig_name) {
+                continue;
+            }
+            self.process_attr(orig_name, orig_val, options);
         }
     }
 
-    pub fn process_attr(
-        &mut self,
-        name: &str,
-        value: &Option<(Box<str>, QuoteType)>,
-        options: &CompilerOptions,
-    ) {
+    pub fn process_attr(&mut self, name: &str, value: &TokenAttrValue, options: &CompilerOptions) {
         let mut name_str = name.to_string();
         let raw_name = name_str.clone();
         let mut value = value.clone();
@@ -1063,7 +1477,7 @@ Consider using an array of objects and use v-model on an object property instead
             // support .foo shorthand syntax for the .prop modifier
             if PROP_BIND_RE.is_match(&name_str) {
                 modifiers_option
-                    .get_or_insert(UniCaseBTreeSet::new())
+                    .get_or_insert(UniCaseIndexSet::new())
                     .insert("prop");
                 name_str = ".".to_string() + &*modifier_regex_replace_all_matches(&name_str[1..]);
             } else if modifiers_option.is_some() {
@@ -1074,7 +1488,7 @@ Consider using an array of objects and use v-model on an object property instead
                 // v-bind
                 name_str = BIND_RE.replace_all(&name_str, "").to_string();
                 if let Some(val) = value {
-                    value = Some((Box::from(parse_filters(&val)), val.1));
+                    value = Some((Box::from(parse_filters(&val.0)), val.1));
                 }
 
                 let is_dynamic = DYNAMIC_ARG_RE.is_match(&name_str);
@@ -1083,24 +1497,26 @@ Consider using an array of objects and use v-model on an object property instead
                 }
 
                 if self.is_dev && value.as_ref().is_some_and(|v| v.0.trim().is_empty()) {
-                    self.warn.call(&format!(
-                        "The value for a v-bind expression cannot be empty. Found in \"v-bind:{}\"",
-                        name_str
-                    ));
+                    self.report(
+                        CompilerDiagnostic::new(DiagnosticCode::EmptyVBindValue)
+                            .arg(name_str.as_str())
+                            .attr(name.to_string()),
+                    );
                 }
 
-                if let Some(ref modifiers) = modifiers_option.as_ref() {
-                    if modifiers.contains("prop") && !is_dynamic {
-                        if name_str.eq_ignore_ascii_case("innerHtml") {
-                            name_str = "innerHTML".to_string();
-                        }
+                if let Some(modifiers) = modifiers_option.as_ref() {
+                    if modifiers.contains("prop")
+                        && !is_dynamic
+                        && name_str.eq_ignore_ascii_case("innerHtml")
+                    {
+                        name_str = "innerHTML".to_string();
                     }
                     if modifiers.contains("camel") && !is_dynamic {
                         name_str = to_camel(&name_str);
                     }
                     if modifiers.contains("sync") {
-                        let sync_gen = if value.is_some() {
-                            gen_assignment_code(value.as_ref().unwrap(), "$event")
+                        let sync_gen = if let Some(value) = &value {
+                            gen_assignment_code(&value.0, "$event")
                         } else {
                             "".to_string()
                         };
@@ -1145,7 +1561,7 @@ Consider using an array of objects and use v-model on an object property instead
                 };
 
                 if (is_some_and_ref(&modifiers_option, |modifiers| modifiers.contains("prop")))
-                    || (self.el.component.is_none()
+                    || (!self.is_maybe_component()
                         && must_use_prop(
                             &self.el.token.data,
                             &get_attribute_value(&self.el.token, "type"),
@@ -1154,17 +1570,18 @@ Consider using an array of objects and use v-model on an object property instead
                 {
                     self.insert_into_props(&name_str, attr_value.0, attr_value.1, is_dynamic);
                 } else {
-                    self.insert_into_attrs(&name_str, attr_value.0, attr_value.1, is_dynamic);
+                    self.insert_into_attrs(
+                        &name_str,
+                        attr_value.0,
+                        attr_value.1,
+                        is_dynamic,
+                        false,
+                    );
                 }
             } else if ON_RE.is_match(&name_str) {
                 // v-on
-                let attr_value: Box<str>;
-                if let Some(val) = value {
-                    attr_value = val.0;
-                } else {
-                    self.warn.call("TODO: ignoring v-on without value");
-                    return;
-                }
+                // a v-on without value still registers an empty handler
+                let attr_value: Box<str> = value.map(|val| val.0).unwrap_or_default();
 
                 name_str = ON_RE.replace_all(&name_str, "").to_string();
                 let is_dynamic = DYNAMIC_ARG_RE.is_match(&name_str);
@@ -1173,19 +1590,7 @@ Consider using an array of objects and use v-model on an object property instead
                 }
                 self.add_handler(&name_str, &attr_value, modifiers_option, false, is_dynamic);
             } else {
-                let attr_value: Option<String>;
-                if let Some(val) = value {
-                    attr_value = Some(val.0.to_string());
-                    self.insert_into_attrs(
-                        &name_str,
-                        Some(attr_value.clone().unwrap().to_string()),
-                        val.1,
-                        false,
-                    );
-                } else {
-                    attr_value = None;
-                    self.insert_into_attrs(&name_str, None, QuoteType::NoValue, false);
-                }
+                let attr_value = value.map(|val| val.0.to_string());
 
                 // normal directives
                 name_str = dir_regex.replace_all(&name_str, "").to_string();
@@ -1195,7 +1600,7 @@ Consider using an array of objects and use v-model on an object property instead
                 let mut arg = arg_match.and_then(|cap| cap.get(1)).map(|m| m.as_str());
                 let mut is_dynamic = false;
                 if let Some(arg_val) = arg {
-                    name_str = (&name_str[..name_str.len() - arg_val.len() - 1]).to_string();
+                    name_str = name_str[..name_str.len() - arg_val.len() - 1].to_string();
                     if DYNAMIC_ARG_RE.is_match(arg_val) {
                         arg = Some(&arg_val[1..arg_val.len() - 1]);
                         is_dynamic = true;
@@ -1225,20 +1630,24 @@ Consider using an array of objects and use v-model on an object property instead
             // literal attribute
             if self.is_dev {
                 if let Some(attr_val) = &attr_entry_opt.0 {
-                    let res = parse_text(&attr_val, &options.delimiters);
+                    let res = parse_text(attr_val, &options.delimiters);
                     if res.is_some() {
-                        self.warn.call(
-                            &format!("{}=\"{}\": Interpolation inside attributes has been removed. Use v-bind or the colon shorthand instead. For example, instead of <div id=\"{{ val }}\">, use <div :id=\"val\">.", &name, &attr_val)
+                        self.report(
+                            CompilerDiagnostic::new(DiagnosticCode::InterpolationInAttribute)
+                                .arg(name.to_string())
+                                .arg(attr_val.as_str())
+                                .arg("id")
+                                .attr(name.to_string()),
                         );
                     }
                 }
             }
-            self.insert_into_attrs(&name_str, attr_entry_opt.0, attr_entry_opt.1, false);
+            self.insert_into_attrs(&name_str, attr_entry_opt.0, attr_entry_opt.1, false, true);
             // #6887 firefox doesn't update muted state if set via attribute
             // even immediately after element creation
-            if self.el.component.is_none() && name == "muted" {
+            if !self.is_maybe_component() && name == "muted" {
                 let attr_entry_opt = get_attribute_value(&self.el.token, "type");
-                if must_use_prop(&self.el.token.data, &attr_entry_opt, &name) {
+                if must_use_prop(&self.el.token.data, &attr_entry_opt, name) {
                     self.insert_into_props(
                         &name_str,
                         Some("true".to_string()),
@@ -1254,26 +1663,28 @@ Consider using an array of objects and use v-model on an object property instead
         &mut self,
         name: &str,
         value: &str,
-        modifiers: Option<UniCaseBTreeSet>,
+        modifiers: Option<UniCaseIndexSet>,
         important: bool,
         dynamic: bool,
     ) {
-        let mut modifiers = modifiers.unwrap_or(UniCaseBTreeSet::new());
+        let mut modifiers = modifiers;
         let mut name = name.to_string();
 
-        if cfg!(debug_assertions) {
-            if modifiers.get("prevent").is_some() && modifiers.get("passive").is_some() {
-                self.warn.call("passive and prevent can't be used together. Passive handler can't prevent default event.");
-            }
+        if self.is_dev
+            && modifiers.as_ref().is_some_and(|modifiers| {
+                modifiers.contains("prevent") && modifiers.contains("passive")
+            })
+        {
+            self.report(CompilerDiagnostic::new(DiagnosticCode::PassiveAndPrevent));
         }
 
-        if modifiers.remove("right") {
+        if remove_modifier(&mut modifiers, "right") {
             if dynamic {
                 name = format!("({})==='click'?'contextmenu':({})", name, name);
             } else if name == "click" {
                 name = "contextmenu".to_string();
             }
-        } else if modifiers.remove("middle") {
+        } else if remove_modifier(&mut modifiers, "middle") {
             if dynamic {
                 name = format!("({})==='click'?'mouseup':({})", name, name);
             } else if name == "click" {
@@ -1281,22 +1692,22 @@ Consider using an array of objects and use v-model on an object property instead
             }
         }
 
-        if modifiers.remove("capture") {
+        if remove_modifier(&mut modifiers, "capture") {
             name = prepend_modifier_marker('!', &name, dynamic);
         }
-        if modifiers.remove("once") {
+        if remove_modifier(&mut modifiers, "once") {
             name = prepend_modifier_marker('~', &name, dynamic);
         }
-        if modifiers.remove("passive") {
+        if remove_modifier(&mut modifiers, "passive") {
             name = prepend_modifier_marker('&', &name, dynamic);
         }
 
-        let events = (if modifiers.remove("native") {
+        let events = (if remove_modifier(&mut modifiers, "native") {
             &mut self.el.native_events
         } else {
             &mut self.el.events
         })
-        .get_or_insert(UniCaseBTreeMap::new());
+        .get_or_insert(UniCaseIndexMap::new());
 
         let new_handler = Handler {
             value: value.trim().to_string(),
@@ -1321,9 +1732,9 @@ Consider using an array of objects and use v-model on an object property instead
         value: Option<String>,
         arg: Option<&str>,
         is_dynamic_arg: bool,
-        modifiers: Option<UniCaseBTreeSet>,
+        modifiers: Option<UniCaseIndexSet>,
     ) {
-        let modifiers = modifiers.unwrap_or(UniCaseBTreeSet::new());
+        let modifiers = modifiers.unwrap_or_default();
 
         let directive = Directive {
             name: name.to_string(),
@@ -1332,6 +1743,7 @@ Consider using an array of objects and use v-model on an object property instead
             arg: arg.map(|arg| arg.to_string()),
             is_dynamic_arg,
             modifiers,
+            needs_runtime: true,
         };
 
         self.el.directives.get_or_insert(Vec::new()).push(directive);
@@ -1339,17 +1751,40 @@ Consider using an array of objects and use v-model on an object property instead
     }
 }
 
-fn parse_modifiers(name: &str) -> Option<UniCaseBTreeSet> {
-    let mut ret: Option<UniCaseBTreeSet> = None;
+// name bound by a `v-bind` attribute without its modifiers, `foo` for `:foo.sync`
+fn binding_name(name: &str, v_bind_prop_short_hand: bool) -> Option<String> {
+    let arg = name
+        .strip_prefix(':')
+        .or_else(|| name.strip_prefix("v-bind:"))
+        .or_else(|| name.strip_prefix('.').filter(|_| v_bind_prop_short_hand))?;
+
+    Some(modifier_regex_replace_all_matches(arg))
+}
+
+fn parse_modifiers(name: &str) -> Option<UniCaseIndexSet> {
+    let mut ret: Option<UniCaseIndexSet> = None;
     for cap in MODIFIER_RE.captures_iter(name) {
         let matched_string = &cap[0];
         if !matched_string.contains(']') {
-            ret.get_or_insert(UniCaseBTreeSet::new())
+            ret.get_or_insert(UniCaseIndexSet::new())
                 .insert(matched_string[1..].to_string());
         }
     }
 
-    return ret;
+    ret
+}
+
+// removes the modifier keeping the order of the others, they are generated in source order
+fn remove_modifier(modifiers: &mut Option<UniCaseIndexSet>, modifier: &str) -> bool {
+    let Some(modifiers) = modifiers else {
+        return false;
+    };
+
+    let found = modifiers.contains(modifier);
+    if found {
+        modifiers.retain(|other| !other.eq_ignore_ascii_case(modifier));
+    }
+    found
 }
 
 #[derive(Debug)]
@@ -1357,33 +1792,3 @@ pub struct SlotName {
     name: String,
     dynamic: bool,
 }
-
-pub fn get_slot_name(binding: &AttrEntry) -> SlotName {
-    let mut name = SLOT_RE.replace_all(&binding.name, "").to_string();
-
-    if name.is_empty() {
-        if !&binding.name.starts_with('#') {
-            name = "default".to_string();
-        } else {
-            // TODO: warn in debug only
-            println!(
-                "v-slot shorthand syntax requires a slot name: {:?}",
-                binding
-            );
-        }
-    }
-
-    if DYNAMIC_ARG_RE.is_match(&name) {
-        // dynamic [name]
-        SlotName {
-            name: name[1..name.len() - 1].to_string(),
-            dynamic: true,
-        }
-    } else {
-        // static name
-        SlotName {
-            name: format!("\"{}\"", name),
-            dynamic: false,
-        }
-    }
-}
@@ -0,0 +1,446 @@
+use crate::ast_tree::{
+    ASTElementKind, ASTNode, ASTTree, AttrItem, Directive, Handler, IfCondition, RawAttr,
+    SSROptimizability,
+};
+use crate::codegen::gen_attr_value;
+use serde::ser::{SerializeMap, SerializeSeq};
+use serde::{Serialize, Serializer};
+use std::cell::RefCell;
+use std::rc::Rc;
+use unicase_collections::unicase_index_set::UniCaseIndexSet;
+
+/**
+ * Serialization into the AST format of vue-template-compiler, e.g. `{"type":1,"tag":"div",
+ * "attrsList":[...],"attrsMap":{...},"children":[...]}`. Like `JSON.stringify` of vue's AST
+ * unset fields are left out, `parent` pointers are skipped, and so is the `block` of the first
+ * if condition which refers back to the element itself.
+ */
+impl Serialize for ASTTree {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.wrapper.borrow().children.first() {
+            Some(root) => self.serializable(root).serialize(serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+/// Serializable view of a node and its subtree, see `ASTTree::serializable`.
+pub struct SerializableNode<'a> {
+    tree: &'a ASTTree,
+    node: &'a Rc<RefCell<ASTNode>>,
+}
+
+impl ASTTree {
+    /// `node` in vue-template-compiler's AST format, the tree resolves `v-else(-if)` blocks.
+    pub fn serializable<'a>(&'a self, node: &'a Rc<RefCell<ASTNode>>) -> SerializableNode<'a> {
+        SerializableNode { tree: self, node }
+    }
+}
+
+impl Serialize for SerializableNode<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let node = self.node.borrow();
+        let el = &node.el;
+        let mut map = serializer.serialize_map(None)?;
+
+        match el.kind {
+            ASTElementKind::Element | ASTElementKind::Root => {}
+            ASTElementKind::Expression => {
+                map.serialize_entry("type", &2)?;
+                map.serialize_entry("expression", &el.expression)?;
+                if let Some(tokens) = &el.tokens {
+                    map.serialize_entry("tokens", &TextTokens(tokens))?;
+                }
+                map.serialize_entry("text", &el.token.data)?;
+                serialize_range(&mut map, el.start, el.end)?;
+                map.serialize_entry("static", &el.is_static)?;
+                return map.end();
+            }
+            ASTElementKind::Text => {
+                map.serialize_entry("type", &3)?;
+                map.serialize_entry("text", &el.token.data)?;
+                if el.is_comment {
+                    map.serialize_entry("isComment", &true)?;
+                }
+                serialize_range(&mut map, el.start, el.end)?;
+                map.serialize_entry("static", &el.is_static)?;
+                return map.end();
+            }
+        }
+
+        let raw_attrs: Vec<&RawAttr> = el.raw_attrs_map.values().collect();
+
+        map.serialize_entry("type", &1)?;
+        map.serialize_entry("tag", &el.token.data)?;
+        map.serialize_entry(
+            "attrsList",
+            &AttrsList(
+                raw_attrs
+                    .iter()
+                    .filter(|raw_attr| !el.ignored.contains(raw_attr.name.as_str()))
+                    .copied()
+                    .collect(),
+            ),
+        )?;
+        map.serialize_entry("attrsMap", &AttrsMap(&raw_attrs))?;
+        map.serialize_entry("rawAttrsMap", &RawAttrsMap(&raw_attrs))?;
+        map.serialize_entry(
+            "children",
+            &Nodes {
+                tree: self.tree,
+                nodes: &node.children,
+            },
+        )?;
+        serialize_range(&mut map, el.start, el.end)?;
+
+        if let Some(ns) = el.ns {
+            map.serialize_entry("ns", ns)?;
+        }
+        serialize_flag(&mut map, "forbidden", el.forbidden)?;
+        serialize_flag(&mut map, "pre", el.pre)?;
+        serialize_flag(&mut map, "plain", el.plain)?;
+
+        if let Some(for_value) = &el.for_value {
+            map.serialize_entry("for", for_value)?;
+            map.serialize_entry("alias", &el.alias)?;
+            serialize_option(&mut map, "iterator1", &el.iterator1)?;
+            serialize_option(&mut map, "iterator2", &el.iterator2)?;
+        }
+
+        serialize_option(&mut map, "if", &el.if_val)?;
+        serialize_option(&mut map, "elseif", &el.else_if_val)?;
+        serialize_flag(&mut map, "else", el.is_else)?;
+        if let Some(if_conditions) = &el.if_conditions {
+            map.serialize_entry(
+                "ifConditions",
+                &IfConditions {
+                    tree: self.tree,
+                    node_id: node.id,
+                    conditions: if_conditions,
+                },
+            )?;
+        }
+        serialize_flag(&mut map, "once", el.once)?;
+
+        serialize_option(&mut map, "key", &el.key)?;
+        serialize_option(&mut map, "ref", &el.ref_val)?;
+        serialize_flag(&mut map, "refInFor", el.ref_in_for)?;
+
+        serialize_option(&mut map, "slotName", &el.slot_name)?;
+        serialize_option(&mut map, "slotTarget", &el.slot_target)?;
+        serialize_flag(&mut map, "slotTargetDynamic", el.slot_target_dynamic)?;
+        serialize_option(&mut map, "slotScope", &el.slot_scope)?;
+        if let Some(scoped_slots) = &el.scoped_slots {
+            let mut slots = Vec::with_capacity(scoped_slots.len());
+            for (name, slot) in scoped_slots.iter() {
+                slots.push((name.as_str(), self.tree.serializable(slot)));
+            }
+            map.serialize_entry("scopedSlots", &Entries(slots))?;
+        }
+
+        serialize_option(&mut map, "component", &el.component)?;
+        serialize_flag(&mut map, "inlineTemplate", el.inline_template)?;
+
+        serialize_option(&mut map, "staticClass", &el.static_class)?;
+        serialize_option(&mut map, "classBinding", &el.class_binding)?;
+        serialize_option(&mut map, "staticStyle", &el.static_style)?;
+        serialize_option(&mut map, "styleBinding", &el.style_binding)?;
+
+        serialize_flag(&mut map, "hasBindings", el.has_bindings)?;
+        if !el.attrs.is_empty() {
+            map.serialize_entry("attrs", &Attrs(&el.attrs))?;
+        }
+        if !el.dynamic_attrs.is_empty() {
+            map.serialize_entry("dynamicAttrs", &Attrs(&el.dynamic_attrs))?;
+        }
+        if !el.props.is_empty() {
+            map.serialize_entry("props", &Attrs(&el.props))?;
+        }
+        if let Some(events) = &el.events {
+            let events: Vec<_> = events
+                .iter()
+                .map(|(name, handlers)| (name.as_str(), Handlers(handlers)))
+                .collect();
+            map.serialize_entry("events", &Entries(events))?;
+        }
+        if let Some(native_events) = &el.native_events {
+            let native_events: Vec<_> = native_events
+                .iter()
+                .map(|(name, handlers)| (name.as_str(), Handlers(handlers)))
+                .collect();
+            map.serialize_entry("nativeEvents", &Entries(native_events))?;
+        }
+        if let Some(directives) = &el.directives {
+            map.serialize_entry("directives", &Directives(directives))?;
+        }
+        if let Some(model) = &el.model {
+            map.serialize_entry(
+                "model",
+                &Entries(vec![
+                    ("value", model.value.as_str()),
+                    ("callback", model.callback.as_str()),
+                    ("expression", model.expression.as_str()),
+                ]),
+            )?;
+        }
+
+        map.serialize_entry("static", &el.is_static)?;
+        map.serialize_entry("staticRoot", &el.static_root)?;
+        if el.static_root {
+            map.serialize_entry("staticInFor", &el.static_in_for)?;
+        }
+        if let Some(ssr_optimizability) = el.ssr_optimizability {
+            // the numeric values of vue's `optimizability` enum
+            let value = match ssr_optimizability {
+                SSROptimizability::Unoptimizable => 0,
+                SSROptimizability::Full => 1,
+                SSROptimizability::SelfOnly => 2,
+                SSROptimizability::Children => 3,
+                SSROptimizability::Partial => 4,
+            };
+            map.serialize_entry("ssrOptimizability", &value)?;
+        }
+
+        map.end()
+    }
+}
+
+fn serialize_range<M: SerializeMap>(
+    map: &mut M,
+    start: Option<usize>,
+    end: Option<usize>,
+) -> Result<(), M::Error> {
+    serialize_option(map, "start", &start)?;
+    serialize_option(map, "end", &end)
+}
+
+fn serialize_option<M: SerializeMap, T: Serialize>(
+    map: &mut M,
+    key: &str,
+    value: &Option<T>,
+) -> Result<(), M::Error> {
+    match value {
+        Some(value) => map.serialize_entry(key, value),
+        None => Ok(()),
+    }
+}
+
+fn serialize_flag<M: SerializeMap>(map: &mut M, key: &str, value: bool) -> Result<(), M::Error> {
+    if value {
+        map.serialize_entry(key, &true)?;
+    }
+    Ok(())
+}
+
+// ordered key value pairs
+struct Entries<'a, V: Serialize>(Vec<(&'a str, V)>);
+
+impl<V: Serialize> Serialize for Entries<'_, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (key, value) in &self.0 {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+struct Nodes<'a> {
+    tree: &'a ASTTree,
+    nodes: &'a [Rc<RefCell<ASTNode>>],
+}
+
+impl Serialize for Nodes<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.nodes.len()))?;
+        for node in self.nodes {
+            seq.serialize_element(&self.tree.serializable(node))?;
+        }
+        seq.end()
+    }
+}
+
+struct IfConditions<'a> {
+    tree: &'a ASTTree,
+    node_id: usize,
+    conditions: &'a [IfCondition],
+}
+
+impl Serialize for IfConditions<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.conditions.len()))?;
+        for condition in self.conditions {
+            let block = self
+                .tree
+                .get(condition.block_id)
+                .filter(|_| condition.block_id != self.node_id);
+
+            seq.serialize_element(&IfConditionEntry {
+                exp: condition.exp.as_deref(),
+                block: block.map(|block| self.tree.serializable(block)),
+            })?;
+        }
+        seq.end()
+    }
+}
+
+struct IfConditionEntry<'a> {
+    exp: Option<&'a str>,
+    block: Option<SerializableNode<'a>>,
+}
+
+impl Serialize for IfConditionEntry<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        serialize_option(&mut map, "exp", &self.exp)?;
+        serialize_option(&mut map, "block", &self.block)?;
+        map.end()
+    }
+}
+
+struct AttrsList<'a>(Vec<&'a RawAttr>);
+
+impl Serialize for AttrsList<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter().map(|raw_attr| RawAttrEntry(raw_attr)))
+    }
+}
+
+// name to value, attributes without a value are empty strings
+struct AttrsMap<'a>(&'a [&'a RawAttr]);
+
+impl Serialize for AttrsMap<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            self.0
+                .iter()
+                .map(|raw_attr| (&raw_attr.name, raw_attr.value.as_deref().unwrap_or(""))),
+        )
+    }
+}
+
+struct RawAttrsMap<'a>(&'a [&'a RawAttr]);
+
+impl Serialize for RawAttrsMap<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            self.0
+                .iter()
+                .map(|raw_attr| (&raw_attr.name, RawAttrEntry(raw_attr))),
+        )
+    }
+}
+
+struct RawAttrEntry<'a>(&'a RawAttr);
+
+impl Serialize for RawAttrEntry<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(4))?;
+        map.serialize_entry("name", &self.0.name)?;
+        map.serialize_entry("value", self.0.value.as_deref().unwrap_or(""))?;
+        map.serialize_entry("start", &self.0.start)?;
+        map.serialize_entry("end", &self.0.end)?;
+        map.end()
+    }
+}
+
+// processed attributes, values are expressions like in the generated code
+struct Attrs<'a>(&'a [AttrItem]);
+
+impl Serialize for Attrs<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter().map(AttrEntry))
+    }
+}
+
+struct AttrEntry<'a>(&'a AttrItem);
+
+impl Serialize for AttrEntry<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("name", &self.0.name)?;
+        map.serialize_entry("value", &gen_attr_value(self.0))?;
+        // only bindings have the flag
+        if !self.0.literal {
+            map.serialize_entry("dynamic", &self.0.dynamic)?;
+        }
+        map.end()
+    }
+}
+
+// a single handler is serialized as is, several as an array
+struct Handlers<'a>(&'a [Handler]);
+
+impl Serialize for Handlers<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            [handler] => HandlerEntry(handler).serialize(serializer),
+            handlers => serializer.collect_seq(handlers.iter().map(HandlerEntry)),
+        }
+    }
+}
+
+struct HandlerEntry<'a>(&'a Handler);
+
+impl Serialize for HandlerEntry<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("value", &self.0.value)?;
+        map.serialize_entry("dynamic", &self.0.dynamic)?;
+        if let Some(modifiers) = &self.0.modifiers {
+            map.serialize_entry("modifiers", &Modifiers(modifiers))?;
+        }
+        map.end()
+    }
+}
+
+struct Directives<'a>(&'a [Directive]);
+
+impl Serialize for Directives<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter().map(DirectiveEntry))
+    }
+}
+
+struct DirectiveEntry<'a>(&'a Directive);
+
+impl Serialize for DirectiveEntry<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let directive = self.0;
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("name", &directive.name)?;
+        map.serialize_entry("rawName", &directive.raw_name)?;
+        map.serialize_entry("value", directive.value.as_deref().unwrap_or(""))?;
+        serialize_option(&mut map, "arg", &directive.arg)?;
+        map.serialize_entry("isDynamicArg", &directive.is_dynamic_arg)?;
+        if !directive.modifiers.is_empty() {
+            map.serialize_entry("modifiers", &Modifiers(&directive.modifiers))?;
+        }
+        map.end()
+    }
+}
+
+// `{"stop":true,"prevent":true}`
+struct Modifiers<'a>(&'a UniCaseIndexSet);
+
+impl Serialize for Modifiers<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|modifier| (modifier.as_str(), true)))
+    }
+}
+
+// text parts are strings, bindings `{"@binding":"msg"}`
+struct TextTokens<'a>(&'a [String]);
+
+impl Serialize for TextTokens<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for token in self.0 {
+            match token.strip_prefix("@binding: ") {
+                Some(binding) => seq.serialize_element(&Entries(vec![("@binding", binding)]))?,
+                None => seq.serialize_element(token)?,
+            }
+        }
+        seq.end()
+    }
+}
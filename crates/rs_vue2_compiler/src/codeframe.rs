@@ -0,0 +1,78 @@
+const RANGE: usize = 2;
+
+/**
+ * Renders the lines around `start..end` (byte offsets into `source`) with line numbers and
+ * carets under the given range, same layout as generateCodeFrame in vue.
+ * Columns are counted in characters and tabs in front of the range are kept in the
+ * underline so the carets stay aligned with the excerpt.
+ */
+pub fn generate_code_frame(source: &str, start: usize, end: usize) -> String {
+    let start = floor_char_boundary(source, start);
+    let end = floor_char_boundary(source, end).max(start);
+
+    // (start offset, line content without the line break)
+    let mut lines: Vec<(usize, &str)> = Vec::new();
+    let mut offset = 0;
+    for line in source.split('\n') {
+        lines.push((offset, line.strip_suffix('\r').unwrap_or(line)));
+        offset += line.len() + 1;
+    }
+
+    let line_of = |offset: usize| {
+        lines
+            .iter()
+            .rposition(|(line_start, _)| *line_start <= offset)
+            .unwrap_or(0)
+    };
+    let first = line_of(start);
+    // the range is exclusive, a range ending right after a line break stays on the previous line
+    let last = if end > start { line_of(end - 1) } else { first };
+
+    let from = first.saturating_sub(RANGE);
+    let to = (last + RANGE).min(lines.len() - 1);
+    let width = (to + 1).to_string().len().max(3);
+    let gutter = " ".repeat(width);
+
+    let mut res: Vec<String> = Vec::new();
+    for (index, (line_start, line)) in lines.iter().enumerate().take(to + 1).skip(from) {
+        res.push(format!("{:<width$}|  {}", index + 1, line));
+
+        if index < first || index > last {
+            continue;
+        }
+
+        let line_end = line_start + line.len();
+        let range_start = if index == first { start } else { *line_start };
+        let range_end = if index == last { end } else { line_end };
+        let range_start = range_start.clamp(*line_start, line_end) - line_start;
+        let range_end = range_end.clamp(*line_start, line_end) - line_start;
+
+        let mut length = line[range_start..range_end.max(range_start)]
+            .chars()
+            .count();
+        if length == 0 {
+            // point at the start of the range, other lines only get carets below actual content
+            if index != first {
+                continue;
+            }
+            length = 1;
+        }
+
+        let pad: String = line[..range_start]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        res.push(format!("{}|  {}{}", gutter, pad, "^".repeat(length)));
+    }
+
+    res.join("\n")
+}
+
+pub(crate) fn floor_char_boundary(source: &str, offset: usize) -> usize {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+
+    offset
+}
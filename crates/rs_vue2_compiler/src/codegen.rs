@@ -0,0 +1,1045 @@
+pub mod events;
+
+use crate::ast_tree::{
+    ASTElementKind, ASTNode, ASTTree, AttrItem, Directive, EMPTY_SLOT_SCOPE_TOKEN,
+};
+use crate::codegen::events::gen_handlers;
+use crate::diagnostics::{CompilerDiagnostic, DiagnosticCode};
+use crate::helpers::{to_camel, to_json_string};
+use crate::strip_with::strip_with;
+use crate::warn_logger::WarnLogger;
+use crate::web::element::is_reserved_tag;
+use crate::{print_line, CompilerOptions};
+use indexmap::IndexMap;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+#[derive(Debug)]
+pub struct CodegenResult {
+    pub render: String,
+    pub static_render_fns: Vec<String>,
+}
+
+// v-bind="obj" and v-on="obj" wrap the generated data object instead of
+// producing a runtime directive
+#[derive(Default)]
+struct DataWrappers {
+    wrap_data: Option<String>,
+    wrap_listeners: Option<String>,
+}
+
+pub(crate) type AltGen<'a> = fn(&mut CodegenState<'a>, &Rc<RefCell<ASTNode>>) -> String;
+
+pub(crate) struct CodegenState<'a> {
+    pub tree: &'a ASTTree,
+    pub options: &'a CompilerOptions,
+    pub warn: Box<dyn WarnLogger>,
+    pub pre: bool,
+    pub once_id: usize,
+    pub static_render_fns: Vec<String>,
+    // ids of the nodes already generated as static root, v-once, v-for and v-if
+    pub static_processed: HashSet<usize>,
+    pub once_processed: HashSet<usize>,
+    pub for_processed: HashSet<usize>,
+    pub if_processed: HashSet<usize>,
+}
+
+pub fn generate(tree: &ASTTree, options: &CompilerOptions) -> CodegenResult {
+    let warn = options.warn.clone().unwrap_or_else(|| Box::new(print_line));
+
+    generate_with_warn(tree, options, warn)
+}
+
+pub(crate) fn generate_with_warn(
+    tree: &ASTTree,
+    options: &CompilerOptions,
+    warn: Box<dyn WarnLogger>,
+) -> CodegenResult {
+    let root = tree.wrapper.borrow().children.first().cloned();
+
+    generate_from_node(tree, root.as_ref(), options, warn)
+}
+
+fn generate_from_node(
+    tree: &ASTTree,
+    root: Option<&Rc<RefCell<ASTNode>>>,
+    options: &CompilerOptions,
+    warn: Box<dyn WarnLogger>,
+) -> CodegenResult {
+    let mut state = CodegenState::new(tree, options, warn);
+
+    let code = match root {
+        Some(root) => {
+            if root.borrow().el.token.data.eq_ignore_ascii_case("script") {
+                "null".to_string()
+            } else {
+                state.gen_element(root)
+            }
+        }
+        None => "_c(\"div\")".to_string(),
+    };
+
+    CodegenResult {
+        render: state.gen_render_fn(&code),
+        static_render_fns: state.static_render_fns,
+    }
+}
+
+fn is_in_pre(node: &ASTNode) -> bool {
+    if node.el.pre {
+        return true;
+    }
+
+    let mut current_node = node
+        .parent
+        .as_ref()
+        .and_then(|parent_weak| parent_weak.upgrade());
+
+    while let Some(parent) = current_node {
+        if parent.borrow().el.pre {
+            return true;
+        }
+        current_node = parent
+            .borrow()
+            .parent
+            .as_ref()
+            .and_then(|parent_weak| parent_weak.upgrade());
+    }
+
+    false
+}
+
+fn needs_normalization(node: &ASTNode) -> bool {
+    node.el.for_value.is_some()
+        || node.el.token.data.eq_ignore_ascii_case("template")
+        || node.el.token.data.eq_ignore_ascii_case("slot")
+}
+
+fn transform_special_newlines(text: &str) -> String {
+    text.replace('\u{2028}', "\\u2028")
+        .replace('\u{2029}', "\\u2029")
+}
+
+impl<'a> CodegenState<'a> {
+    pub fn new(
+        tree: &'a ASTTree,
+        options: &'a CompilerOptions,
+        warn: Box<dyn WarnLogger>,
+    ) -> CodegenState<'a> {
+        CodegenState {
+            tree,
+            options,
+            warn,
+            pre: false,
+            once_id: 0,
+            static_render_fns: vec![],
+            static_processed: HashSet::new(),
+            once_processed: HashSet::new(),
+            for_processed: HashSet::new(),
+            if_processed: HashSet::new(),
+        }
+    }
+
+    pub fn gen_render_fn(&self, code: &str) -> String {
+        let render = format!("with(this){{return {}}}", code);
+
+        if self.options.strip_with {
+            strip_with(&render)
+        } else {
+            render
+        }
+    }
+
+    pub fn maybe_component(&self, node: &ASTNode) -> bool {
+        node.el.component.is_some() || !is_reserved_tag(&node.el.token.data)
+    }
+
+    pub fn gen_element(&mut self, node_rc: &Rc<RefCell<ASTNode>>) -> String {
+        let (gen_static, gen_once, gen_for, gen_if) = {
+            let node = node_rc.borrow();
+            (
+                node.el.static_root && !self.static_processed.contains(&node.id),
+                node.el.once && !self.once_processed.contains(&node.id),
+                node.el.for_value.is_some() && !self.for_processed.contains(&node.id),
+                node.el.if_val.is_some() && !self.if_processed.contains(&node.id),
+            )
+        };
+
+        if gen_static {
+            return self.gen_static(node_rc);
+        }
+        if gen_once {
+            return self.gen_once(node_rc);
+        }
+        if gen_for {
+            return self.gen_for(node_rc, None, None);
+        }
+        if gen_if {
+            return self.gen_if(node_rc, None, "_e()");
+        }
+
+        let node = node_rc.borrow();
+        let tag = &node.el.token.data;
+
+        if tag.eq_ignore_ascii_case("template") && node.el.slot_target.is_none() && !self.pre {
+            return self
+                .gen_children(&node, false)
+                .unwrap_or_else(|| "void 0".to_string());
+        }
+        if tag.eq_ignore_ascii_case("slot") {
+            return self.gen_slot(&node);
+        }
+
+        if node.el.component.is_some() {
+            drop(node);
+            return self.gen_component(node_rc);
+        }
+
+        let tag = tag.to_string();
+        let needs_data = !node.el.plain || (is_in_pre(&node) && self.maybe_component(&node));
+        drop(node);
+
+        let data = if needs_data {
+            Some(self.gen_data(node_rc))
+        } else {
+            None
+        };
+        let node = node_rc.borrow();
+        let children = if node.el.inline_template {
+            None
+        } else {
+            self.gen_children(&node, true)
+        };
+
+        format!(
+            "_c('{}'{}{})",
+            tag,
+            data.map(|data| format!(",{}", data)).unwrap_or_default(),
+            children
+                .map(|children| format!(",{}", children))
+                .unwrap_or_default()
+        )
+    }
+
+    // hoist static sub-trees out
+    fn gen_static(&mut self, node_rc: &Rc<RefCell<ASTNode>>) -> String {
+        let (pre, static_in_for) = {
+            let node = node_rc.borrow();
+            self.static_processed.insert(node.id);
+            (is_in_pre(&node), node.el.static_in_for)
+        };
+
+        // Some elements (templates) need to behave differently inside of a v-pre
+        // node.  All pre nodes are static roots, so we can use this as a location to
+        // wrap a state change and reset it upon exiting the pre node.
+        let original_pre_state = self.pre;
+        if pre {
+            self.pre = true;
+        }
+        let code = self.gen_element(node_rc);
+        let render = self.gen_render_fn(&code);
+        self.static_render_fns.push(render);
+        self.pre = original_pre_state;
+
+        format!(
+            "_m({}{})",
+            self.static_render_fns.len() - 1,
+            if static_in_for { ",true" } else { "" }
+        )
+    }
+
+    // v-once
+    fn gen_once(&mut self, node_rc: &Rc<RefCell<ASTNode>>) -> String {
+        let (gen_if, static_in_for) = {
+            let node = node_rc.borrow();
+            self.once_processed.insert(node.id);
+            (
+                node.el.if_val.is_some() && !self.if_processed.contains(&node.id),
+                node.el.static_in_for,
+            )
+        };
+
+        if gen_if {
+            return self.gen_if(node_rc, None, "_e()");
+        }
+        if !static_in_for {
+            return self.gen_static(node_rc);
+        }
+
+        let mut key = None;
+        let mut current_node = node_rc
+            .borrow()
+            .parent
+            .as_ref()
+            .and_then(|parent_weak| parent_weak.upgrade());
+        while let Some(parent) = current_node {
+            let parent_node = parent.borrow();
+            if parent_node.el.for_value.is_some() {
+                key = parent_node
+                    .el
+                    .key
+                    .as_ref()
+                    .map(|key| gen_binding_value(&parent_node, "key", key));
+                break;
+            }
+            drop(parent_node);
+            current_node = parent
+                .borrow()
+                .parent
+                .as_ref()
+                .and_then(|parent_weak| parent_weak.upgrade());
+        }
+
+        let Some(key) = key else {
+            if self.options.dev {
+                let node = node_rc.borrow();
+                self.warn.diagnostic(node.locate(
+                    CompilerDiagnostic::new(DiagnosticCode::VOnceWithoutKeyedFor).attr("v-once"),
+                ));
+            }
+            return self.gen_element(node_rc);
+        };
+
+        let code = self.gen_element(node_rc);
+        let once_id = self.once_id;
+        self.once_id += 1;
+
+        format!("_o({},{},{})", code, once_id, key)
+    }
+
+    pub fn gen_if(
+        &mut self,
+        node_rc: &Rc<RefCell<ASTNode>>,
+        alt_gen: Option<AltGen<'a>>,
+        alt_empty: &str,
+    ) -> String {
+        let conditions: Vec<(Option<String>, usize)> = {
+            let node = node_rc.borrow();
+            self.if_processed.insert(node.id);
+            node.el
+                .if_conditions
+                .iter()
+                .flatten()
+                .map(|condition| (condition.exp.clone(), condition.block_id))
+                .collect()
+        };
+
+        self.gen_if_conditions(&conditions, alt_gen, alt_empty)
+    }
+
+    fn gen_if_conditions(
+        &mut self,
+        conditions: &[(Option<String>, usize)],
+        alt_gen: Option<AltGen<'a>>,
+        alt_empty: &str,
+    ) -> String {
+        let Some(((exp, block_id), rest)) = conditions.split_first() else {
+            return alt_empty.to_string();
+        };
+
+        let block = match self.tree.get(*block_id) {
+            Some(block) => Rc::clone(block),
+            None => return self.gen_if_conditions(rest, alt_gen, alt_empty),
+        };
+        // v-if with v-once should generate code like (a)?_m(0):_m(1)
+        let block_code = if let Some(alt_gen) = alt_gen {
+            alt_gen(self, &block)
+        } else if block.borrow().el.once {
+            self.gen_once(&block)
+        } else {
+            self.gen_element(&block)
+        };
+
+        if let Some(exp) = exp {
+            format!(
+                "({})?{}:{}",
+                exp,
+                block_code,
+                self.gen_if_conditions(rest, alt_gen, alt_empty)
+            )
+        } else {
+            block_code
+        }
+    }
+
+    pub fn gen_for(
+        &mut self,
+        node_rc: &Rc<RefCell<ASTNode>>,
+        alt_gen: Option<AltGen<'a>>,
+        alt_helper: Option<&str>,
+    ) -> String {
+        let (exp, alias, iterator1, iterator2) = {
+            let node = node_rc.borrow();
+            self.for_processed.insert(node.id);
+            (
+                node.el.for_value.clone().unwrap_or_default(),
+                node.el.alias.clone().unwrap_or_default(),
+                node.el.iterator1.clone(),
+                node.el.iterator2.clone(),
+            )
+        };
+
+        {
+            let node = node_rc.borrow();
+            let tag = &node.el.token.data;
+            if self.options.dev
+                && self.maybe_component(&node)
+                && !tag.eq_ignore_ascii_case("slot")
+                && !tag.eq_ignore_ascii_case("template")
+                && node.el.key.is_none()
+            {
+                self.warn.diagnostic(
+                    node.locate(
+                        CompilerDiagnostic::new(DiagnosticCode::ComponentListWithoutKey)
+                            .arg(tag.as_ref())
+                            .arg(alias.as_str())
+                            .arg(exp.as_str())
+                            .attr("v-for"),
+                    ),
+                );
+            }
+        }
+
+        format!(
+            "{}(({}),function({}{}{}){{return {}}})",
+            alt_helper.unwrap_or("_l"),
+            exp,
+            alias,
+            iterator1.map(|it| format!(",{}", it)).unwrap_or_default(),
+            iterator2.map(|it| format!(",{}", it)).unwrap_or_default(),
+            match alt_gen {
+                Some(alt_gen) => alt_gen(self, node_rc),
+                None => self.gen_element(node_rc),
+            }
+        )
+    }
+
+    pub fn gen_data(&mut self, node_rc: &Rc<RefCell<ASTNode>>) -> String {
+        let mut data = String::from("{");
+        let mut wrappers = DataWrappers::default();
+
+        // directives first.
+        if let Some(dirs) = self.gen_directives(node_rc, &mut wrappers) {
+            data += &dirs;
+            data.push(',');
+        }
+
+        let node = &*node_rc.borrow();
+
+        if let Some(key) = &node.el.key {
+            data += &format!("key:{},", gen_binding_value(node, "key", key));
+        }
+        if let Some(ref_val) = &node.el.ref_val {
+            data += &format!("ref:{},", gen_binding_value(node, "ref", ref_val));
+        }
+        if node.el.ref_in_for {
+            data += "refInFor:true,";
+        }
+        if is_in_pre(node) {
+            data += "pre:true,";
+        }
+        // record original tag name for components using "is" attribute
+        if node.el.component.is_some() {
+            data += &format!("tag:\"{}\",", node.el.token.data);
+        }
+        // module data generation functions
+        if let Some(registered_modules) = &self.options.modules {
+            for module in registered_modules {
+                if let Some(module_data) = module.gen_data(node) {
+                    data += &module_data;
+                }
+            }
+        }
+        if !node.el.attrs.is_empty() {
+            data += &format!("attrs:{},", gen_props(&node.el.attrs));
+        }
+        if !node.el.props.is_empty() {
+            data += &format!("domProps:{},", gen_props(&node.el.props));
+        }
+        // event handlers
+        if let Some(events) = &node.el.events {
+            data += &format!(
+                "{},",
+                gen_handlers(events, false, self.options.key_codes.as_ref())
+            );
+        }
+        if let Some(native_events) = &node.el.native_events {
+            data += &format!(
+                "{},",
+                gen_handlers(native_events, true, self.options.key_codes.as_ref())
+            );
+        }
+        // only for non-scoped slots
+        if let Some(slot_target) = &node.el.slot_target {
+            if node.el.slot_scope.is_none() {
+                data += &format!("slot:{},", gen_binding_value(node, "slot", slot_target));
+            }
+        }
+        // scoped slots
+        if let Some(scoped_slots) = &node.el.scoped_slots {
+            data += &self.gen_scoped_slots(node, scoped_slots);
+            data.push(',');
+        }
+        // component v-model
+        if let Some(model) = &node.el.model {
+            data += &format!(
+                "model:{{value:{},callback:{},expression:{}}},",
+                model.value, model.callback, model.expression
+            );
+        }
+        // inline-template
+        if node.el.inline_template {
+            if let Some(inline_template) = self.gen_inline_template(node) {
+                data += &inline_template;
+                data.push(',');
+            }
+        }
+
+        if data.ends_with(',') {
+            data.pop();
+        }
+        data.push('}');
+
+        // v-bind dynamic argument wrap
+        // v-bind with dynamic arguments must be applied using the same v-bind object
+        // merge helper so that class/style/mustUseProp attrs are handled correctly.
+        if !node.el.dynamic_attrs.is_empty() {
+            data = format!(
+                "_b({},\"{}\",{})",
+                data,
+                node.el.token.data,
+                gen_props(&node.el.dynamic_attrs)
+            );
+        }
+        if let Some(wrap_data) = wrappers.wrap_data {
+            data = wrap_data.replacen("{data}", &data, 1);
+        }
+        if let Some(wrap_listeners) = wrappers.wrap_listeners {
+            data = wrap_listeners.replacen("{data}", &data, 1);
+        }
+
+        data
+    }
+
+    fn gen_directives(
+        &mut self,
+        node_rc: &Rc<RefCell<ASTNode>>,
+        wrappers: &mut DataWrappers,
+    ) -> Option<String> {
+        let node = node_rc.borrow();
+        let dirs = node.el.directives.as_ref()?;
+        let mut res = String::from("directives:[");
+        let mut has_runtime = false;
+
+        for dir in dirs {
+            let need_runtime = match dir.name.as_str() {
+                "bind" => {
+                    wrappers.wrap_data = Some(format!(
+                        "_b({{data}},'{}',{},{}{})",
+                        node.el.token.data,
+                        dir.value.as_deref().unwrap_or_default(),
+                        dir.modifiers.contains("prop"),
+                        if dir.modifiers.contains("sync") {
+                            ",true"
+                        } else {
+                            ""
+                        }
+                    ));
+                    false
+                }
+                "on" => {
+                    if self.options.dev && !dir.modifiers.is_empty() {
+                        self.warn.diagnostic(
+                            node.locate(
+                                CompilerDiagnostic::new(
+                                    DiagnosticCode::VOnWithoutArgumentModifiers,
+                                )
+                                .attr(dir.raw_name.as_str()),
+                            ),
+                        );
+                    }
+                    wrappers.wrap_listeners = Some(format!(
+                        "_g({{data}},{})",
+                        dir.value.as_deref().unwrap_or_default()
+                    ));
+                    false
+                }
+                "cloak" => false,
+                _ => dir.needs_runtime,
+            };
+
+            if need_runtime {
+                has_runtime = true;
+                res += &gen_directive(dir);
+                res.push(',');
+            }
+        }
+
+        if has_runtime {
+            res.pop();
+            res.push(']');
+            Some(res)
+        } else {
+            None
+        }
+    }
+
+    fn gen_scoped_slots(
+        &mut self,
+        node: &ASTNode,
+        slots: &IndexMap<String, Rc<RefCell<ASTNode>>>,
+    ) -> String {
+        // by default scoped slots are considered "stable", this allows child
+        // components with only scoped slots to skip forced updates from parent.
+        // but in some cases we have to bail-out of this optimization
+        // for example if the slot contains dynamic names, has v-if or v-for on them...
+        let mut needs_force_update = node.el.for_value.is_some()
+            || slots.iter().any(|(_, slot_rc)| {
+                let slot = slot_rc.borrow();
+                slot.el.slot_target_dynamic
+                    || slot.el.if_val.is_some()
+                    || slot.el.for_value.is_some()
+                    // is passing down slot from parent which may be dynamic
+                    || contains_slot_child(&slot)
+            });
+
+        // #9534: if a component with scoped slots is inside a conditional branch,
+        // it's possible for the same component to be reused but with different
+        // compiled slot content. To avoid that, we generate a unique key based on
+        // the generated code of all the slot contents.
+        let mut needs_key = node.el.if_val.is_some();
+
+        // OR when it is inside another scoped slot or v-for (the reactivity may be
+        // disconnected due to the intermediate scope variable)
+        // #9438, #9506
+        if !needs_force_update {
+            let mut current_node = node
+                .parent
+                .as_ref()
+                .and_then(|parent_weak| parent_weak.upgrade());
+
+            while let Some(parent_rc) = current_node {
+                let parent = parent_rc.borrow();
+                if parent
+                    .el
+                    .slot_scope
+                    .as_ref()
+                    .is_some_and(|slot_scope| slot_scope != EMPTY_SLOT_SCOPE_TOKEN)
+                    || parent.el.for_value.is_some()
+                {
+                    needs_force_update = true;
+                    break;
+                }
+                if parent.el.if_val.is_some() {
+                    needs_key = true;
+                }
+
+                current_node = parent
+                    .parent
+                    .as_ref()
+                    .and_then(|parent_weak| parent_weak.upgrade());
+            }
+        }
+
+        let generated_slots = slots
+            .iter()
+            .map(|(_, slot)| self.gen_scoped_slot(slot))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        format!(
+            "scopedSlots:_u([{}]{}{})",
+            generated_slots,
+            if needs_force_update { ",null,true" } else { "" },
+            if !needs_force_update && needs_key {
+                format!(",null,false,{}", hash(&generated_slots))
+            } else {
+                String::new()
+            }
+        )
+    }
+
+    fn gen_scoped_slot(&mut self, node_rc: &Rc<RefCell<ASTNode>>) -> String {
+        let (is_legacy_syntax, gen_if, gen_for) = {
+            let node = node_rc.borrow();
+            (
+                node.has_raw_attr("slot-scope"),
+                node.el.if_val.is_some() && !self.if_processed.contains(&node.id),
+                node.el.for_value.is_some() && !self.for_processed.contains(&node.id),
+            )
+        };
+
+        if gen_if && !is_legacy_syntax {
+            return self.gen_if(node_rc, Some(Self::gen_scoped_slot), "null");
+        }
+        if gen_for {
+            return self.gen_for(node_rc, Some(Self::gen_scoped_slot), None);
+        }
+
+        let node = node_rc.borrow();
+        let slot_scope = match node.el.slot_scope.as_deref() {
+            Some(EMPTY_SLOT_SCOPE_TOKEN) | None => "",
+            Some(slot_scope) => slot_scope,
+        }
+        .to_string();
+        let key = match &node.el.slot_target {
+            Some(slot_target) => gen_binding_value(&node, "slot", slot_target),
+            None => "\"default\"".to_string(),
+        };
+
+        let body = if node.el.token.data.eq_ignore_ascii_case("template") {
+            let children = self
+                .gen_children(&node, false)
+                .unwrap_or_else(|| "undefined".to_string());
+
+            match &node.el.if_val {
+                Some(if_val) if is_legacy_syntax => {
+                    format!("({})?{}:undefined", if_val, children)
+                }
+                _ => children,
+            }
+        } else {
+            drop(node);
+            self.gen_element(node_rc)
+        };
+
+        format!(
+            "{{key:{},fn:function({}){{return {}}}{}}}",
+            key,
+            slot_scope,
+            body,
+            // reverse proxy v-slot without scope on this.$slots
+            if slot_scope.is_empty() {
+                ",proxy:true"
+            } else {
+                ""
+            }
+        )
+    }
+
+    fn gen_inline_template(&mut self, node: &ASTNode) -> Option<String> {
+        let first_child = node.children.first();
+        let is_element =
+            first_child.is_some_and(|child| child.borrow().el.kind == ASTElementKind::Element);
+
+        if self.options.dev && (node.children.len() != 1 || !is_element) {
+            self.warn.diagnostic(
+                node.locate(
+                    CompilerDiagnostic::new(DiagnosticCode::InlineTemplateChildren)
+                        .attr("inline-template"),
+                ),
+            );
+        }
+
+        if !is_element {
+            return None;
+        }
+
+        let inline_render_fns =
+            generate_from_node(self.tree, first_child, self.options, self.warn.clone());
+
+        Some(format!(
+            "inlineTemplate:{{render:function(){{{}}},staticRenderFns:[{}]}}",
+            inline_render_fns.render,
+            inline_render_fns
+                .static_render_fns
+                .iter()
+                .map(|code| format!("function(){{{}}}", code))
+                .collect::<Vec<_>>()
+                .join(",")
+        ))
+    }
+
+    pub fn gen_children(&mut self, node: &ASTNode, check_skip: bool) -> Option<String> {
+        self.gen_children_with(node, check_skip, None, None)
+    }
+
+    pub fn gen_children_with(
+        &mut self,
+        node: &ASTNode,
+        check_skip: bool,
+        alt_gen_element: Option<AltGen<'a>>,
+        alt_gen_node: Option<AltGen<'a>>,
+    ) -> Option<String> {
+        let children = &node.children;
+        let first_child = children.first()?;
+
+        if children.len() == 1 {
+            let single_for = {
+                let child = first_child.borrow();
+                let tag = &child.el.token.data;
+
+                if child.el.for_value.is_some()
+                    && !tag.eq_ignore_ascii_case("template")
+                    && !tag.eq_ignore_ascii_case("slot")
+                {
+                    Some(if !check_skip {
+                        ""
+                    } else if self.maybe_component(&child) {
+                        ",1"
+                    } else {
+                        ",0"
+                    })
+                } else {
+                    None
+                }
+            };
+
+            // optimize single v-for
+            if let Some(normalization_type) = single_for {
+                let code = match alt_gen_element {
+                    Some(alt_gen_element) => alt_gen_element(self, first_child),
+                    None => self.gen_element(first_child),
+                };
+                return Some(format!("{}{}", code, normalization_type));
+            }
+        }
+
+        let normalization_type = if check_skip {
+            self.get_normalization_type(children)
+        } else {
+            0
+        };
+        let code = children
+            .iter()
+            .map(|child| match alt_gen_node {
+                Some(alt_gen_node) => alt_gen_node(self, child),
+                None => self.gen_node(child),
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+
+        Some(if normalization_type != 0 {
+            format!("[{}],{}", code, normalization_type)
+        } else {
+            format!("[{}]", code)
+        })
+    }
+
+    // determine the normalization needed for the children array.
+    // 0: no normalization needed
+    // 1: simple normalization needed (possible 1-level deep nested array)
+    // 2: full normalization needed
+    fn get_normalization_type(&self, children: &[Rc<RefCell<ASTNode>>]) -> u8 {
+        let mut res = 0;
+
+        for child_rc in children {
+            let child = child_rc.borrow();
+            if child.el.kind != ASTElementKind::Element {
+                continue;
+            }
+
+            if needs_normalization(&child) || self.some_if_block(&child, needs_normalization) {
+                return 2;
+            }
+            if self.maybe_component(&child)
+                || self.some_if_block(&child, |block| self.maybe_component(block))
+            {
+                res = 1;
+            }
+        }
+
+        res
+    }
+
+    fn some_if_block(&self, node: &ASTNode, predicate: impl Fn(&ASTNode) -> bool) -> bool {
+        node.el.if_conditions.iter().flatten().any(|condition| {
+            if condition.block_id == node.id {
+                return predicate(node);
+            }
+            self.tree
+                .get(condition.block_id)
+                .is_some_and(|block| predicate(&block.borrow()))
+        })
+    }
+
+    pub fn gen_node(&mut self, node_rc: &Rc<RefCell<ASTNode>>) -> String {
+        let (kind, is_comment) = {
+            let node = node_rc.borrow();
+            (node.el.kind, node.el.is_comment)
+        };
+
+        match kind {
+            ASTElementKind::Element | ASTElementKind::Root => self.gen_element(node_rc),
+            ASTElementKind::Text if is_comment => gen_comment(&node_rc.borrow()),
+            _ => gen_text(&node_rc.borrow()),
+        }
+    }
+
+    fn gen_slot(&mut self, node: &ASTNode) -> String {
+        let slot_name = match &node.el.slot_name {
+            Some(slot_name) => gen_binding_value(node, "name", slot_name),
+            None => "\"default\"".to_string(),
+        };
+        let children = self.gen_children(node, false);
+        let mut res = format!(
+            "_t({}{}",
+            slot_name,
+            children
+                .as_ref()
+                .map(|children| format!(",function(){{return {}}}", children))
+                .unwrap_or_default()
+        );
+
+        let attrs = if node.el.attrs.is_empty() && node.el.dynamic_attrs.is_empty() {
+            None
+        } else {
+            let props = node
+                .el
+                .attrs
+                .iter()
+                .chain(node.el.dynamic_attrs.iter())
+                .map(|attr| AttrItem {
+                    name: to_camel(&attr.name),
+                    ..attr.clone()
+                })
+                .collect::<Vec<_>>();
+
+            Some(gen_props(&props))
+        };
+        let bind = node.get_raw_attr_value("v-bind");
+
+        if (attrs.is_some() || bind.is_some()) && children.is_none() {
+            res += ",null";
+        }
+        if let Some(attrs) = &attrs {
+            res += &format!(",{}", attrs);
+        }
+        if let Some(bind) = bind {
+            res += &format!("{},{}", if attrs.is_some() { "" } else { ",null" }, bind);
+        }
+
+        res + ")"
+    }
+
+    fn gen_component(&mut self, node_rc: &Rc<RefCell<ASTNode>>) -> String {
+        let (component, children) = {
+            let node = node_rc.borrow();
+            let component = node.el.component.as_deref().unwrap_or_default();
+            let children = if node.el.inline_template {
+                None
+            } else {
+                self.gen_children(&node, true)
+            };
+
+            (gen_binding_value(&node, "is", component), children)
+        };
+
+        format!(
+            "_c({},{}{})",
+            component,
+            self.gen_data(node_rc),
+            children
+                .map(|children| format!(",{}", children))
+                .unwrap_or_default()
+        )
+    }
+}
+
+fn contains_slot_child(node: &ASTNode) -> bool {
+    if node.el.kind != ASTElementKind::Element {
+        return false;
+    }
+
+    node.el.token.data.eq_ignore_ascii_case("slot")
+        || node
+            .children
+            .iter()
+            .any(|child| contains_slot_child(&child.borrow()))
+}
+
+// djb2 string hash, same as the one used by vue-template-compiler
+fn hash(str: &str) -> u32 {
+    let mut hash: u32 = 5381;
+    for code in str.encode_utf16().collect::<Vec<_>>().into_iter().rev() {
+        hash = hash.wrapping_mul(33) ^ u32::from(code);
+    }
+
+    hash
+}
+
+pub fn gen_text(node: &ASTNode) -> String {
+    if node.el.kind == ASTElementKind::Expression {
+        if let Some(expression) = &node.el.expression {
+            return format!("_v({})", expression);
+        }
+    }
+
+    format!("_v({})", to_json_string(&node.el.token.data))
+}
+
+pub fn gen_comment(node: &ASTNode) -> String {
+    format!("_e({})", to_json_string(&node.el.token.data))
+}
+
+fn gen_directive(dir: &Directive) -> String {
+    let mut res = format!("{{name:\"{}\",rawName:\"{}\"", dir.name, dir.raw_name);
+
+    if let Some(value) = &dir.value {
+        res += &format!(",value:({}),expression:{}", value, to_json_string(value));
+    }
+    if let Some(arg) = &dir.arg {
+        if dir.is_dynamic_arg {
+            res += &format!(",arg:{}", arg);
+        } else {
+            res += &format!(",arg:\"{}\"", arg);
+        }
+    }
+    if !dir.modifiers.is_empty() {
+        res += &format!(
+            ",modifiers:{{{}}}",
+            dir.modifiers
+                .iter()
+                .map(|modifier| format!("{}:true", to_json_string(modifier)))
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+    }
+
+    res.push('}');
+    res
+}
+
+/// Values read through `get_binding_attr` are raw text for plain attributes,
+/// these have to be turned into string literals.
+fn gen_binding_value(node: &ASTNode, name: &str, value: &str) -> String {
+    if node.is_literal_binding(name) {
+        to_json_string(value)
+    } else {
+        value.to_string()
+    }
+}
+
+pub(crate) fn gen_attr_value(attr: &AttrItem) -> String {
+    match &attr.value {
+        Some(value) if attr.literal => to_json_string(value),
+        Some(value) => transform_special_newlines(value),
+        None => "\"\"".to_string(),
+    }
+}
+
+pub fn gen_props(props: &[AttrItem]) -> String {
+    let mut static_props = String::new();
+    let mut dynamic_props = String::new();
+
+    for prop in props {
+        let value = gen_attr_value(prop);
+
+        if prop.dynamic {
+            dynamic_props += &format!("{},{},", prop.name, value);
+        } else {
+            static_props += &format!("\"{}\":{},", prop.name, value);
+        }
+    }
+
+    static_props.pop();
+    let static_props = format!("{{{}}}", static_props);
+
+    if dynamic_props.is_empty() {
+        static_props
+    } else {
+        dynamic_props.pop();
+        format!("_d({},[{}])", static_props, dynamic_props)
+    }
+}
@@ -0,0 +1,238 @@
+use crate::ast_tree::Handler;
+use crate::helpers::to_json_string;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+use unicase_collections::unicase_index_map::UniCaseIndexMap;
+
+lazy_static! {
+    static ref FN_EXP_RE: Regex =
+        Regex::new(r"^([\w$_]+|\([^)]*?\))\s*=>|^function(?:\s+[\w$]+)?\s*\(").unwrap();
+    static ref FN_INVOKE_RE: Regex = Regex::new(r"\([^)]*?\);*$").unwrap();
+    static ref SIMPLE_PATH_RE: Regex = Regex::new(
+        r#"^[A-Za-z_$][\w$]*(?:\.[A-Za-z_$][\w$]*|\['[^']*?']|\["[^"]*?"]|\[\d+]|\[[A-Za-z_$][\w$]*])*$"#
+    )
+    .unwrap();
+}
+
+// KeyboardEvent.keyCode aliases
+fn built_in_key_code(key: &str) -> Option<&'static [u32]> {
+    match key {
+        "esc" => Some(&[27]),
+        "tab" => Some(&[9]),
+        "enter" => Some(&[13]),
+        "space" => Some(&[32]),
+        "up" => Some(&[38]),
+        "left" => Some(&[37]),
+        "right" => Some(&[39]),
+        "down" => Some(&[40]),
+        "delete" => Some(&[8, 46]),
+        _ => None,
+    }
+}
+
+// KeyboardEvent.key aliases
+fn built_in_key_name(key: &str) -> Option<&'static [&'static str]> {
+    match key {
+        // #7880: IE11 and Edge use `Esc` for Escape key name.
+        "esc" => Some(&["Esc", "Escape"]),
+        "tab" => Some(&["Tab"]),
+        "enter" => Some(&["Enter"]),
+        // #9112: IE11 uses `Spacebar` for Space key name.
+        "space" => Some(&[" ", "Spacebar"]),
+        // #7806: IE11 uses key names without `Arrow` prefix for arrow keys.
+        "up" => Some(&["Up", "ArrowUp"]),
+        "left" => Some(&["Left", "ArrowLeft"]),
+        "right" => Some(&["Right", "ArrowRight"]),
+        "down" => Some(&["Down", "ArrowDown"]),
+        // #9112: IE11 uses `Del` for Delete key name.
+        "delete" => Some(&["Backspace", "Delete", "Del"]),
+        _ => None,
+    }
+}
+
+// serialize a single value as a scalar and multiple values as an array, like JSON.stringify would
+fn stringify_alias<T: ToString>(values: Option<&[T]>) -> String {
+    match values {
+        None | Some([]) => "undefined".to_string(),
+        Some([value]) => value.to_string(),
+        Some(values) => format!(
+            "[{}]",
+            values
+                .iter()
+                .map(|value| value.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+    }
+}
+
+fn gen_guard(condition: &str) -> String {
+    format!("if({})return null;", condition)
+}
+
+fn modifier_code(modifier: &str) -> Option<String> {
+    match modifier {
+        "stop" => Some("$event.stopPropagation();".to_string()),
+        "prevent" => Some("$event.preventDefault();".to_string()),
+        "self" => Some(gen_guard("$event.target !== $event.currentTarget")),
+        "ctrl" => Some(gen_guard("!$event.ctrlKey")),
+        "shift" => Some(gen_guard("!$event.shiftKey")),
+        "alt" => Some(gen_guard("!$event.altKey")),
+        "meta" => Some(gen_guard("!$event.metaKey")),
+        "left" => Some(gen_guard("'button' in $event && $event.button !== 0")),
+        "middle" => Some(gen_guard("'button' in $event && $event.button !== 1")),
+        "right" => Some(gen_guard("'button' in $event && $event.button !== 2")),
+        _ => None,
+    }
+}
+
+pub fn gen_handlers(
+    events: &UniCaseIndexMap<Vec<Handler>>,
+    is_native: bool,
+    key_codes: Option<&HashMap<String, Vec<u32>>>,
+) -> String {
+    let prefix = if is_native { "nativeOn:" } else { "on:" };
+    let mut static_handlers = String::new();
+    let mut dynamic_handlers = String::new();
+
+    for (name, handlers) in events.iter() {
+        let handler_code = if handlers.len() == 1 {
+            gen_handler(&handlers[0], key_codes)
+        } else {
+            format!(
+                "[{}]",
+                handlers
+                    .iter()
+                    .map(|handler| gen_handler(handler, key_codes))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            )
+        };
+
+        if handlers.first().is_some_and(|handler| handler.dynamic) {
+            dynamic_handlers += &format!("{},{},", name, handler_code);
+        } else {
+            static_handlers += &format!("\"{}\":{},", name, handler_code);
+        }
+    }
+
+    static_handlers.pop();
+    let static_handlers = format!("{{{}}}", static_handlers);
+
+    if dynamic_handlers.is_empty() {
+        format!("{}{}", prefix, static_handlers)
+    } else {
+        dynamic_handlers.pop();
+        format!("{}_d({},[{}])", prefix, static_handlers, dynamic_handlers)
+    }
+}
+
+fn gen_handler(handler: &Handler, key_codes: Option<&HashMap<String, Vec<u32>>>) -> String {
+    let value = &handler.value;
+    let is_method_path = SIMPLE_PATH_RE.is_match(value);
+    let is_function_expression = FN_EXP_RE.is_match(value);
+    let is_function_invocation = SIMPLE_PATH_RE.is_match(&FN_INVOKE_RE.replace(value, ""));
+
+    let Some(modifiers) = &handler.modifiers else {
+        if is_method_path || is_function_expression {
+            return value.to_string();
+        }
+
+        // inline statement
+        return if is_function_invocation {
+            format!("function($event){{return {}}}", value)
+        } else {
+            format!("function($event){{{}}}", value)
+        };
+    };
+
+    let mut code = String::new();
+    let mut gen_modifier_code = String::new();
+    let mut keys = vec![];
+
+    for modifier in modifiers.iter() {
+        let modifier: &str = modifier;
+        if let Some(code) = modifier_code(modifier) {
+            gen_modifier_code += &code;
+            // left/right
+            if built_in_key_code(modifier).is_some() {
+                keys.push(modifier);
+            }
+        } else if modifier == "exact" {
+            gen_modifier_code += &gen_guard(
+                &["ctrl", "shift", "alt", "meta"]
+                    .iter()
+                    .filter(|key_modifier| !modifiers.contains(**key_modifier))
+                    .map(|key_modifier| format!("$event.{}Key", key_modifier))
+                    .collect::<Vec<_>>()
+                    .join("||"),
+            );
+        } else {
+            keys.push(modifier);
+        }
+    }
+
+    if !keys.is_empty() {
+        code += &gen_key_filter(&keys, key_codes);
+    }
+    // Make sure modifiers like prevent and stop get executed after key filtering
+    code += &gen_modifier_code;
+
+    let handler_code = if is_method_path {
+        format!("return {}.apply(null, arguments)", value)
+    } else if is_function_expression {
+        format!("return ({}).apply(null, arguments)", value)
+    } else if is_function_invocation {
+        format!("return {}", value)
+    } else {
+        value.to_string()
+    };
+
+    format!("function($event){{{}{}}}", code, handler_code)
+}
+
+fn gen_key_filter(keys: &[&str], key_codes: Option<&HashMap<String, Vec<u32>>>) -> String {
+    format!(
+        // make sure the key filters only apply to KeyboardEvents
+        // #9441: can't use 'keyCode' in $event because Chrome autofill fires fake
+        // key events that do not have keyCode property...
+        "if(!$event.type.indexOf('key')&&{})return null;",
+        keys.iter()
+            .map(|key| gen_filter_code(key, key_codes))
+            .collect::<Vec<_>>()
+            .join("&&")
+    )
+}
+
+fn gen_filter_code(key: &str, key_codes: Option<&HashMap<String, Vec<u32>>>) -> String {
+    // same as parseInt, numeric modifiers like .13 compare the key code directly
+    let digits: String = key.chars().take_while(|c| c.is_ascii_digit()).collect();
+    if let Ok(key_val) = digits.parse::<u32>() {
+        if key_val != 0 {
+            return format!("$event.keyCode!=={}", key_val);
+        }
+    }
+
+    let key_code = match key_codes.and_then(|key_codes| key_codes.get(key)) {
+        Some(user_key_code) => stringify_alias(Some(user_key_code.as_slice())),
+        None => stringify_alias(built_in_key_code(key)),
+    };
+    let key_name = match built_in_key_name(key) {
+        Some(names) => stringify_alias(Some(
+            names
+                .iter()
+                .map(|name| to_json_string(name))
+                .collect::<Vec<_>>()
+                .as_slice(),
+        )),
+        None => "undefined".to_string(),
+    };
+
+    format!(
+        "_k($event.keyCode,{},{},$event.key,{})",
+        to_json_string(key),
+        key_code,
+        key_name
+    )
+}
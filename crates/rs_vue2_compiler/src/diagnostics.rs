@@ -0,0 +1,266 @@
+use crate::codeframe::{floor_char_boundary, generate_code_frame};
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Severity {
+    Error,
+    Warn,
+    Tip,
+}
+
+/// Stable identifier for every diagnostic the compiler can emit.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DiagnosticCode {
+    // parser
+    InvalidDynamicArgument,
+    ForbiddenTag,
+    MultipleRootElements,
+    RootTextOnly,
+    TextOutsideRoot,
+    RootSlotOrTemplate,
+    RootVFor,
+    AttributeCaseMismatch,
+    DuplicateAttribute,
+    // directives
+    InvalidVForExpression,
+    MissingVIfExpression,
+    MissingVElseIfExpression,
+    EmptyVElseIfCondition,
+    TextBetweenIfElse,
+    ElseWithoutIf,
+    KeyOnSlot,
+    KeyOnTemplate,
+    IndexAsKeyInTransitionGroup,
+    DeprecatedScopeAttribute,
+    AmbiguousSlotScopeAndVFor,
+    MixedSlotSyntax,
+    TemplateVSlotNotAtRoot,
+    VSlotOnNonComponent,
+    DefaultSlotAmbiguity,
+    VSlotShorthandWithoutName,
+    EmptyVBindValue,
+    InterpolationInAttribute,
+    PassiveAndPrevent,
+    // expressions
+    InvalidExpression,
+    KeywordAsPropertyName,
+    UnaryOperatorAsPropertyName,
+    InvalidIdentifier,
+    InvalidFunctionParameterExpression,
+    // v-model
+    VModelOnForAlias,
+    VModelOnFileInput,
+    VModelUnsupportedElement,
+    VModelValueConflict,
+    // modules
+    StyleParseFailed,
+    // codegen
+    VOnceWithoutKeyedFor,
+    ComponentListWithoutKey,
+    VOnWithoutArgumentModifiers,
+    InlineTemplateChildren,
+    // single file components
+    DuplicateBlock,
+    MissingEndTag,
+    UnknownCssModuleClass,
+}
+
+impl DiagnosticCode {
+    pub fn severity(&self) -> Severity {
+        match self {
+            DiagnosticCode::DeprecatedScopeAttribute
+            | DiagnosticCode::AmbiguousSlotScopeAndVFor
+            | DiagnosticCode::ComponentListWithoutKey => Severity::Tip,
+            DiagnosticCode::TextOutsideRoot
+            | DiagnosticCode::AttributeCaseMismatch
+            | DiagnosticCode::TextBetweenIfElse
+            | DiagnosticCode::IndexAsKeyInTransitionGroup
+            | DiagnosticCode::PassiveAndPrevent
+            | DiagnosticCode::VModelOnForAlias
+            | DiagnosticCode::VModelOnFileInput
+            | DiagnosticCode::UnknownCssModuleClass => Severity::Warn,
+            _ => Severity::Error,
+        }
+    }
+
+    /// Message template, `{0}`, `{1}`... are replaced by the diagnostic arguments.
+    fn template(&self) -> &'static str {
+        match self {
+            DiagnosticCode::InvalidDynamicArgument => "Invalid dynamic argument expression: attribute names cannot contain spaces, quotes, <, >, / or =.",
+            DiagnosticCode::ForbiddenTag => "Templates should only be responsible for mapping the state to the UI. Avoid placing tags with side-effects in your templates, such as <{0}>, as they will not be parsed.",
+            DiagnosticCode::MultipleRootElements => "Component template should contain exactly one root element. If you are using v-if on multiple elements, use v-else-if to chain them instead.",
+            DiagnosticCode::RootTextOnly => "Component template requires a root element, rather than just text.",
+            DiagnosticCode::TextOutsideRoot => "text \"{0}\" outside root element will be ignored.",
+            DiagnosticCode::RootSlotOrTemplate => "Cannot use <{0}> as component root element because it may contain multiple nodes.",
+            DiagnosticCode::RootVFor => "Cannot use v-for on stateful component root element because it renders multiple elements.",
+            DiagnosticCode::AttributeCaseMismatch => "Attribute \"{0}\" on <{1}> only differs in case from \"{2}\". HTML attributes are case-insensitive and in-DOM templates are lowercased by the browser, camelCased props have to use their kebab-case equivalent \"{3}\" there.",
+            DiagnosticCode::DuplicateAttribute => "duplicate attribute: {0}",
+            DiagnosticCode::InvalidVForExpression => "Invalid v-for expression",
+            DiagnosticCode::MissingVIfExpression => "Missing v-if expression.",
+            DiagnosticCode::MissingVElseIfExpression => "Missing v-else-if expression.",
+            DiagnosticCode::EmptyVElseIfCondition => "empty v-else-if condition",
+            DiagnosticCode::TextBetweenIfElse => "text \"{0}\" between v-if and v-else(-if) will be ignored.",
+            DiagnosticCode::ElseWithoutIf => "v-{0} used on element <{1}> without corresponding v-if.",
+            DiagnosticCode::KeyOnSlot => "`key` does not work on <slot> because slots are abstract outlets and can possibly expand into multiple elements. Use the key on a wrapping element instead.",
+            DiagnosticCode::KeyOnTemplate => "<template> cannot be keyed. Place the key on real elements instead. key was {0}",
+            DiagnosticCode::IndexAsKeyInTransitionGroup => "Do not use v-for index as key on <transition-group> children,\nthis is the same as not using keys.",
+            DiagnosticCode::DeprecatedScopeAttribute => "the \"scope\" attribute for scoped slots have been deprecated and replaced by \"slot-scope\" since 2.5. The new \"slot-scope\" attribute can also be used on plain elements in addition to <template> to denote scoped slots.",
+            DiagnosticCode::AmbiguousSlotScopeAndVFor => "Ambiguous combined usage of slot-scope and v-for on <{0}> (v-for takes higher priority). Use a wrapper <template> for the scoped slot to make it clearer.",
+            DiagnosticCode::MixedSlotSyntax => "Unexpected mixed usage of different slot syntaxes.",
+            DiagnosticCode::TemplateVSlotNotAtRoot => "<template v-slot> can only appear at the root level inside the receiving component.",
+            DiagnosticCode::VSlotOnNonComponent => "v-slot can only be used on components or <template>.",
+            DiagnosticCode::DefaultSlotAmbiguity => "To avoid scope ambiguity, the default slot should also use <template> syntax when there are other named slots.",
+            DiagnosticCode::VSlotShorthandWithoutName => "v-slot shorthand syntax requires a slot name.",
+            DiagnosticCode::EmptyVBindValue => "The value for a v-bind expression cannot be empty. Found in \"v-bind:{0}\"",
+            DiagnosticCode::InterpolationInAttribute => "{0}=\"{1}\": Interpolation inside attributes has been removed. Use v-bind or the colon shorthand instead. For example, instead of <div {2}=\"{{ val }}\">, use <div :{2}=\"val\">.",
+            DiagnosticCode::PassiveAndPrevent => "passive and prevent can't be used together. Passive handler can't prevent default event.",
+            DiagnosticCode::InvalidExpression => "invalid expression: {0} in\n\n    {1}\n\n  Raw expression: {2}\n",
+            DiagnosticCode::KeywordAsPropertyName => "avoid using JavaScript keyword as property name: \"{0}\"\n  Raw expression: {1}",
+            DiagnosticCode::UnaryOperatorAsPropertyName => "avoid using JavaScript unary operator as property name: \"{0}\" in expression {1}",
+            DiagnosticCode::InvalidIdentifier => "invalid {0} \"{1}\" in expression: {2}",
+            DiagnosticCode::InvalidFunctionParameterExpression => "invalid function parameter expression: {0} in\n\n    {1}\n\n  Raw expression: {2}\n",
+            DiagnosticCode::VModelOnForAlias => "<{0} v-model=\"{1}\">: You are binding v-model directly to a v-for iteration alias. This will not be able to modify the v-for source array because writing to the alias is like modifying a function local variable. Consider using an array of objects and use v-model on an object property instead.",
+            DiagnosticCode::VModelOnFileInput => "<{0} v-model=\"{1}\" type=\"file\">:\nFile inputs are read only. Use a v-on:change listener instead.",
+            DiagnosticCode::VModelUnsupportedElement => "<{0} v-model=\"{1}\">: v-model is not supported on this element type. If you are working with contenteditable, it's recommended to wrap a library dedicated for that purpose inside a custom component.",
+            DiagnosticCode::VModelValueConflict => "{0}=\"{1}\" conflicts with v-model on the same element because the latter already expands to a value binding internally",
+            DiagnosticCode::StyleParseFailed => "Failed to parse style: {0}",
+            DiagnosticCode::VOnceWithoutKeyedFor => "v-once can only be used inside v-for that is keyed. ",
+            DiagnosticCode::ComponentListWithoutKey => "<{0} v-for=\"{1} in {2}\">: component lists rendered with v-for should have explicit keys. See https://vuejs.org/guide/list.html#key for more info.",
+            DiagnosticCode::VOnWithoutArgumentModifiers => "v-on without argument does not support modifiers.",
+            DiagnosticCode::InlineTemplateChildren => "Inline-template components must have exactly one child element.",
+            DiagnosticCode::DuplicateBlock => "Single file component can contain only one <{0}> element.",
+            DiagnosticCode::MissingEndTag => "tag <{0}> has no matching end tag.",
+            DiagnosticCode::UnknownCssModuleClass => "Class \"{1}\" referenced as {0}.{1} is not defined in the css module \"{0}\".",
+        }
+    }
+}
+
+/// A single compiler warning, error or tip.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompilerDiagnostic {
+    pub code: DiagnosticCode,
+    pub severity: Severity,
+    pub args: Vec<String>,
+    /// tag of the element the diagnostic was raised for
+    pub tag: Option<String>,
+    /// name of the offending attribute
+    pub attr: Option<String>,
+    /// byte offsets into the template
+    pub start: Option<usize>,
+    pub end: Option<usize>,
+    /// another range the diagnostic refers to, e.g. the first of duplicate attributes
+    pub related: Option<(usize, usize)>,
+}
+
+impl CompilerDiagnostic {
+    pub fn new(code: DiagnosticCode) -> CompilerDiagnostic {
+        CompilerDiagnostic {
+            code,
+            severity: code.severity(),
+            args: Vec::new(),
+            tag: None,
+            attr: None,
+            start: None,
+            end: None,
+            related: None,
+        }
+    }
+
+    pub fn arg(mut self, arg: impl Into<String>) -> CompilerDiagnostic {
+        self.args.push(arg.into());
+        self
+    }
+
+    pub fn tag(mut self, tag: impl Into<String>) -> CompilerDiagnostic {
+        self.tag = Some(tag.into());
+        self
+    }
+
+    pub fn attr(mut self, attr: impl Into<String>) -> CompilerDiagnostic {
+        self.attr = Some(attr.into());
+        self
+    }
+
+    pub fn range(mut self, start: usize, end: usize) -> CompilerDiagnostic {
+        self.start = Some(start);
+        self.end = Some(end);
+        self
+    }
+
+    pub fn related(mut self, start: usize, end: usize) -> CompilerDiagnostic {
+        self.related = Some((start, end));
+        self
+    }
+
+    /// 1-based line and column of the start of the diagnostic.
+    pub fn line_column(&self, source: &str) -> Option<(usize, usize)> {
+        self.start.map(|start| line_column(source, start))
+    }
+
+    /// Excerpt of `source` with the diagnostic range underlined, `None` when the range is unknown.
+    pub fn code_frame(&self, source: &str) -> Option<String> {
+        let start = self.start?;
+
+        Some(generate_code_frame(
+            source,
+            start,
+            self.end.unwrap_or(start),
+        ))
+    }
+
+    /// Message followed by the code frame, the format used when printing compile errors.
+    pub fn render(&self, source: &str) -> String {
+        match self.code_frame(source) {
+            Some(frame) => format!("{}\n\n{}", self.message(), frame),
+            None => self.message(),
+        }
+    }
+
+    pub fn message(&self) -> String {
+        let template = self.code.template();
+        let mut message = String::with_capacity(template.len());
+        let mut rest = template;
+
+        // single pass so that arguments containing "{n}" are kept as is
+        while let Some(start) = rest.find('{') {
+            message.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            let index = rest[1..]
+                .find('}')
+                .and_then(|end| rest[1..=end].parse::<usize>().ok().map(|i| (i, end + 2)));
+
+            match index.and_then(|(i, len)| self.args.get(i).map(|arg| (arg, len))) {
+                Some((arg, len)) => {
+                    message.push_str(arg);
+                    rest = &rest[len..];
+                }
+                None => {
+                    message.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        message.push_str(rest);
+
+        message
+    }
+}
+
+impl fmt::Display for CompilerDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message())
+    }
+}
+
+/// Converts a byte offset into a 1-based line and column, columns are counted in characters.
+pub fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let offset = floor_char_boundary(source, offset);
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map(|index| index + 1).unwrap_or(0);
+
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
@@ -1,4 +1,6 @@
-use rs_html_parser_tokenizer_tokens::QuoteType;
+use crate::ast_tree::{ASTNode, ComponentModel};
+use crate::helpers::to_json_string;
+use unicase_collections::unicase_index_set::UniCaseIndexSet;
 
 pub struct DirectivesParser {
     len: usize,
@@ -97,17 +99,73 @@ pub struct ModelParseResult {
     pub key: Option<String>,
 }
 
+/**
+ * Parse a v-model expression into a base path and a final key segment.
+ * Handles both dot-path and possible square brackets.
+ *
+ * Possible cases:
+ *
+ * - test
+ * - test[key]
+ * - test[test1[key]]
+ * - test["a"][key]
+ * - xxx.test[a[a].test1[key]]
+ * - test.xxx.a["asa"][test1[key]]
+ *
+ */
 pub fn parse_model(val: &str) -> ModelParseResult {
+    // Fix https://github.com/vuejs/vue/pull/7730
+    // allow v-model="obj.val " (trailing whitespace)
     let val = val.trim();
+
+    if val.len() < 2 || !val.contains('[') || !val.ends_with(']') {
+        return match val.rfind('.') {
+            Some(index) => ModelParseResult {
+                exp: val[..index].to_string(),
+                key: Some(format!("\"{}\"", &val[index + 1..])),
+            },
+            None => ModelParseResult {
+                exp: val.to_string(),
+                key: None,
+            },
+        };
+    }
+
     let mut parser = DirectivesParser::new(val);
     parser.parse()
 }
 
-pub fn gen_assignment_code(val: &(Box<str>, QuoteType), assignment: &str) -> String {
-    let value = &*val.0;
+/**
+ * Cross-platform codegen helper for generating v-model value assignment code.
+ */
+pub fn gen_assignment_code(value: &str, assignment: &str) -> String {
     let res = parse_model(value);
     match res.key {
         None => format!("{}={}", value, assignment),
         Some(key) => format!("$set({}, {}, {})", res.exp, key, assignment),
     }
 }
+
+/**
+ * Cross-platform code generation for component v-model
+ */
+pub fn gen_component_model(node: &mut ASTNode, value: &str, modifiers: &UniCaseIndexSet) {
+    let base_value_expression = "$$v";
+    let mut value_expression = base_value_expression.to_string();
+    if modifiers.contains("trim") {
+        value_expression = format!(
+            "(typeof {} === 'string'? {}.trim(): {})",
+            base_value_expression, base_value_expression, base_value_expression
+        );
+    }
+    if modifiers.contains("number") {
+        value_expression = format!("_n({})", value_expression);
+    }
+    let assignment = gen_assignment_code(value, &value_expression);
+
+    node.el.model = Some(ComponentModel {
+        value: format!("({})", value),
+        expression: to_json_string(value),
+        callback: format!("function ({}) {{{}}}", base_value_expression, assignment),
+    });
+}
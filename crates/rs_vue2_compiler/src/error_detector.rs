@@ -0,0 +1,307 @@
+use crate::ast_tree::{ASTElementKind, ASTNode, ASTTree, RawAttr};
+use crate::diagnostics::{CompilerDiagnostic, DiagnosticCode};
+use crate::js_lexer::{matching, tokenize, JsSyntaxError, JsTokenKind};
+use crate::js_validator::{validate_binding, validate_expression, validate_params};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+lazy_static! {
+    static ref DIR_RE: Regex = Regex::new(r"^(v-|@|:|#)").unwrap();
+    static ref ON_RE: Regex = Regex::new(r"^@|^v-on:").unwrap();
+}
+
+// these keywords should not appear inside expressions, but operators like
+// typeof, instanceof and in are allowed
+const PROHIBITED_KEYWORDS: [&str; 31] = [
+    "do",
+    "if",
+    "for",
+    "let",
+    "new",
+    "try",
+    "var",
+    "case",
+    "else",
+    "with",
+    "await",
+    "break",
+    "catch",
+    "class",
+    "const",
+    "super",
+    "throw",
+    "while",
+    "yield",
+    "delete",
+    "export",
+    "import",
+    "return",
+    "switch",
+    "default",
+    "extends",
+    "finally",
+    "continue",
+    "debugger",
+    "function",
+    "arguments",
+];
+
+// these unary operators should not be used as property/method names
+const UNARY_OPERATORS: [&str; 3] = ["delete", "typeof", "void"];
+
+/**
+ * Detects syntax errors in template expressions, a port of vue's `detectErrors`. Instead of
+ * evaluating them with `new Function(...)` expressions are validated by `js_validator`, which
+ * allows reporting the exact range of the offending token.
+ */
+pub(crate) fn detect_errors(tree: &ASTTree) {
+    let roots = tree.wrapper.borrow().children.clone();
+
+    for root in &roots {
+        check_node(tree, root);
+    }
+}
+
+fn check_node(tree: &ASTTree, node_rc: &Rc<RefCell<ASTNode>>) {
+    if node_rc.borrow().el.pre {
+        return;
+    }
+
+    let diagnostics = {
+        let node = node_rc.borrow();
+        match node.el.kind {
+            ASTElementKind::Element => check_element(&node),
+            ASTElementKind::Expression => check_interpolation(&node),
+            _ => vec![],
+        }
+    };
+    let mut nested = vec![];
+    {
+        let mut node = node_rc.borrow_mut();
+        for diagnostic in diagnostics {
+            node.report(diagnostic);
+        }
+
+        nested.extend(node.children.iter().cloned());
+        nested.extend(
+            node.el
+                .if_conditions
+                .iter()
+                .flatten()
+                .skip(1)
+                .filter_map(|condition| tree.get(condition.block_id).cloned()),
+        );
+        nested.extend(
+            node.el
+                .scoped_slots
+                .iter()
+                .flat_map(|slots| slots.values().cloned()),
+        );
+    }
+
+    for child in &nested {
+        check_node(tree, child);
+    }
+}
+
+fn check_element(node: &ASTNode) -> Vec<CompilerDiagnostic> {
+    let mut diagnostics = vec![];
+    for raw_attr in node.el.raw_attrs_map.values() {
+        let name = raw_attr.name.as_str();
+        let Some(value) = raw_attr.value.as_deref().filter(|value| !value.is_empty()) else {
+            continue;
+        };
+        if !DIR_RE.is_match(name) {
+            continue;
+        }
+
+        let check = Check {
+            raw_attr,
+            text: format!("{}=\"{}\"", name, value),
+        };
+        if name == "v-for" {
+            check.check_for(node, value, &mut diagnostics);
+        } else if name == "v-slot" || name.starts_with('#') {
+            check.check_function_parameter_expression(value, &mut diagnostics);
+        } else if ON_RE.is_match(name) {
+            check.check_event(value, &mut diagnostics);
+        } else {
+            check.check_expression(value, 0, &mut diagnostics);
+        }
+    }
+
+    diagnostics
+}
+
+fn check_interpolation(node: &ASTNode) -> Vec<CompilerDiagnostic> {
+    let Some(expression) = node.el.expression.as_deref() else {
+        return vec![];
+    };
+
+    // processed expressions don't map back to the template, the whole text is reported
+    match validate_expression(expression) {
+        Ok(()) => vec![],
+        Err(err) => vec![expression_diagnostic(
+            expression,
+            &err,
+            node.el.token.data.trim(),
+        )],
+    }
+}
+
+// a directive attribute being checked, diagnostics point into its value
+struct Check<'a> {
+    raw_attr: &'a RawAttr,
+    // raw expression, e.g. `v-if="a &&"`
+    text: String,
+}
+
+impl<'a> Check<'a> {
+    fn check_for(&self, node: &ASTNode, value: &str, diagnostics: &mut Vec<CompilerDiagnostic>) {
+        // invalid v-for values are reported by the parser
+        let Some(for_value) = node.el.for_value.as_deref() else {
+            return;
+        };
+
+        let offset = value.rfind(for_value).unwrap_or(0);
+        self.check_expression(for_value, offset, diagnostics);
+
+        // search the aliases in order, an iterator can be a substring of the alias
+        let mut cursor = 0;
+        let identifiers = [
+            (node.el.alias.as_deref(), "v-for alias"),
+            (node.el.iterator1.as_deref(), "v-for iterator"),
+            (node.el.iterator2.as_deref(), "v-for iterator"),
+        ];
+        for (identifier, kind) in identifiers {
+            let Some(identifier) = identifier else {
+                continue;
+            };
+
+            let offset = value[cursor..]
+                .find(identifier)
+                .map_or(0, |index| cursor + index);
+            cursor = offset + identifier.len();
+            if let Err(err) = validate_binding(identifier) {
+                diagnostics.push(
+                    self.diagnostic(
+                        DiagnosticCode::InvalidIdentifier,
+                        offset + err.start,
+                        offset + err.end,
+                    )
+                    .arg(kind)
+                    .arg(identifier)
+                    .arg(self.text.trim()),
+                );
+            }
+        }
+    }
+
+    fn check_function_parameter_expression(
+        &self,
+        value: &str,
+        diagnostics: &mut Vec<CompilerDiagnostic>,
+    ) {
+        if let Err(err) = validate_params(value) {
+            diagnostics.push(
+                self.diagnostic(
+                    DiagnosticCode::InvalidFunctionParameterExpression,
+                    err.start,
+                    err.end,
+                )
+                .arg(&err.message)
+                .arg(value)
+                .arg(self.text.trim()),
+            );
+        }
+    }
+
+    fn check_event(&self, value: &str, diagnostics: &mut Vec<CompilerDiagnostic>) {
+        if let Ok(tokens) = tokenize(value) {
+            let unary_call = (0..tokens.len()).find(|index| {
+                tokens[*index].kind == JsTokenKind::Keyword
+                    && UNARY_OPERATORS.contains(&tokens[*index].text(value))
+                    && tokens
+                        .get(index + 1)
+                        .is_some_and(|token| token.is_punctuator(value, "("))
+                    && !(*index > 0 && matches!(tokens[index - 1].text(value), "." | "?."))
+            });
+
+            if let Some(index) = unary_call {
+                let start = tokens[index].start;
+                let end = matching(&tokens, value, index + 1)
+                    .map_or(value.len(), |close| tokens[close].end);
+                diagnostics.push(
+                    self.diagnostic(DiagnosticCode::UnaryOperatorAsPropertyName, start, end)
+                        .arg(&value[start..end])
+                        .arg(self.text.trim()),
+                );
+            }
+        }
+
+        self.check_expression(value, 0, diagnostics);
+    }
+
+    // `offset` is the position of `expression` inside the attribute value
+    fn check_expression(
+        &self,
+        expression: &str,
+        offset: usize,
+        diagnostics: &mut Vec<CompilerDiagnostic>,
+    ) {
+        if let Err(err) = validate_expression(expression) {
+            let diagnostic = expression_diagnostic(expression, &err, self.text.trim());
+            let (start, end) = prohibited_keyword(expression).unwrap_or((err.start, err.end));
+
+            diagnostics.push(self.locate(diagnostic, offset + start, offset + end));
+        }
+    }
+
+    fn diagnostic(&self, code: DiagnosticCode, start: usize, end: usize) -> CompilerDiagnostic {
+        self.locate(CompilerDiagnostic::new(code), start, end)
+    }
+
+    // narrows the diagnostic down to `start..end` of the attribute value
+    fn locate(
+        &self,
+        diagnostic: CompilerDiagnostic,
+        start: usize,
+        end: usize,
+    ) -> CompilerDiagnostic {
+        let diagnostic = diagnostic.attr(self.raw_attr.name.as_str());
+        let Some(value_start) = self.raw_attr.value_start else {
+            return diagnostic.range(self.raw_attr.start, self.raw_attr.end);
+        };
+
+        // entities in the value can shift the offsets, stay inside the attribute
+        let start = (value_start + start).min(self.raw_attr.end);
+        let end = (value_start + end).clamp(start, self.raw_attr.end);
+        diagnostic.range(start, end)
+    }
+}
+
+fn expression_diagnostic(expression: &str, err: &JsSyntaxError, text: &str) -> CompilerDiagnostic {
+    match prohibited_keyword(expression) {
+        Some((start, end)) => CompilerDiagnostic::new(DiagnosticCode::KeywordAsPropertyName)
+            .arg(&expression[start..end])
+            .arg(text),
+        None => CompilerDiagnostic::new(DiagnosticCode::InvalidExpression)
+            .arg(&err.message)
+            .arg(expression)
+            .arg(text),
+    }
+}
+
+// range of the first keyword that is not allowed in expressions, strings excluded
+fn prohibited_keyword(expression: &str) -> Option<(usize, usize)> {
+    tokenize(expression)
+        .ok()?
+        .iter()
+        .find(|token| {
+            matches!(token.kind, JsTokenKind::Keyword | JsTokenKind::Identifier)
+                && PROHIBITED_KEYWORDS.contains(&token.text(expression))
+        })
+        .map(|token| (token.start, token.end))
+}
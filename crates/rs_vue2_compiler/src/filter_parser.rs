@@ -1,8 +1,6 @@
-use rs_html_parser_tokenizer_tokens::QuoteType;
-
-pub fn parse_filters((expr, quote_type): &(Box<str>, QuoteType)) -> String {
-    let mut in_single = quote_type == &QuoteType::Single;
-    let mut in_double = quote_type == &QuoteType::Double;
+pub fn parse_filters(expr: &str) -> String {
+    let mut in_single = false;
+    let mut in_double = false;
     let mut in_template_string = false;
     let mut in_regex = false;
     let mut curly = 0;
@@ -13,8 +11,9 @@ pub fn parse_filters((expr, quote_type): &(Box<str>, QuoteType)) -> String {
     let mut prev;
     let mut expression: Option<String> = None;
     let mut filters: Vec<String> = Vec::new();
+    let bytes = expr.as_bytes();
 
-    for (i, char) in expr.chars().enumerate() {
+    for (i, char) in expr.char_indices() {
         prev = c;
         c = char;
 
@@ -35,8 +34,8 @@ pub fn parse_filters((expr, quote_type): &(Box<str>, QuoteType)) -> String {
                 in_regex = false;
             }
         } else if c == '|'
-            && expr.chars().nth(i + 1).unwrap() != '|'
-            && expr.chars().nth(i - 1).unwrap() != '|'
+            && bytes.get(i + 1) != Some(&b'|')
+            && (i == 0 || bytes[i - 1] != b'|')
             && curly == 0
             && square == 0
             && paren == 0
@@ -45,7 +44,7 @@ pub fn parse_filters((expr, quote_type): &(Box<str>, QuoteType)) -> String {
                 last_filter_index = i + 1;
                 expression = Some(expr[..i].trim().to_string());
             } else {
-                push_filter(&mut filters, &expr, &mut last_filter_index, i);
+                push_filter(&mut filters, expr, &mut last_filter_index, i);
             }
         } else {
             match c {
@@ -61,16 +60,8 @@ pub fn parse_filters((expr, quote_type): &(Box<str>, QuoteType)) -> String {
                 _ => (),
             }
             if c == '/' {
-                let mut j: i32 = i as i32 - 1;
-                let mut p: char = '0';
-                while j >= 0 {
-                    p = expr.chars().nth(j as usize).unwrap();
-                    if p != ' ' {
-                        break;
-                    }
-                    j -= 1;
-                }
-                if j < 0 || !valid_division_char(p) {
+                let p = expr[..i].chars().rev().find(|p| *p != ' ');
+                if !p.is_some_and(valid_division_char) {
                     in_regex = true;
                 }
             }
@@ -80,7 +71,7 @@ pub fn parse_filters((expr, quote_type): &(Box<str>, QuoteType)) -> String {
     if expression.is_none() {
         expression = Some(expr[..].trim().to_string());
     } else if last_filter_index != 0 {
-        push_filter(&mut filters, &expr, &mut last_filter_index, expr.len());
+        push_filter(&mut filters, expr, &mut last_filter_index, expr.len());
     }
 
     if !filters.is_empty() {
@@ -109,12 +100,9 @@ fn valid_division_char(p: char) -> bool {
 }
 
 fn wrap_filter(exp: String, filter: String) -> String {
-    let i = filter.find('(');
-    if i.is_none() {
-        return format!("_f(\"{}\")({})", filter, exp);
-    } else {
-        let (name, args) = filter.split_at(i.unwrap());
-        return format!(
+    if let Some(i) = filter.find('(') {
+        let (name, args) = filter.split_at(i);
+        format!(
             "_f(\"{}\")({}{})",
             name,
             exp,
@@ -123,6 +111,8 @@ fn wrap_filter(exp: String, filter: String) -> String {
             } else {
                 args.to_string()
             }
-        );
+        )
+    } else {
+        format!("_f(\"{}\")({})", filter, exp)
     }
 }
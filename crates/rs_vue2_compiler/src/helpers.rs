@@ -37,6 +37,16 @@ pub fn to_hyphen_case(s: &str) -> String {
 pub fn is_some_and_ref<T>(item: &Option<T>, f: impl FnOnce(&T) -> bool) -> bool {
     match item {
         None => false,
-        Some(x) => f(&x),
+        Some(x) => f(x),
     }
 }
+
+/**
+ * Serialize a string into a JavaScript string literal.
+ */
+pub fn to_json_string(s: &str) -> String {
+    serde_json::to_string(s)
+        .unwrap()
+        .replace('\u{2028}', "\\u2028")
+        .replace('\u{2029}', "\\u2029")
+}
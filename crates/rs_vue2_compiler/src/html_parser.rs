@@ -0,0 +1,303 @@
+use crate::ast_tree::{RawAttr, Token, TokenAttrs};
+use crate::diagnostics::{CompilerDiagnostic, DiagnosticCode};
+use crate::warn_logger::WarnLogger;
+use crate::web::element::{can_be_left_open_tag, is_non_phrasing_tag, is_unary_tag};
+use rs_html_parser_tokenizer::{Tokenizer, TokenizerOptions};
+use rs_html_parser_tokenizer_tokens::{QuoteType, TokenizerToken, TokenizerTokenLocation};
+use rs_html_parser_tokens::TokenKind;
+use std::collections::VecDeque;
+use std::mem::take;
+
+// an element waiting for its end tag
+struct OpenElement {
+    tag: String,
+    // range of the start tag
+    start: usize,
+    end: usize,
+}
+
+/**
+ * Turns the raw tokenizer output into open and close tag tokens the same way vue's `parseHTML`
+ * does: start tags of non-phrasing elements close an open `<p>`, elements that can be left open
+ * are closed by a sibling of the same type, unary and self-closing tags never get children and
+ * end tags close every element opened after the matching start tag. Elements closed without an
+ * end tag of their own are reported as missing one, stray end tags are dropped.
+ *
+ * Close tags emitted without an end tag in the source are marked as implied, like start tags
+ * implied by a stray `</p>` or `</br>`. Tokens carry their range in the source, implied close
+ * tags end where the last token written in the source ended.
+ */
+pub(crate) struct HtmlParser<'a> {
+    template: &'a str,
+    tokenizer: Tokenizer<'a>,
+    offset: usize,
+    // only set in dev mode
+    warn: Option<Box<dyn WarnLogger>>,
+    stack: Vec<OpenElement>,
+    next_tokens: VecDeque<Token>,
+    open_tag: Option<OpenElement>,
+    attrs: TokenAttrs,
+    raw_attrs: Vec<RawAttr>,
+    attr: Option<RawAttr>,
+    last_end: usize,
+}
+
+impl<'a> HtmlParser<'a> {
+    /// `offset` is added to every range, for templates embedded in a larger file.
+    pub fn new(
+        template: &'a str,
+        offset: usize,
+        options: &'a TokenizerOptions,
+        warn: Option<Box<dyn WarnLogger>>,
+    ) -> HtmlParser<'a> {
+        HtmlParser {
+            template,
+            tokenizer: Tokenizer::new(template.as_bytes(), options),
+            offset,
+            warn,
+            stack: vec![],
+            next_tokens: VecDeque::new(),
+            open_tag: None,
+            attrs: TokenAttrs::new(),
+            raw_attrs: vec![],
+            attr: None,
+            last_end: offset,
+        }
+    }
+
+    fn on_token(&mut self, token: TokenizerToken) {
+        let template = self.template;
+        let text = &template[token.start..token.end];
+        let offset = self.offset;
+
+        match token.location {
+            TokenizerTokenLocation::OpenTagName => {
+                self.open_tag = Some(OpenElement {
+                    tag: text.to_string(),
+                    start: offset + self.rfind_byte(token.start, b'<'),
+                    end: offset + token.end,
+                });
+            }
+            TokenizerTokenLocation::AttrName => {
+                self.attr = Some(RawAttr {
+                    name: text.to_string(),
+                    value: None,
+                    start: offset + token.start,
+                    end: offset + token.end,
+                    value_start: None,
+                    quote_type: QuoteType::NoValue,
+                });
+            }
+            TokenizerTokenLocation::AttrData => {
+                if let Some(attr) = self.attr.as_mut() {
+                    attr.value_start.get_or_insert(offset + token.start);
+                    attr.value.get_or_insert_with(String::new).push_str(text);
+                }
+            }
+            TokenizerTokenLocation::AttrEntity => {
+                if let (Some(attr), Some(c)) = (self.attr.as_mut(), char::from_u32(token.code)) {
+                    attr.value.get_or_insert_with(String::new).push(c);
+                }
+            }
+            TokenizerTokenLocation::AttrEnd => {
+                if let Some(mut attr) = self.attr.take() {
+                    attr.end = attr.end.max(offset + token.end);
+                    attr.quote_type = token.quote;
+                    // the first of attributes with the same name wins
+                    if !self
+                        .raw_attrs
+                        .iter()
+                        .any(|raw_attr| raw_attr.name == attr.name)
+                    {
+                        self.attrs.insert(
+                            attr.name.clone(),
+                            attr.value
+                                .clone()
+                                .map(|value| (value.into_boxed_str(), token.quote)),
+                        );
+                    }
+                    self.raw_attrs.push(attr);
+                }
+            }
+            // the token points at the closing ">"
+            TokenizerTokenLocation::OpenTagEnd => self.start_tag(offset + token.start + 1, false),
+            TokenizerTokenLocation::SelfClosingTag => {
+                self.start_tag(offset + token.start + 1, true)
+            }
+            TokenizerTokenLocation::CloseTag => {
+                let start = offset + self.rfind_byte(token.start, b'<');
+                let end = offset + self.find_byte(token.end, b'>') + 1;
+                self.end_tag(text, start, end);
+            }
+            TokenizerTokenLocation::Text => self.push_located(
+                TokenKind::Text,
+                text,
+                offset + token.start,
+                offset + token.end,
+            ),
+            TokenizerTokenLocation::TextEntity => {
+                if let Some(c) = char::from_u32(token.code) {
+                    self.push_located(
+                        TokenKind::Text,
+                        &c.to_string(),
+                        offset + token.start,
+                        offset + token.end,
+                    );
+                }
+            }
+            TokenizerTokenLocation::Comment | TokenizerTokenLocation::CData => {
+                let start = offset + self.rfind_byte(token.start, b'<');
+                let end = offset + self.find_byte(token.end, b'>') + 1;
+                self.push_located(TokenKind::Comment, text, start, end);
+                self.push(TokenKind::CommentEnd, "", false);
+            }
+            TokenizerTokenLocation::End => self.close_elements(0),
+            _ => {}
+        }
+    }
+
+    fn start_tag(&mut self, end: usize, unary_slash: bool) {
+        let Some(mut element) = self.open_tag.take() else {
+            return;
+        };
+        element.end = end;
+
+        if self.last_tag_is("p") && is_non_phrasing_tag(&element.tag) {
+            self.close_last();
+        }
+        if can_be_left_open_tag(&element.tag) && self.last_tag_is(&element.tag) {
+            self.close_last();
+        }
+
+        let attrs = take(&mut self.attrs);
+        self.next_tokens.push_back(Token {
+            data: element.tag.clone().into_boxed_str(),
+            attrs: (!attrs.is_empty()).then_some(attrs),
+            kind: TokenKind::OpenTag,
+            is_implied: false,
+            start: Some(element.start),
+            end: Some(element.end),
+            raw_attrs: take(&mut self.raw_attrs),
+        });
+        self.last_end = element.end;
+
+        if is_unary_tag(&element.tag) || unary_slash {
+            self.push(TokenKind::CloseTag, &element.tag, true);
+        } else {
+            self.stack.push(element);
+        }
+    }
+
+    fn end_tag(&mut self, tag: &str, start: usize, end: usize) {
+        let position = self
+            .stack
+            .iter()
+            .rposition(|element| element.tag.eq_ignore_ascii_case(tag));
+
+        if let Some(position) = position {
+            self.close_elements(position + 1);
+            if let Some(element) = self.stack.pop() {
+                self.push_located(TokenKind::CloseTag, &element.tag, start, end);
+            }
+        } else if tag.eq_ignore_ascii_case("br") {
+            self.push(TokenKind::OpenTag, tag, true);
+            self.push(TokenKind::CloseTag, tag, true);
+        } else if tag.eq_ignore_ascii_case("p") {
+            // the end tag closes the implied start tag
+            self.push(TokenKind::OpenTag, tag, true);
+            self.push_located(TokenKind::CloseTag, tag, start, end);
+        }
+    }
+
+    fn last_tag_is(&self, tag: &str) -> bool {
+        self.stack.last().is_some_and(|element| element.tag == tag)
+    }
+
+    // closes the innermost element, its end tag is implied by the next start tag
+    fn close_last(&mut self) {
+        if let Some(element) = self.stack.pop() {
+            self.push(TokenKind::CloseTag, &element.tag, true);
+        }
+    }
+
+    // closes the elements above `depth` which are missing their end tag
+    fn close_elements(&mut self, depth: usize) {
+        while self.stack.len() > depth {
+            let element = self.stack.pop().unwrap();
+
+            if let Some(warn) = self.warn.as_mut() {
+                warn.diagnostic(
+                    CompilerDiagnostic::new(DiagnosticCode::MissingEndTag)
+                        .arg(element.tag.as_str())
+                        .tag(element.tag.as_str())
+                        .range(element.start, element.end),
+                );
+            }
+            self.push(TokenKind::CloseTag, &element.tag, true);
+        }
+    }
+
+    // token made up by the parser, implied close tags end with the last token of the source
+    fn push(&mut self, kind: TokenKind, data: &str, is_implied: bool) {
+        let end = (kind == TokenKind::CloseTag).then_some(self.last_end);
+
+        self.next_tokens.push_back(Token {
+            data: data.into(),
+            attrs: None,
+            kind,
+            is_implied,
+            start: None,
+            end,
+            raw_attrs: vec![],
+        });
+    }
+
+    fn push_located(&mut self, kind: TokenKind, data: &str, start: usize, end: usize) {
+        self.next_tokens.push_back(Token {
+            data: data.into(),
+            attrs: None,
+            kind,
+            is_implied: false,
+            start: Some(start),
+            end: Some(end),
+            raw_attrs: vec![],
+        });
+        self.last_end = end;
+    }
+
+    fn rfind_byte(&self, from: usize, byte: u8) -> usize {
+        self.template.as_bytes()[..from]
+            .iter()
+            .rposition(|b| *b == byte)
+            .unwrap_or(from)
+    }
+
+    fn find_byte(&self, from: usize, byte: u8) -> usize {
+        let bytes = self.template.as_bytes();
+        let from = from.min(bytes.len());
+
+        bytes[from..]
+            .iter()
+            .position(|b| *b == byte)
+            .map(|index| from + index)
+            .unwrap_or(bytes.len().saturating_sub(1))
+    }
+}
+
+impl Iterator for HtmlParser<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        loop {
+            if let Some(token) = self.next_tokens.pop_front() {
+                return Some(token);
+            }
+
+            match self.tokenizer.next() {
+                Some(token) => self.on_token(token),
+                None if !self.stack.is_empty() => self.close_elements(0),
+                None => return None,
+            }
+        }
+    }
+}
@@ -0,0 +1,363 @@
+/**
+ * Minimal tokenizer for the javascript found in template expressions and generated render code.
+ * It knows enough of the grammar to tell identifiers from property names, strings, template
+ * literals and regular expressions; it doesn't build a syntax tree.
+ */
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum JsTokenKind {
+    Identifier,
+    Keyword,
+    Number,
+    String,
+    // a chunk of a template literal, "`a${", "}b${" or "}c`"
+    Template,
+    Regex,
+    Punctuator,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct JsToken {
+    pub kind: JsTokenKind,
+    // byte offsets into the tokenized source
+    pub start: usize,
+    pub end: usize,
+}
+
+impl JsToken {
+    pub fn text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.start..self.end]
+    }
+
+    pub fn is_punctuator(&self, source: &str, punctuator: &str) -> bool {
+        self.kind == JsTokenKind::Punctuator && self.text(source) == punctuator
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsSyntaxError {
+    pub message: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+const KEYWORDS: [&str; 43] = [
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "import",
+    "in",
+    "instanceof",
+    "let",
+    "new",
+    "null",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+    "implements",
+    "interface",
+    "package",
+];
+
+// longest first, so the first match wins
+const PUNCTUATORS: [&str; 51] = [
+    ">>>=", "...", "===", "!==", "**=", "<<=", ">>=", ">>>", "&&=", "||=", "??=", "=>", "==", "!=",
+    "<=", ">=", "&&", "||", "??", "?.", "++", "--", "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=",
+    "**", "<<", ">>", "{", "}", "(", ")", "[", "]", ";", ",", "<", ">", "+", "-", "*", "/", "%",
+    "&", "|", "^",
+];
+
+const SINGLE_PUNCTUATORS: [char; 5] = ['!', '~', '?', ':', '='];
+
+pub fn is_keyword(name: &str) -> bool {
+    KEYWORDS.contains(&name)
+}
+
+pub fn is_identifier_start(c: char) -> bool {
+    c == '$' || c == '_' || c.is_alphabetic()
+}
+
+pub fn is_identifier_part(c: char) -> bool {
+    c == '$' || c == '_' || c.is_alphanumeric()
+}
+
+pub fn tokenize(source: &str) -> Result<Vec<JsToken>, JsSyntaxError> {
+    let bytes = source.as_bytes();
+    let mut tokens: Vec<JsToken> = vec![];
+    // open braces, true for `${` of template literals
+    let mut braces: Vec<bool> = vec![];
+    let mut pos = 0;
+
+    while pos < source.len() {
+        let c = source[pos..].chars().next().unwrap();
+
+        if c.is_whitespace() {
+            pos += c.len_utf8();
+            continue;
+        }
+        if source[pos..].starts_with("//") {
+            pos = source[pos..]
+                .find('\n')
+                .map_or(source.len(), |end| pos + end);
+            continue;
+        }
+        if source[pos..].starts_with("/*") {
+            pos = match source[pos + 2..].find("*/") {
+                Some(end) => pos + 2 + end + 2,
+                None => return Err(error("Unterminated comment", pos, source.len())),
+            };
+            continue;
+        }
+
+        let start = pos;
+        let kind = if is_identifier_start(c) {
+            pos = scan_while(source, pos, is_identifier_part);
+            if is_keyword(&source[start..pos]) {
+                JsTokenKind::Keyword
+            } else {
+                JsTokenKind::Identifier
+            }
+        } else if c.is_ascii_digit()
+            || (c == '.' && bytes.get(pos + 1).is_some_and(u8::is_ascii_digit))
+        {
+            pos = scan_number(source, pos);
+            JsTokenKind::Number
+        } else if c == '"' || c == '\'' {
+            pos = scan_string(source, pos, c)?;
+            JsTokenKind::String
+        } else if c == '`' {
+            pos = scan_template(source, pos + 1, start, &mut braces)?;
+            JsTokenKind::Template
+        } else if c == '}' && braces.last() == Some(&true) {
+            braces.pop();
+            pos = scan_template(source, pos + 1, start, &mut braces)?;
+            JsTokenKind::Template
+        } else if c == '/' && regex_allowed(source, tokens.last()) {
+            pos = scan_regex(source, pos)?;
+            JsTokenKind::Regex
+        } else if source[pos..].starts_with("...") || c == '.' {
+            pos += if source[pos..].starts_with("...") {
+                3
+            } else {
+                1
+            };
+            JsTokenKind::Punctuator
+        } else if let Some(punctuator) = PUNCTUATORS.iter().find(|p| {
+            source[pos..].starts_with(*p)
+                // `a?.5:1` is a conditional
+                && (**p != "?." || !bytes.get(pos + 2).is_some_and(u8::is_ascii_digit))
+        }) {
+            pos += punctuator.len();
+            match *punctuator {
+                "{" => braces.push(false),
+                "}" => {
+                    braces.pop();
+                }
+                _ => {}
+            }
+            JsTokenKind::Punctuator
+        } else if SINGLE_PUNCTUATORS.contains(&c) {
+            pos += 1;
+            JsTokenKind::Punctuator
+        } else {
+            return Err(error(
+                "Invalid or unexpected token",
+                pos,
+                pos + c.len_utf8(),
+            ));
+        };
+
+        tokens.push(JsToken {
+            kind,
+            start,
+            end: pos,
+        });
+    }
+
+    if braces.last() == Some(&true) {
+        return Err(error(
+            "Unterminated template literal",
+            source.len(),
+            source.len(),
+        ));
+    }
+
+    Ok(tokens)
+}
+
+/// Index of the token closing the bracket opened at `open`, template substitutions included.
+pub fn matching(tokens: &[JsToken], source: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+
+    for (index, token) in tokens.iter().enumerate().skip(open) {
+        match token.kind {
+            JsTokenKind::Punctuator => match token.text(source) {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => depth -= 1,
+                _ => {}
+            },
+            JsTokenKind::Template => {
+                let text = token.text(source);
+                if text.starts_with('}') {
+                    depth -= 1;
+                }
+                if text.ends_with("${") {
+                    depth += 1;
+                }
+            }
+            _ => {}
+        }
+        if depth == 0 {
+            return Some(index);
+        }
+    }
+
+    None
+}
+
+pub fn error(message: impl Into<String>, start: usize, end: usize) -> JsSyntaxError {
+    JsSyntaxError {
+        message: message.into(),
+        start,
+        end,
+    }
+}
+
+fn scan_while(source: &str, pos: usize, predicate: impl Fn(char) -> bool) -> usize {
+    source[pos..]
+        .char_indices()
+        .find(|(_, c)| !predicate(*c))
+        .map_or(source.len(), |(index, _)| pos + index)
+}
+
+fn scan_number(source: &str, pos: usize) -> usize {
+    let bytes = source.as_bytes();
+    let is_hex = source[pos..].len() > 1 && bytes[pos] == b'0' && bytes[pos + 1] != b'.';
+    let mut end = pos;
+
+    while end < bytes.len() {
+        let b = bytes[end];
+        let is_exponent_sign =
+            (b == b'+' || b == b'-') && !is_hex && matches!(bytes[end - 1], b'e' | b'E');
+        if !(b.is_ascii_alphanumeric() || b == b'_' || b == b'.' || is_exponent_sign) {
+            break;
+        }
+        end += 1;
+    }
+
+    end
+}
+
+fn scan_string(source: &str, pos: usize, quote: char) -> Result<usize, JsSyntaxError> {
+    let mut chars = source[pos + 1..].char_indices();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '\n' => break,
+            _ if c == quote => return Ok(pos + 1 + index + 1),
+            _ => {}
+        }
+    }
+
+    Err(error("Invalid or unexpected token", pos, source.len()))
+}
+
+// scans until the closing backtick or the next `${`
+fn scan_template(
+    source: &str,
+    pos: usize,
+    start: usize,
+    braces: &mut Vec<bool>,
+) -> Result<usize, JsSyntaxError> {
+    let mut chars = source[pos..].char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '`' => return Ok(pos + index + 1),
+            '$' if chars.peek().is_some_and(|(_, c)| *c == '{') => {
+                braces.push(true);
+                return Ok(pos + index + 2);
+            }
+            _ => {}
+        }
+    }
+
+    Err(error("Unterminated template literal", start, source.len()))
+}
+
+fn scan_regex(source: &str, pos: usize) -> Result<usize, JsSyntaxError> {
+    let mut chars = source[pos + 1..].char_indices();
+    let mut in_class = false;
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '\n' => break,
+            '/' if !in_class => {
+                // flags
+                return Ok(scan_while(source, pos + 1 + index + 1, is_identifier_part));
+            }
+            _ => {}
+        }
+    }
+
+    Err(error(
+        "Invalid regular expression: missing /",
+        pos,
+        source.len(),
+    ))
+}
+
+// a slash starts a regular expression unless it follows something that ends an operand
+fn regex_allowed(source: &str, previous: Option<&JsToken>) -> bool {
+    let Some(previous) = previous else {
+        return true;
+    };
+
+    match previous.kind {
+        JsTokenKind::Keyword => !matches!(
+            previous.text(source),
+            "this" | "super" | "true" | "false" | "null"
+        ),
+        JsTokenKind::Punctuator => !matches!(previous.text(source), ")" | "]" | "}"),
+        _ => false,
+    }
+}
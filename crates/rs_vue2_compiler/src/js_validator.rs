@@ -0,0 +1,502 @@
+use crate::js_lexer::{error, matching, tokenize, JsSyntaxError, JsToken, JsTokenKind};
+
+const BINARY_OPERATORS: [&str; 39] = [
+    "+", "-", "*", "/", "%", "**", "==", "!=", "===", "!==", "<", ">", "<=", ">=", "&&", "||",
+    "??", "&", "|", "^", "<<", ">>", ">>>", "=", "+=", "-=", "*=", "/=", "%=", "**=", "<<=", ">>=",
+    ">>>=", "&=", "|=", "^=", "&&=", "||=", "??=",
+];
+
+const STATEMENT_KEYWORDS: [&str; 12] = [
+    "if", "for", "while", "do", "var", "let", "const", "return", "throw", "try", "switch", "with",
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Frame {
+    // grouping parens, or the arguments of a call
+    Paren { call: bool },
+    // array literal
+    Array,
+    // member access, `a[b]`
+    Index,
+    Object,
+    ComputedKey,
+    Substitution,
+    Ternary,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum State {
+    // `empty` allows closing the current list, e.g. `f()`, `[1,]`
+    Operand { empty: bool },
+    Operator,
+    Key,
+    AfterKey { shorthand: bool },
+}
+
+struct Validator<'a> {
+    source: &'a str,
+    tokens: Vec<JsToken>,
+    frames: Vec<Frame>,
+    state: State,
+}
+
+/**
+ * Checks that `source` is a valid expression, statements separated by `;` are accepted too.
+ * This is the compile-time counterpart of the `new Function(...)` calls vue's error detector
+ * relies on: tokens are checked for a valid sequence of operands and operators, function bodies
+ * are only checked for balanced brackets.
+ */
+pub fn validate_expression(source: &str) -> Result<(), JsSyntaxError> {
+    Validator::new(source)?.validate()
+}
+
+/// Checks that `source` can be declared with `var`, an identifier or a destructuring pattern.
+pub fn validate_binding(source: &str) -> Result<(), JsSyntaxError> {
+    let tokens = tokenize(source)?;
+
+    check_binding(source, &tokens, 0, tokens.len())
+}
+
+/// Checks a function parameter list, e.g. the value of `v-slot`.
+pub fn validate_params(source: &str) -> Result<(), JsSyntaxError> {
+    let tokens = tokenize(source)?;
+    let mut start = 0;
+
+    while start < tokens.len() {
+        // end of this parameter
+        let mut end = start;
+        while end < tokens.len() && !tokens[end].is_punctuator(source, ",") {
+            if matches!(tokens[end].text(source), "(" | "[" | "{") {
+                end = matching(&tokens, source, end)
+                    .ok_or_else(|| error("Unexpected end of input", source.len(), source.len()))?;
+            }
+            end += 1;
+        }
+
+        let mut param_start = start;
+        if tokens[start].is_punctuator(source, "...") {
+            param_start += 1;
+        }
+        // a default value follows the pattern, `=` inside the pattern belongs to its properties
+        let pattern_end = match tokens.get(param_start).map(|token| token.text(source)) {
+            Some("{" | "[") if param_start < end => {
+                matching(tokens.as_slice(), source, param_start).map_or(end, |close| close + 1)
+            }
+            _ => param_start,
+        };
+        let default = (pattern_end..end).find(|index| tokens[*index].is_punctuator(source, "="));
+        check_binding(source, &tokens, param_start, default.unwrap_or(end))?;
+
+        if let Some(default) = default {
+            let Some(first) = tokens.get(default + 1).filter(|_| default + 1 < end) else {
+                return Err(unexpected_end(source, &tokens, end));
+            };
+            let expression_end = tokens[end - 1].end;
+            validate_expression(&source[first.start..expression_end]).map_err(|err| {
+                error(err.message, first.start + err.start, first.start + err.end)
+            })?;
+        }
+
+        if end < tokens.len() && end + 1 == tokens.len() {
+            // trailing comma
+            return Err(error("Unexpected end of input", source.len(), source.len()));
+        }
+        start = end + 1;
+    }
+
+    Ok(())
+}
+
+// a single identifier or a bracket balanced destructuring pattern in tokens[start..end]
+fn check_binding(
+    source: &str,
+    tokens: &[JsToken],
+    start: usize,
+    end: usize,
+) -> Result<(), JsSyntaxError> {
+    let Some(first) = tokens.get(start).filter(|_| start < end) else {
+        return Err(unexpected_end(source, tokens, end));
+    };
+
+    match first.kind {
+        JsTokenKind::Identifier => {
+            if end - start > 1 {
+                return Err(unexpected(source, &tokens[start + 1]));
+            }
+            Ok(())
+        }
+        JsTokenKind::Punctuator if matches!(first.text(source), "{" | "[") => {
+            match matching(tokens, source, start) {
+                Some(close) if close + 1 == end => Ok(()),
+                Some(close) if close + 1 < end => Err(unexpected(source, &tokens[close + 1])),
+                _ => Err(unexpected_end(source, tokens, end)),
+            }
+        }
+        _ => Err(unexpected(source, first)),
+    }
+}
+
+fn unexpected(source: &str, token: &JsToken) -> JsSyntaxError {
+    let text = token.text(source);
+    let message = match token.kind {
+        JsTokenKind::Identifier => format!("Unexpected identifier '{}'", text),
+        JsTokenKind::Number => "Unexpected number".to_string(),
+        JsTokenKind::String => "Unexpected string".to_string(),
+        JsTokenKind::Template => "Unexpected template string".to_string(),
+        JsTokenKind::Regex => "Unexpected regular expression".to_string(),
+        JsTokenKind::Keyword | JsTokenKind::Punctuator => format!("Unexpected token '{}'", text),
+    };
+
+    error(message, token.start, token.end)
+}
+
+fn unexpected_end(source: &str, tokens: &[JsToken], index: usize) -> JsSyntaxError {
+    match tokens.get(index) {
+        Some(token) => unexpected(source, token),
+        None => error("Unexpected end of input", source.len(), source.len()),
+    }
+}
+
+impl<'a> Validator<'a> {
+    fn new(source: &'a str) -> Result<Validator<'a>, JsSyntaxError> {
+        Ok(Validator {
+            source,
+            tokens: tokenize(source)?,
+            frames: vec![],
+            state: State::Operand { empty: true },
+        })
+    }
+
+    fn text(&self, index: usize) -> &'a str {
+        self.tokens[index].text(self.source)
+    }
+
+    fn is_punctuator(&self, index: usize, punctuator: &str) -> bool {
+        self.tokens
+            .get(index)
+            .is_some_and(|token| token.is_punctuator(self.source, punctuator))
+    }
+
+    fn unexpected(&self, index: usize) -> JsSyntaxError {
+        unexpected_end(self.source, &self.tokens, index)
+    }
+
+    // index of the closing bracket of a function body or parameter list
+    fn skip_to_matching(&self, open: usize) -> Result<usize, JsSyntaxError> {
+        matching(&self.tokens, self.source, open).ok_or_else(|| {
+            error(
+                "Unexpected end of input",
+                self.source.len(),
+                self.source.len(),
+            )
+        })
+    }
+
+    fn validate(&mut self) -> Result<(), JsSyntaxError> {
+        let mut index = 0;
+
+        while index < self.tokens.len() {
+            index = match self.state {
+                State::Operand { empty } => self.operand(index, empty)?,
+                State::Operator => self.operator(index)?,
+                State::Key => self.key(index)?,
+                State::AfterKey { shorthand } => self.after_key(index, shorthand)?,
+            };
+        }
+
+        let complete = match self.state {
+            State::Operand { empty } => empty,
+            State::Operator => true,
+            _ => false,
+        };
+        if !complete || !self.frames.is_empty() {
+            return Err(error(
+                "Unexpected end of input",
+                self.source.len(),
+                self.source.len(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    // returns the index of the next token to check
+    fn operand(&mut self, index: usize, empty: bool) -> Result<usize, JsSyntaxError> {
+        let token = self.tokens[index];
+        let text = self.text(index);
+
+        match token.kind {
+            JsTokenKind::Identifier
+            | JsTokenKind::Number
+            | JsTokenKind::String
+            | JsTokenKind::Regex => {
+                self.state = State::Operator;
+            }
+            JsTokenKind::Template => {
+                if text.starts_with('}') {
+                    return Err(self.unexpected(index));
+                }
+                self.template_head(text);
+            }
+            JsTokenKind::Keyword => match text {
+                "this" | "true" | "false" | "null" | "super" => self.state = State::Operator,
+                "typeof" | "void" | "delete" | "new" | "await" => {
+                    self.state = State::Operand { empty: false };
+                }
+                "function" => return self.function(index),
+                _ => return Err(self.unexpected(index)),
+            },
+            JsTokenKind::Punctuator => match text {
+                "(" => {
+                    // `() => body`
+                    if self.is_punctuator(index + 1, ")") {
+                        if !self.is_punctuator(index + 2, "=>") {
+                            return Err(self.unexpected(index + 1));
+                        }
+                        return self.arrow_body(index + 2);
+                    }
+                    self.frames.push(Frame::Paren { call: false });
+                    self.state = State::Operand { empty: false };
+                }
+                "[" => {
+                    self.frames.push(Frame::Array);
+                    self.state = State::Operand { empty: true };
+                }
+                "{" => {
+                    self.frames.push(Frame::Object);
+                    self.state = State::Key;
+                }
+                "!" | "~" | "+" | "-" | "++" | "--" => {
+                    self.state = State::Operand { empty: false };
+                }
+                "..." if matches!(self.frames.last(), Some(Frame::Paren { .. } | Frame::Array)) => {
+                    self.state = State::Operand { empty: false };
+                }
+                // array holes
+                "," if self.frames.last() == Some(&Frame::Array) => {}
+                ")" | "]" if empty => return self.close(index),
+                _ => return Err(self.unexpected(index)),
+            },
+        }
+
+        Ok(index + 1)
+    }
+
+    fn operator(&mut self, index: usize) -> Result<usize, JsSyntaxError> {
+        let token = self.tokens[index];
+        let text = self.text(index);
+
+        match token.kind {
+            JsTokenKind::Keyword if matches!(text, "in" | "instanceof") => {
+                self.state = State::Operand { empty: false };
+            }
+            // tagged template
+            JsTokenKind::Template if text.starts_with('`') => self.template_head(text),
+            JsTokenKind::Template => {
+                if self.frames.last() != Some(&Frame::Substitution) {
+                    return Err(self.unexpected(index));
+                }
+                if text.ends_with("${") {
+                    self.state = State::Operand { empty: false };
+                } else {
+                    self.frames.pop();
+                }
+            }
+            JsTokenKind::Punctuator => match text {
+                "=>" => {
+                    let previous = self.tokens[index - 1];
+                    if !(previous.kind == JsTokenKind::Identifier
+                        || previous.is_punctuator(self.source, ")"))
+                    {
+                        return Err(self.unexpected(index));
+                    }
+                    return self.arrow_body(index);
+                }
+                "?" => {
+                    self.frames.push(Frame::Ternary);
+                    self.state = State::Operand { empty: false };
+                }
+                ":" if self.frames.last() == Some(&Frame::Ternary) => {
+                    self.frames.pop();
+                    self.state = State::Operand { empty: false };
+                }
+                "." | "?." => {
+                    let is_name = self.tokens.get(index + 1).is_some_and(|token| {
+                        matches!(token.kind, JsTokenKind::Identifier | JsTokenKind::Keyword)
+                    });
+                    if is_name {
+                        return Ok(index + 2);
+                    }
+                    if text == "."
+                        || !(self.is_punctuator(index + 1, "(")
+                            || self.is_punctuator(index + 1, "["))
+                    {
+                        return Err(self.unexpected(index + 1));
+                    }
+                }
+                "(" => {
+                    self.frames.push(Frame::Paren { call: true });
+                    self.state = State::Operand { empty: true };
+                }
+                "[" => {
+                    self.frames.push(Frame::Index);
+                    self.state = State::Operand { empty: false };
+                }
+                "++" | "--" => {}
+                "," => {
+                    self.state = match self.frames.last() {
+                        Some(Frame::Object) => State::Key,
+                        Some(Frame::Paren { call: true } | Frame::Array) => {
+                            State::Operand { empty: true }
+                        }
+                        Some(Frame::Ternary | Frame::ComputedKey) => {
+                            return Err(self.unexpected(index))
+                        }
+                        _ => State::Operand { empty: false },
+                    };
+                }
+                ";" if self.frames.is_empty() => {
+                    // statements are only checked for balanced brackets
+                    if self.tokens.get(index + 1).is_some_and(|token| {
+                        token.kind == JsTokenKind::Keyword
+                            && STATEMENT_KEYWORDS.contains(&token.text(self.source))
+                    }) {
+                        return self.skip_statements(index + 1);
+                    }
+                    self.state = State::Operand { empty: true };
+                }
+                ")" | "]" | "}" => return self.close(index),
+                _ if BINARY_OPERATORS.contains(&text) => {
+                    self.state = State::Operand { empty: false };
+                }
+                _ => return Err(self.unexpected(index)),
+            },
+            _ => return Err(self.unexpected(index)),
+        }
+
+        Ok(index + 1)
+    }
+
+    fn key(&mut self, index: usize) -> Result<usize, JsSyntaxError> {
+        let token = self.tokens[index];
+
+        match token.kind {
+            JsTokenKind::Identifier => self.state = State::AfterKey { shorthand: true },
+            JsTokenKind::Keyword | JsTokenKind::String | JsTokenKind::Number => {
+                self.state = State::AfterKey { shorthand: false };
+            }
+            JsTokenKind::Punctuator => match self.text(index) {
+                "}" => return self.close(index),
+                "[" => {
+                    self.frames.push(Frame::ComputedKey);
+                    self.state = State::Operand { empty: false };
+                }
+                "..." => self.state = State::Operand { empty: false },
+                _ => return Err(self.unexpected(index)),
+            },
+            _ => return Err(self.unexpected(index)),
+        }
+
+        Ok(index + 1)
+    }
+
+    fn after_key(&mut self, index: usize, shorthand: bool) -> Result<usize, JsSyntaxError> {
+        match self.text(index) {
+            ":" => self.state = State::Operand { empty: false },
+            "," if shorthand => self.state = State::Key,
+            "}" if shorthand => return self.close(index),
+            // shorthand with default, only valid in destructuring patterns
+            "=" if shorthand => self.state = State::Operand { empty: false },
+            // method, `{ key() {} }`
+            "(" => {
+                let close = self.skip_to_matching(index)?;
+                if !self.is_punctuator(close + 1, "{") {
+                    return Err(self.unexpected(close + 1));
+                }
+                self.state = State::Operator;
+                return Ok(self.skip_to_matching(close + 1)? + 1);
+            }
+            _ => return Err(self.unexpected(index)),
+        }
+
+        Ok(index + 1)
+    }
+
+    fn close(&mut self, index: usize) -> Result<usize, JsSyntaxError> {
+        let text = self.text(index);
+        let state = match (self.frames.last(), text) {
+            (Some(Frame::Paren { .. }), ")")
+            | (Some(Frame::Array | Frame::Index), "]")
+            | (Some(Frame::Object), "}") => State::Operator,
+            (Some(Frame::ComputedKey), "]") => State::AfterKey { shorthand: false },
+            _ => return Err(self.unexpected(index)),
+        };
+
+        self.frames.pop();
+        self.state = state;
+        Ok(index + 1)
+    }
+
+    fn template_head(&mut self, text: &str) {
+        if text.len() > 1 && text.ends_with('`') {
+            self.state = State::Operator;
+        } else {
+            self.frames.push(Frame::Substitution);
+            self.state = State::Operand { empty: false };
+        }
+    }
+
+    // function name? ( params ) { body }
+    fn function(&mut self, index: usize) -> Result<usize, JsSyntaxError> {
+        let mut open = index + 1;
+        if self
+            .tokens
+            .get(open)
+            .is_some_and(|token| token.kind == JsTokenKind::Identifier)
+        {
+            open += 1;
+        }
+        if !self.is_punctuator(open, "(") {
+            return Err(self.unexpected(open));
+        }
+
+        let close = self.skip_to_matching(open)?;
+        if !self.is_punctuator(close + 1, "{") {
+            return Err(self.unexpected(close + 1));
+        }
+
+        self.state = State::Operator;
+        Ok(self.skip_to_matching(close + 1)? + 1)
+    }
+
+    // `arrow` is the index of `=>`
+    fn arrow_body(&mut self, arrow: usize) -> Result<usize, JsSyntaxError> {
+        if self.is_punctuator(arrow + 1, "{") {
+            self.state = State::Operator;
+            return Ok(self.skip_to_matching(arrow + 1)? + 1);
+        }
+
+        self.state = State::Operand { empty: false };
+        Ok(arrow + 1)
+    }
+
+    fn skip_statements(&mut self, index: usize) -> Result<usize, JsSyntaxError> {
+        let mut index = index;
+
+        while index < self.tokens.len() {
+            match self.text(index) {
+                "(" | "[" | "{" if self.tokens[index].kind == JsTokenKind::Punctuator => {
+                    index = self.skip_to_matching(index)?;
+                }
+                ")" | "]" | "}" if self.tokens[index].kind == JsTokenKind::Punctuator => {
+                    return Err(self.unexpected(index));
+                }
+                _ => {}
+            }
+            index += 1;
+        }
+
+        self.state = State::Operator;
+        Ok(index)
+    }
+}
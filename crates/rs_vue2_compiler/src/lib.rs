@@ -1,7 +1,19 @@
 pub mod ast_tree;
+pub mod codeframe;
+pub mod codegen;
+pub mod diagnostics;
 mod directives_model;
+mod error_detector;
 mod filter_parser;
 mod helpers;
+mod html_parser;
+mod js_lexer;
+mod js_validator;
+pub mod optimizer;
+pub mod scope;
+pub mod sfc;
+pub mod ssr;
+pub mod strip_with;
 mod text_parser;
 mod uni_codes;
 mod util;
@@ -10,24 +22,26 @@ pub mod web;
 
 extern crate lazy_static;
 
-use crate::ast_tree::{
-    create_ast_element, ASTElement, ASTElementKind, ASTNode, ASTTree, IfCondition,
-};
+use crate::ast_tree::{create_ast_element, ASTElementKind, ASTNode, ASTTree, IfCondition, Token};
+use crate::codegen::generate_with_warn;
+use crate::diagnostics::{CompilerDiagnostic, DiagnosticCode};
+use crate::error_detector::detect_errors;
+use crate::html_parser::HtmlParser;
+use crate::optimizer::optimize;
 use crate::text_parser::parse_text;
 use crate::uni_codes::{UC_TYPE, UC_V_FOR};
 use crate::util::{get_attribute_value, has_attribute};
-use crate::warn_logger::WarnLogger;
+use crate::warn_logger::{CollectingWarnLogger, WarnLogger};
 use crate::web::element::get_namespace;
+use indexmap::IndexMap;
 use lazy_static::lazy_static;
 use regex::Regex;
-use rs_html_parser::{Parser, ParserOptions};
 use rs_html_parser_tokenizer::TokenizerOptions;
-use rs_html_parser_tokens::{Token, TokenKind};
+use rs_html_parser_tokens::TokenKind;
 use std::cell::{RefCell, RefMut};
-use std::collections::VecDeque;
-use std::format;
+use std::collections::{HashMap, VecDeque};
+use std::mem::take;
 use std::rc::Rc;
-use unicase_collections::unicase_btree_map::UniCaseBTreeMap;
 
 lazy_static! {
     static ref INVALID_ATTRIBUTE_RE: Regex = Regex::new(r##"/[\s"'<>\/=]/"##).unwrap();
@@ -74,10 +88,27 @@ pub struct CompilerOptions {
     pub get_namespace: Option<fn(tag: &str) -> Option<&'static str>>,
     pub warn: Option<Box<dyn WarnLogger>>,
     pub delimiters: Option<(String, String)>,
+    // user defined key modifier aliases, the compile-time equivalent of Vue.config.keyCodes
+    pub key_codes: Option<HashMap<String, Vec<u32>>>,
+    // attribute of scoped styles added to every element, e.g. "data-v-7ba5bd90"
+    pub scope_id: Option<String>,
+    // render functions read the instance through `_vm.` instead of `with(this)`,
+    // required for strict mode, es modules and CSP
+    pub strip_with: bool,
 
     pub modules: Option<Vec<Box<dyn ModuleApi>>>,
 }
 
+pub struct CompiledResult {
+    pub ast: ASTTree,
+    pub render: String,
+    pub static_render_fns: Vec<String>,
+    pub errors: Vec<String>,
+    pub tips: Vec<String>,
+    /// structured form of `errors` and `tips`, in the order they were emitted
+    pub diagnostics: Vec<CompilerDiagnostic>,
+}
+
 pub trait ModuleApi {
     fn transform_node(&self, node: &mut ASTNode, options: &CompilerOptions);
     fn gen_data(&self, node: &ASTNode) -> Option<String>;
@@ -91,22 +122,18 @@ pub trait ModuleApi {
 }
 
 fn is_forbidden_tag(el: &Token) -> bool {
-    if &el.kind != &TokenKind::OpenTag {
+    if el.kind != TokenKind::OpenTag {
         return false;
     }
 
     match &*el.data {
         "style" => true,
         "script" => {
-            let attr_entry = get_attribute_value(el, &UC_TYPE);
-
-            if let Some(some_attr_entry) = attr_entry {
-                if let Some((val, _quotes)) = some_attr_entry {
-                    return val.as_ref() == "text/javascript";
-                }
+            if let Some(Some((val, _quotes))) = get_attribute_value(el, &UC_TYPE) {
+                return val.as_ref() == "text/javascript";
             }
 
-            return false;
+            false
         }
         _ => false,
     }
@@ -129,22 +156,27 @@ pub struct VueParser<'a> {
     get_namespace: fn(tag: &str) -> Option<&'static str>,
 
     active_text: Option<String>,
+    active_text_range: Option<(usize, usize)>,
     options: &'a CompilerOptions,
 }
 
-const PARSER_OPTIONS: ParserOptions = ParserOptions {
-    xml_mode: false,
-    tokenizer_options: TokenizerOptions {
-        xml_mode: Some(false),
-        decode_entities: Some(true),
-        ignore_whitespace_between_tags: Some(true),
-    },
+const TOKENIZER_OPTIONS: TokenizerOptions = TokenizerOptions {
+    xml_mode: Some(false),
+    decode_entities: Some(true),
+    ignore_whitespace_between_tags: Some(true),
 };
 
 impl<'a> VueParser<'a> {
     pub fn new(options: &'a CompilerOptions) -> VueParser<'a> {
+        VueParser::with_warn(
+            options,
+            options.warn.clone().unwrap_or_else(|| Box::new(print_line)),
+        )
+    }
+
+    fn with_warn(options: &'a CompilerOptions, warn: Box<dyn WarnLogger>) -> VueParser<'a> {
         VueParser {
-            options: &options,
+            options,
             dev: options.dev,
             is_pre_tag: options
                 .is_pre_tag
@@ -157,38 +189,49 @@ impl<'a> VueParser<'a> {
             whitespace_handling: options.whitespace_handling,
             preserve_comments: false,
             active_text: None,
-            warn: options.warn.clone().unwrap_or_else(|| Box::new(print_line)),
+            active_text_range: None,
+            warn,
         }
     }
 
-    fn warn_once(&mut self, msg: &str) {
+    fn warn_once(&mut self, diagnostic: CompilerDiagnostic) {
         if !self.warned {
             self.warned = true;
-            self.warn.call(msg);
+            self.warn.diagnostic(diagnostic);
         }
     }
 
-    fn check_root_constraints(&mut self, new_root: &ASTElement) {
+    fn check_root_constraints(&mut self, new_root: &ASTNode) {
         if self.warned {
             return;
         }
 
-        if new_root.token.data.eq_ignore_ascii_case("slot")
-            || new_root.token.data.eq_ignore_ascii_case("template")
-        {
-            self.warn_once(&format!(
-                "Cannot use <{}> as component root element because it may contain multiple nodes.",
-                new_root.token.data
+        let tag = &new_root.el.token.data;
+        if tag.eq_ignore_ascii_case("slot") || tag.eq_ignore_ascii_case("template") {
+            self.warn_once(new_root.locate(
+                CompilerDiagnostic::new(DiagnosticCode::RootSlotOrTemplate).arg(tag.as_ref()),
             ))
         }
-        if has_attribute(&new_root.token, &UC_V_FOR) {
-            self.warn_once("Cannot use v-for on stateful component root element because it renders multiple elements.")
+        if has_attribute(&new_root.el.token, &UC_V_FOR) {
+            self.warn_once(
+                new_root.locate(CompilerDiagnostic::new(DiagnosticCode::RootVFor).attr("v-for")),
+            )
         }
     }
 
     pub fn parse(&mut self, template: &str) -> ASTTree {
-        let parser = Parser::new(template, &PARSER_OPTIONS);
+        self.parse_at(template, 0)
+    }
+
+    /// Parses a template embedded in a larger file, source ranges are shifted by `offset`.
+    pub(crate) fn parse_at(&mut self, template: &str, offset: usize) -> ASTTree {
         let is_dev = self.dev;
+        let parser = HtmlParser::new(
+            template,
+            offset,
+            &TOKENIZER_OPTIONS,
+            is_dev.then(|| self.warn.clone_box()),
+        );
 
         let mut root_tree: ASTTree = ASTTree::new(is_dev, self.warn.clone_box());
         let mut stack: VecDeque<usize> = VecDeque::new();
@@ -200,6 +243,9 @@ impl<'a> VueParser<'a> {
                 TokenKind::OpenTag => {
                     self.end_text_node(&mut root_tree, current_parent_id);
 
+                    let mut token = token;
+                    let raw_attrs = take(&mut token.raw_attrs);
+                    let start = token.start;
                     let mut node_rc = root_tree.create(
                         create_ast_element(token, ASTElementKind::Element),
                         current_parent_id,
@@ -210,6 +256,20 @@ impl<'a> VueParser<'a> {
                     let node_id = node.id;
                     root_tree.set(node_id, node_rc.clone());
 
+                    node.el.start = start;
+                    for raw_attr in raw_attrs {
+                        if is_dev {
+                            node.check_duplicate_attr(
+                                &raw_attr,
+                                self.options.v_bind_prop_short_hand,
+                            );
+                        }
+                        node.el.raw_attrs_map.insert(raw_attr);
+                    }
+                    if is_dev {
+                        node.check_case_insensitive_attrs();
+                    }
+
                     let ns = if let Some(parent_ns) = current_namespace {
                         Some(parent_ns)
                     } else {
@@ -223,10 +283,15 @@ impl<'a> VueParser<'a> {
 
                     if is_dev {
                         if let Some(attrs) = &node.el.token.attrs {
-                            for (attr_key, _attr_value) in attrs {
-                                if INVALID_ATTRIBUTE_RE.find(&attr_key).is_some() {
-                                    self.warn.call(
-                                        "Invalid dynamic argument expression: attribute names cannot contain spaces, quotes, <, >, / or =."
+                            for (attr_key, _attr_value) in attrs.iter() {
+                                if INVALID_ATTRIBUTE_RE.find(attr_key).is_some() {
+                                    self.warn.diagnostic(
+                                        node.locate(
+                                            CompilerDiagnostic::new(
+                                                DiagnosticCode::InvalidDynamicArgument,
+                                            )
+                                            .attr(attr_key),
+                                        ),
                                     )
                                 }
                             }
@@ -237,13 +302,11 @@ impl<'a> VueParser<'a> {
                         node.el.forbidden = true;
 
                         if is_dev {
-                            // TODO: add tag
-                            self.warn.call(
-                                "
-            Templates should only be responsible for mapping the state to the
-            UI. Avoid placing tags with side-effects in your templates, such as
-            <{tag}> as they will not be parsed.
-                ",
+                            self.warn.diagnostic(
+                                node.locate(
+                                    CompilerDiagnostic::new(DiagnosticCode::ForbiddenTag)
+                                        .arg(node.el.token.data.as_ref()),
+                                ),
                             )
                         }
                     }
@@ -290,10 +353,14 @@ impl<'a> VueParser<'a> {
                     if let Some(open_tag_id) = current_open_tag_id {
                         let node_ptr = root_tree.get(open_tag_id).unwrap();
                         let mut node = node_ptr.borrow_mut();
+                        if let Some(start) = token.start {
+                            node.el.start.get_or_insert(start);
+                        }
+                        node.el.end = token.end;
                         // trim white space ??
 
                         if !self.in_v_pre && !node.el.processed {
-                            node.process_element(&root_tree, &self.options);
+                            node.process_element(&root_tree, self.options);
                         }
                         // tree management
                         if stack.is_empty() && node.id != 1 {
@@ -301,7 +368,7 @@ impl<'a> VueParser<'a> {
                                 && (node.el.else_if_val.is_some() || node.el.is_else)
                             {
                                 if is_dev {
-                                    self.check_root_constraints(&node.el);
+                                    self.check_root_constraints(&node);
                                 }
                                 let else_if_val = node.el.else_if_val.clone();
                                 let node_id = node.id;
@@ -311,7 +378,9 @@ impl<'a> VueParser<'a> {
                                     block_id: node_id,
                                 });
                             } else if is_dev {
-                                self.warn.call("Component template should contain exactly one root element. If you are using v-if on multiple elements, use v-else-if to chain them instead.");
+                                self.warn.diagnostic(node.locate(CompilerDiagnostic::new(
+                                    DiagnosticCode::MultipleRootElements,
+                                )));
                             }
                         }
                         let mut current_parent =
@@ -320,7 +389,7 @@ impl<'a> VueParser<'a> {
                         // always take root node, even if forbidden
                         if !node.el.forbidden || node.id == 1 {
                             if is_dev && node.id == 1 {
-                                self.check_root_constraints(&node.el);
+                                self.check_root_constraints(&node);
                             }
                             if node.el.else_if_val.is_some() || node.el.is_else {
                                 node.process_if_conditions(
@@ -335,7 +404,7 @@ impl<'a> VueParser<'a> {
                                     let scoped_slots = current_parent
                                         .el
                                         .scoped_slots
-                                        .get_or_insert(UniCaseBTreeMap::new());
+                                        .get_or_insert_with(IndexMap::new);
 
                                     let slot_target = node.el.slot_target.clone();
                                     let name = if let Some(slot_target) = slot_target {
@@ -363,7 +432,7 @@ impl<'a> VueParser<'a> {
                         node.children = node
                             .children
                             .iter()
-                            .map(|child| Rc::clone(child))
+                            .map(Rc::clone)
                             .filter_map(|child_rc| {
                                 let child = child_rc.borrow_mut();
                                 if child.el.slot_scope.is_none() {
@@ -404,6 +473,8 @@ impl<'a> VueParser<'a> {
                         );
                         let mut node = node_rc.borrow_mut();
                         node.el.is_comment = true;
+                        node.el.start = node.el.token.start;
+                        node.el.end = node.el.token.end;
                         root_tree.set(node.id, node_rc.clone());
                         current_parent_id = node.id;
                         stack.push_back(node.id);
@@ -422,19 +493,25 @@ impl<'a> VueParser<'a> {
                     }
                 }
                 TokenKind::Text => {
+                    let range = token.start.zip(token.end);
                     if current_parent_id == 0 {
                         if is_dev {
-                            if &token.data.as_ref() == &template {
-                                self.warn.call("Component template requires a root element, rather than just text.")
+                            let diagnostic = if token.data.as_ref() == template {
+                                Some(CompilerDiagnostic::new(DiagnosticCode::RootTextOnly))
                             } else {
                                 let text_trimmed = token.data.trim();
 
-                                if !text_trimmed.is_empty() {
-                                    self.warn.call(&format!(
-                                        "text \"{}\" outside root element will be ignored.",
-                                        text_trimmed
-                                    ));
+                                (!text_trimmed.is_empty()).then(|| {
+                                    CompilerDiagnostic::new(DiagnosticCode::TextOutsideRoot)
+                                        .arg(text_trimmed)
+                                })
+                            };
+
+                            if let Some(mut diagnostic) = diagnostic {
+                                if let Some((start, end)) = range {
+                                    diagnostic = diagnostic.range(start, end);
                                 }
+                                self.warn.diagnostic(diagnostic);
                             }
                         }
 
@@ -444,6 +521,11 @@ impl<'a> VueParser<'a> {
                     let text = self.condense_whitespace(&root_tree, current_parent_id, &token.data);
 
                     if !text.is_empty() {
+                        if let Some((start, end)) = range {
+                            let active_start =
+                                self.active_text_range.map_or(start, |active| active.0);
+                            self.active_text_range = Some((active_start, end));
+                        }
                         if let Some(active_text) = &mut self.active_text {
                             *active_text += &text;
                         } else {
@@ -455,6 +537,10 @@ impl<'a> VueParser<'a> {
             }
         }
 
+        if let Some(scope_id) = &self.options.scope_id {
+            root_tree.add_scope_id(scope_id);
+        }
+
         root_tree
     }
 
@@ -484,6 +570,9 @@ impl<'a> VueParser<'a> {
                                 attrs: None,
                                 kind: TokenKind::Text,
                                 is_implied: false,
+                                start: None,
+                                end: None,
+                                raw_attrs: vec![],
                             },
                             ASTElementKind::Expression,
                         ),
@@ -502,6 +591,9 @@ impl<'a> VueParser<'a> {
                                 attrs: None,
                                 kind: TokenKind::Text,
                                 is_implied: false,
+                                start: None,
+                                end: None,
+                                raw_attrs: vec![],
                             },
                             ASTElementKind::Text,
                         ),
@@ -512,6 +604,9 @@ impl<'a> VueParser<'a> {
                     node = node_rc.borrow_mut();
                 }
 
+                node.el.start = self.active_text_range.map(|range| range.0);
+                node.el.end = self.active_text_range.map(|range| range.1);
+
                 root_tree
                     .get(current_parent_id)
                     .unwrap()
@@ -522,6 +617,7 @@ impl<'a> VueParser<'a> {
             }
 
             self.active_text = None;
+            self.active_text_range = None;
         }
     }
 
@@ -563,3 +659,41 @@ impl<'a> VueParser<'a> {
         };
     }
 }
+
+/**
+ * Compile a template into render function code, static sub-trees are hoisted into `static_render_fns`.
+ * Warnings are collected into `errors` and `tips` instead of being passed to `options.warn`.
+ */
+pub fn compile(template: &str, options: &CompilerOptions) -> CompiledResult {
+    compile_at(template, 0, options)
+}
+
+pub(crate) fn compile_at(
+    template: &str,
+    offset: usize,
+    options: &CompilerOptions,
+) -> CompiledResult {
+    let logger = CollectingWarnLogger::default();
+
+    let mut ast =
+        VueParser::with_warn(options, Box::new(logger.clone())).parse_at(template, offset);
+    if options.dev {
+        detect_errors(&ast);
+    }
+    let code = if options.is_ssr {
+        ssr::optimizer::optimize(&mut ast);
+        ssr::codegen::generate_with_warn(&ast, options, Box::new(logger.clone()))
+    } else {
+        optimize(&ast, options);
+        generate_with_warn(&ast, options, Box::new(logger.clone()))
+    };
+
+    CompiledResult {
+        ast,
+        render: code.render,
+        static_render_fns: code.static_render_fns,
+        errors: logger.errors.take(),
+        tips: logger.tips.take(),
+        diagnostics: logger.diagnostics.take(),
+    }
+}
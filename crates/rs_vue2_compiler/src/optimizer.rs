@@ -0,0 +1,231 @@
+use crate::ast_tree::{ASTElement, ASTElementKind, ASTNode, ASTTree};
+use crate::web::element::is_reserved_tag;
+use crate::CompilerOptions;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+const BASE_STATIC_KEYS: [&str; 11] = [
+    "type",
+    "tag",
+    "attrsList",
+    "attrsMap",
+    "plain",
+    "parent",
+    "children",
+    "attrs",
+    "start",
+    "end",
+    "rawAttrsMap",
+];
+
+/**
+ * Goal of the optimizer: walk the generated template AST tree
+ * and detect sub-trees that are purely static, i.e. parts of
+ * the DOM that never needs to change.
+ *
+ * Once we detect these sub-trees, we can:
+ *
+ * 1. Hoist them into constants, so that we no longer need to
+ *    create fresh nodes for them on each re-render;
+ * 2. Completely skip them in the patching process.
+ */
+pub fn optimize(tree: &ASTTree, options: &CompilerOptions) {
+    let root = tree.wrapper.borrow().children.first().cloned();
+
+    if let Some(root) = root {
+        let optimizer = Optimizer::new(tree, options);
+
+        optimizer.mark_static(&root);
+        // second pass: mark static roots.
+        optimizer.mark_static_roots(&root, false);
+    }
+}
+
+struct Optimizer<'a> {
+    tree: &'a ASTTree,
+    static_keys: Vec<&'static str>,
+}
+
+impl<'a> Optimizer<'a> {
+    fn new(tree: &'a ASTTree, options: &CompilerOptions) -> Optimizer<'a> {
+        let mut static_keys = BASE_STATIC_KEYS.to_vec();
+
+        if let Some(registered_modules) = &options.modules {
+            for module in registered_modules {
+                static_keys.extend(module.static_keys());
+            }
+        }
+
+        Optimizer { tree, static_keys }
+    }
+
+    fn if_blocks(&self, node: &ASTNode) -> Vec<Rc<RefCell<ASTNode>>> {
+        node.el
+            .if_conditions
+            .iter()
+            .flatten()
+            .skip(1)
+            .filter_map(|condition| self.tree.get(condition.block_id).cloned())
+            .collect()
+    }
+
+    fn mark_static(&self, node_rc: &Rc<RefCell<ASTNode>>) {
+        let (children, if_blocks) = {
+            let mut node = node_rc.borrow_mut();
+            node.el.is_static = self.is_static(&node);
+
+            if node.el.kind != ASTElementKind::Element {
+                return;
+            }
+
+            // do not make component slot content static. this avoids
+            // 1. components not able to mutate slot nodes
+            // 2. static slot content fails for hot-reloading
+            let tag = &node.el.token.data;
+            if !is_reserved_tag(tag)
+                && !tag.eq_ignore_ascii_case("slot")
+                && !node.has_raw_attr("inline-template")
+            {
+                return;
+            }
+
+            (node.children.clone(), self.if_blocks(&node))
+        };
+
+        let mut is_static = true;
+        for child in children.iter().chain(if_blocks.iter()) {
+            self.mark_static(child);
+            if !child.borrow().el.is_static {
+                is_static = false;
+            }
+        }
+
+        if !is_static {
+            node_rc.borrow_mut().el.is_static = false;
+        }
+    }
+
+    fn mark_static_roots(&self, node_rc: &Rc<RefCell<ASTNode>>, is_in_for: bool) {
+        let (children, if_blocks, children_in_for) = {
+            let mut node = node_rc.borrow_mut();
+
+            if node.el.kind != ASTElementKind::Element {
+                return;
+            }
+
+            if node.el.is_static || node.el.once {
+                node.el.static_in_for = is_in_for;
+            }
+
+            // For a node to qualify as a static root, it should have children that
+            // are not just static text. Otherwise the cost of hoisting out will
+            // outweigh the benefits and it's better off to just always render it fresh.
+            let has_only_text_child = node.children.len() == 1
+                && node.children[0].borrow().el.kind == ASTElementKind::Text;
+            if node.el.is_static && !node.children.is_empty() && !has_only_text_child {
+                node.el.static_root = true;
+                return;
+            }
+            node.el.static_root = false;
+
+            (
+                node.children.clone(),
+                self.if_blocks(&node),
+                is_in_for || node.el.for_value.is_some(),
+            )
+        };
+
+        for child in &children {
+            self.mark_static_roots(child, children_in_for);
+        }
+        for block in &if_blocks {
+            self.mark_static_roots(block, is_in_for);
+        }
+    }
+
+    fn is_static(&self, node: &ASTNode) -> bool {
+        match node.el.kind {
+            // expression
+            ASTElementKind::Expression => false,
+            // text
+            ASTElementKind::Text => true,
+            _ => {
+                let tag = &node.el.token.data;
+
+                node.el.pre
+                    || (!node.el.has_bindings // no dynamic bindings
+                        && node.el.if_val.is_none()
+                        && node.el.for_value.is_none() // not v-if or v-for or v-else
+                        && !tag.eq_ignore_ascii_case("slot")
+                        && !tag.eq_ignore_ascii_case("component") // not a built-in
+                        && is_reserved_tag(tag) // not a component
+                        && !is_direct_child_of_template_for(node)
+                        && element_keys(&node.el)
+                            .iter()
+                            .all(|key| self.static_keys.contains(key)))
+            }
+        }
+    }
+}
+
+fn is_direct_child_of_template_for(node: &ASTNode) -> bool {
+    let mut current_node = node
+        .parent
+        .as_ref()
+        .and_then(|parent_weak| parent_weak.upgrade());
+
+    while let Some(parent_rc) = current_node {
+        let parent = parent_rc.borrow();
+        if !parent.el.token.data.eq_ignore_ascii_case("template") {
+            return false;
+        }
+        if parent.el.for_value.is_some() {
+            return true;
+        }
+
+        current_node = parent
+            .parent
+            .as_ref()
+            .and_then(|parent_weak| parent_weak.upgrade());
+    }
+
+    false
+}
+
+// the element properties that would be present on a vue-template-compiler AST node
+fn element_keys(el: &ASTElement) -> Vec<&'static str> {
+    let mut keys = vec![];
+
+    let mut add_key = |key: &'static str, present: bool| {
+        if present {
+            keys.push(key);
+        }
+    };
+
+    add_key("ns", el.ns.is_some());
+    add_key("forbidden", el.forbidden);
+    add_key("processed", el.processed);
+    add_key("key", el.key.is_some());
+    add_key("ref", el.ref_val.is_some());
+    add_key("refInFor", el.ref_in_for);
+    add_key("once", el.once);
+    add_key("else", el.is_else);
+    add_key("elseif", el.else_if_val.is_some());
+    add_key("slotName", el.slot_name.is_some());
+    add_key("slotTarget", el.slot_target.is_some());
+    add_key("slotScope", el.slot_scope.is_some());
+    add_key("scopedSlots", el.scoped_slots.is_some());
+    add_key("component", el.component.is_some());
+    add_key("inlineTemplate", el.inline_template);
+    add_key("dynamicAttrs", !el.dynamic_attrs.is_empty());
+    add_key("props", !el.props.is_empty());
+    add_key("directives", el.directives.is_some());
+    add_key("events", el.events.is_some());
+    add_key("nativeEvents", el.native_events.is_some());
+    add_key("staticClass", el.static_class.is_some());
+    add_key("classBinding", el.class_binding.is_some());
+    add_key("staticStyle", el.static_style.is_some());
+    add_key("styleBinding", el.style_binding.is_some());
+
+    keys
+}
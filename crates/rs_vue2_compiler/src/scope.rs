@@ -0,0 +1,243 @@
+use crate::ast_tree::{ASTElementKind, ASTNode, EMPTY_SLOT_SCOPE_TOKEN};
+use crate::strip_with::{free_identifiers, pattern_bindings};
+
+// runtime helpers referenced by processed expressions, e.g. `_f("filter")(value)`
+const RENDER_HELPERS: [&str; 18] = [
+    "_c", "_o", "_n", "_s", "_l", "_t", "_q", "_i", "_m", "_f", "_k", "_b", "_v", "_e", "_u", "_g",
+    "_d", "_p",
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ScopeBindingKind {
+    ForAlias,
+    ForIterator,
+    SlotScope,
+    Event,
+}
+
+/// Template local visible to the expressions of a node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeBinding {
+    pub name: String,
+    pub kind: ScopeBindingKind,
+    /// id of the node introducing the binding
+    pub node_id: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpressionScope {
+    /// attribute the expression comes from, e.g. `v-if`, `:title` or `@click`,
+    /// `{{}}` for interpolations
+    pub source: String,
+    pub expression: String,
+    /// template locals read by the expression
+    pub locals: Vec<String>,
+    /// identifiers read from the component instance
+    pub instance_reads: Vec<String>,
+}
+
+/**
+ * Returns the template locals in scope at `node`, innermost first: `v-for` aliases and iterators
+ * (destructured ones included) and `slot-scope` / `v-slot` params of the node and its ancestors.
+ * `$event` is only in scope inside handlers, see `expression_scopes`.
+ */
+pub fn scope_bindings(node: &ASTNode) -> Vec<ScopeBinding> {
+    let mut bindings = own_bindings(node);
+
+    let mut current_node = node
+        .parent
+        .as_ref()
+        .and_then(|parent_weak| parent_weak.upgrade());
+    while let Some(parent) = current_node {
+        bindings.extend(own_bindings(&parent.borrow()));
+        current_node = parent
+            .borrow()
+            .parent
+            .as_ref()
+            .and_then(|parent_weak| parent_weak.upgrade());
+    }
+
+    bindings
+}
+
+fn own_bindings(node: &ASTNode) -> Vec<ScopeBinding> {
+    let mut bindings = vec![];
+    let mut push = |pattern: &str, kind: ScopeBindingKind| {
+        bindings.extend(
+            pattern_bindings(pattern)
+                .into_iter()
+                .map(|name| ScopeBinding {
+                    name,
+                    kind,
+                    node_id: node.id,
+                }),
+        );
+    };
+
+    if node.el.for_value.is_some() {
+        if let Some(alias) = &node.el.alias {
+            push(alias, ScopeBindingKind::ForAlias);
+        }
+        for iterator in [&node.el.iterator1, &node.el.iterator2]
+            .into_iter()
+            .flatten()
+        {
+            push(iterator, ScopeBindingKind::ForIterator);
+        }
+    }
+    if let Some(slot_scope) = &node.el.slot_scope {
+        if slot_scope != EMPTY_SLOT_SCOPE_TOKEN {
+            push(slot_scope, ScopeBindingKind::SlotScope);
+        }
+    }
+
+    bindings
+}
+
+/**
+ * Lists the expressions of a node together with the template locals and the component instance
+ * properties they read. Expressions which can't be tokenized are left out.
+ */
+pub fn expression_scopes(node: &ASTNode) -> Vec<ExpressionScope> {
+    let bindings = scope_bindings(node);
+    let mut res = vec![];
+    let mut add = |source: String, expression: &str, bindings: &[ScopeBinding]| {
+        if let Some(scope) = analyze_expression(source, expression, bindings) {
+            res.push(scope);
+        }
+    };
+
+    if node.el.kind == ASTElementKind::Expression {
+        if let Some(expression) = &node.el.expression {
+            add("{{}}".to_string(), expression, &bindings);
+        }
+        return res;
+    }
+    if node.el.kind != ASTElementKind::Element {
+        return res;
+    }
+
+    // the iterated list is evaluated outside of the loop
+    if let Some(for_value) = &node.el.for_value {
+        let outer: Vec<_> = bindings
+            .iter()
+            .filter(|binding| {
+                binding.node_id != node.id
+                    || !matches!(
+                        binding.kind,
+                        ScopeBindingKind::ForAlias | ScopeBindingKind::ForIterator
+                    )
+            })
+            .cloned()
+            .collect();
+        add("v-for".to_string(), for_value, &outer);
+    }
+    if let Some(if_val) = &node.el.if_val {
+        add("v-if".to_string(), if_val, &bindings);
+    }
+    if let Some(else_if_val) = &node.el.else_if_val {
+        add("v-else-if".to_string(), else_if_val, &bindings);
+    }
+
+    let bindings_values = [
+        ("key", &node.el.key),
+        ("ref", &node.el.ref_val),
+        ("slot", &node.el.slot_target),
+        ("is", &node.el.component),
+    ];
+    for (name, value) in bindings_values {
+        if let Some(value) = value {
+            if !node.is_literal_binding(name) {
+                add(format!(":{}", name), value, &bindings);
+            }
+        }
+    }
+    if let Some(class_binding) = &node.el.class_binding {
+        add(":class".to_string(), class_binding, &bindings);
+    }
+    if let Some(style_binding) = &node.el.style_binding {
+        add(":style".to_string(), style_binding, &bindings);
+    }
+
+    let attrs = node
+        .el
+        .attrs
+        .iter()
+        .chain(node.el.dynamic_attrs.iter())
+        .chain(node.el.props.iter());
+    for attr in attrs.filter(|attr| !attr.literal) {
+        let source = if attr.dynamic {
+            add(format!(":[{}]", attr.name), &attr.name, &bindings);
+            format!(":[{}]", attr.name)
+        } else {
+            format!(":{}", attr.name)
+        };
+        if let Some(value) = &attr.value {
+            add(source, value, &bindings);
+        }
+    }
+
+    for dir in node.el.directives.iter().flatten() {
+        if let Some(value) = &dir.value {
+            add(dir.raw_name.clone(), value, &bindings);
+        }
+        if let Some(arg) = dir.arg.as_ref().filter(|_| dir.is_dynamic_arg) {
+            add(dir.raw_name.clone(), arg, &bindings);
+        }
+    }
+
+    let mut handler_bindings = vec![ScopeBinding {
+        name: "$event".to_string(),
+        kind: ScopeBindingKind::Event,
+        node_id: node.id,
+    }];
+    handler_bindings.extend(bindings.iter().cloned());
+
+    let events = node
+        .el
+        .events
+        .iter()
+        .flatten()
+        .map(|(name, handlers)| (format!("@{}", name), handlers));
+    let native_events = node
+        .el
+        .native_events
+        .iter()
+        .flatten()
+        .map(|(name, handlers)| (format!("@{}.native", name), handlers));
+    for (source, handlers) in events.chain(native_events) {
+        for handler in handlers {
+            add(source.clone(), &handler.value, &handler_bindings);
+        }
+    }
+
+    res
+}
+
+fn analyze_expression(
+    source: String,
+    expression: &str,
+    bindings: &[ScopeBinding],
+) -> Option<ExpressionScope> {
+    let mut locals: Vec<String> = vec![];
+    let mut instance_reads: Vec<String> = vec![];
+
+    for identifier in free_identifiers(expression, &RENDER_HELPERS)? {
+        let name = identifier.name;
+        let target = if bindings.iter().any(|binding| binding.name == name) {
+            &mut locals
+        } else {
+            &mut instance_reads
+        };
+        if !target.contains(&name) {
+            target.push(name);
+        }
+    }
+
+    Some(ExpressionScope {
+        source,
+        expression: expression.to_string(),
+        locals,
+        instance_reads,
+    })
+}
@@ -0,0 +1,219 @@
+pub mod style;
+
+use crate::ast_tree::ASTTree;
+use crate::diagnostics::{CompilerDiagnostic, DiagnosticCode};
+use crate::{compile_at, CompiledResult, CompilerOptions, VueParser};
+use rs_html_parser_tokenizer::{Tokenizer, TokenizerOptions};
+use rs_html_parser_tokenizer_tokens::TokenizerTokenLocation;
+use unicase_collections::unicase_btree_map::UniCaseBTreeMap;
+
+const TOKENIZER_OPTIONS: TokenizerOptions = TokenizerOptions {
+    xml_mode: Some(false),
+    // attribute values and block contents are kept as written
+    decode_entities: Some(false),
+    ignore_whitespace_between_tags: Some(false),
+};
+
+/// A top level block of a `.vue` file.
+#[derive(Debug, Clone)]
+pub struct SFCBlock {
+    /// `template`, `script`, `style` or the tag name of a custom block
+    pub block_type: String,
+    pub content: String,
+    /// attributes as written, `None` for attributes without a value
+    pub attrs: UniCaseBTreeMap<Option<String>>,
+    pub lang: Option<String>,
+    pub src: Option<String>,
+    pub scoped: bool,
+    /// name of the css module binding, a bare `module` attribute binds to `$style`
+    pub module: Option<String>,
+    pub functional: bool,
+    /// range of `content`, byte offsets into the file
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SFCDescriptor {
+    pub template: Option<SFCBlock>,
+    pub script: Option<SFCBlock>,
+    pub styles: Vec<SFCBlock>,
+    pub custom_blocks: Vec<SFCBlock>,
+    pub errors: Vec<CompilerDiagnostic>,
+}
+
+impl SFCDescriptor {
+    /// Parses the template block, node ranges point into the `.vue` file.
+    pub fn parse_template(&self, options: &CompilerOptions) -> Option<ASTTree> {
+        let template = self.template.as_ref()?;
+
+        Some(VueParser::new(options).parse_at(&template.content, template.start))
+    }
+
+    /// Compiles the template block, diagnostic ranges point into the `.vue` file.
+    pub fn compile_template(&self, options: &CompilerOptions) -> Option<CompiledResult> {
+        let template = self.template.as_ref()?;
+
+        Some(compile_at(&template.content, template.start, options))
+    }
+}
+
+struct OpenBlock {
+    block_type: String,
+    tag_start: usize,
+    attrs: UniCaseBTreeMap<Option<String>>,
+    content_start: Option<usize>,
+    // nested tags with the same name as the block, e.g. <template> inside <template>
+    depth: usize,
+    nested_open: bool,
+}
+
+/**
+ * Splits a single file component into its top level blocks, same as parseComponent in
+ * vue-template-compiler. Block contents are kept verbatim, only the template is parsed further.
+ */
+pub fn parse_component(source: &str) -> SFCDescriptor {
+    let bytes = source.as_bytes();
+    let mut descriptor = SFCDescriptor::default();
+    let mut block: Option<OpenBlock> = None;
+    let mut attr: Option<(String, Option<String>)> = None;
+
+    for token in Tokenizer::new(bytes, &TOKENIZER_OPTIONS) {
+        let current = match block.as_mut() {
+            Some(current) => current,
+            None => {
+                if token.location == TokenizerTokenLocation::OpenTagName {
+                    block = Some(OpenBlock {
+                        block_type: source[token.start..token.end].to_ascii_lowercase(),
+                        tag_start: source[..token.start].rfind('<').unwrap_or(token.start),
+                        attrs: UniCaseBTreeMap::new(),
+                        content_start: None,
+                        depth: 0,
+                        nested_open: false,
+                    });
+                }
+                continue;
+            }
+        };
+
+        if current.content_start.is_none() {
+            // still inside the start tag of the block
+            match token.location {
+                TokenizerTokenLocation::AttrName => {
+                    attr = Some((source[token.start..token.end].to_string(), None));
+                }
+                TokenizerTokenLocation::AttrData => {
+                    if let Some((_, value)) = attr.as_mut() {
+                        value
+                            .get_or_insert_with(String::new)
+                            .push_str(&source[token.start..token.end]);
+                    }
+                }
+                TokenizerTokenLocation::AttrEnd => {
+                    if let Some((name, value)) = attr.take() {
+                        if !current.attrs.contains_key(name.as_str()) {
+                            current.attrs.insert(name, value);
+                        }
+                    }
+                }
+                TokenizerTokenLocation::OpenTagEnd => {
+                    current.content_start = Some(token.start + 1);
+                }
+                TokenizerTokenLocation::SelfClosingTag => {
+                    let end = token.start + 1;
+                    let current = block.take().unwrap();
+                    add_block(&mut descriptor, current, source, end, end);
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        match token.location {
+            TokenizerTokenLocation::OpenTagName => {
+                current.nested_open =
+                    source[token.start..token.end].eq_ignore_ascii_case(&current.block_type);
+            }
+            TokenizerTokenLocation::OpenTagEnd => {
+                if current.nested_open {
+                    current.depth += 1;
+                }
+                current.nested_open = false;
+            }
+            TokenizerTokenLocation::SelfClosingTag => {
+                current.nested_open = false;
+            }
+            TokenizerTokenLocation::CloseTag
+                if source[token.start..token.end].eq_ignore_ascii_case(&current.block_type) =>
+            {
+                if current.depth > 0 {
+                    current.depth -= 1;
+                } else {
+                    let end = source[..token.start].rfind('<').unwrap_or(token.start);
+                    let current = block.take().unwrap();
+                    let content_start = current.content_start.unwrap_or(end);
+                    add_block(&mut descriptor, current, source, content_start, end);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(current) = block {
+        descriptor.errors.push(
+            CompilerDiagnostic::new(DiagnosticCode::MissingEndTag)
+                .arg(current.block_type.as_str())
+                .tag(current.block_type.as_str())
+                .range(current.tag_start, source.len()),
+        );
+
+        let start = current.content_start.unwrap_or(source.len());
+        add_block(&mut descriptor, current, source, start, source.len());
+    }
+
+    descriptor
+}
+
+fn add_block(
+    descriptor: &mut SFCDescriptor,
+    block: OpenBlock,
+    source: &str,
+    start: usize,
+    end: usize,
+) {
+    let tag_start = block.tag_start;
+    let attr = |name: &str| block.attrs.get(name).cloned();
+    let module = attr("module").map(|module| module.unwrap_or_else(|| "$style".to_string()));
+
+    let sfc_block = SFCBlock {
+        content: source[start..end].to_string(),
+        lang: attr("lang").flatten(),
+        src: attr("src").flatten(),
+        scoped: block.attrs.contains_key("scoped"),
+        module,
+        functional: block.attrs.contains_key("functional"),
+        start,
+        end,
+        block_type: block.block_type,
+        attrs: block.attrs,
+    };
+
+    let slot = match sfc_block.block_type.as_str() {
+        "template" => &mut descriptor.template,
+        "script" => &mut descriptor.script,
+        "style" => return descriptor.styles.push(sfc_block),
+        _ => return descriptor.custom_blocks.push(sfc_block),
+    };
+
+    if slot.is_none() {
+        *slot = Some(sfc_block);
+        return;
+    }
+
+    descriptor.errors.push(
+        CompilerDiagnostic::new(DiagnosticCode::DuplicateBlock)
+            .arg(sfc_block.block_type.as_str())
+            .tag(sfc_block.block_type.as_str())
+            .range(tag_start, start),
+    );
+}
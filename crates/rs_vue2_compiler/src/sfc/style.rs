@@ -0,0 +1,657 @@
+use crate::ast_tree::ASTTree;
+use crate::diagnostics::{CompilerDiagnostic, DiagnosticCode};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+
+lazy_static! {
+    static ref KEYFRAMES_RE: Regex =
+        Regex::new(r"(?i)^@((?:-\w+-)?keyframes)(\s+)([^\s{]+)").unwrap();
+    static ref ANIMATION_NAME_RE: Regex = Regex::new(r"(?i)^(-\w+-)?animation-name$").unwrap();
+    static ref ANIMATION_RE: Regex = Regex::new(r"(?i)^(-\w+-)?animation$").unwrap();
+    static ref WHITESPACE_RE: Regex = Regex::new(r"\s+").unwrap();
+}
+
+// at-rules containing style rules, everything else is copied as is
+const CONDITIONAL_AT_RULES: [&str; 6] = [
+    "media",
+    "supports",
+    "document",
+    "-moz-document",
+    "container",
+    "layer",
+];
+
+pub const DEFAULT_SCOPED_NAME: &str = "[name]__[local]___[hash:5]";
+
+pub struct StyleCompileOptions<'a> {
+    pub source: &'a str,
+    /// scope attribute, e.g. `data-v-7ba5bd90`
+    pub id: &'a str,
+    pub scoped: bool,
+    pub modules: Option<CssModulesOptions<'a>>,
+}
+
+pub struct CssModulesOptions<'a> {
+    /// path of the component, `[name]` is its file name without the extension
+    pub filename: &'a str,
+    /// pattern of the hashed class names, `[name]`, `[local]` and `[hash]` or `[hash:<length>]`
+    /// are replaced, the hash is hexadecimal and `[hash:<length>]` keeps its last digits
+    pub generate_scoped_name: &'a str,
+}
+
+#[derive(Debug, Default)]
+pub struct StyleCompileResult {
+    pub code: String,
+    pub errors: Vec<String>,
+    /// local to hashed class names of css modules
+    pub modules: Option<BTreeMap<String, String>>,
+}
+
+/**
+ * Compiles the content of a `<style>` block, same as compileStyle in @vue/component-compiler-utils.
+ * Scoped styles get the scope attribute appended to the last compound selector of every rule,
+ * `>>>`, `/deep/` and `::v-deep` stop the scoping, keyframes are renamed to `name-<hash>`.
+ * With css modules every class outside of `:global(...)` is renamed by `generate_scoped_name`.
+ * The source has to be plain css, preprocessors are not supported.
+ */
+pub fn compile_style(options: &StyleCompileOptions) -> StyleCompileResult {
+    if !options.scoped && options.modules.is_none() {
+        return StyleCompileResult {
+            code: options.source.to_string(),
+            ..Default::default()
+        };
+    }
+
+    let mut compiler = StyleCompiler {
+        css: options.source,
+        attr: options.scoped.then(|| format!("[{}]", options.id)),
+        modules: options.modules.as_ref(),
+        classes: BTreeMap::new(),
+        keyframes: HashMap::new(),
+        errors: vec![],
+    };
+
+    if options.scoped {
+        let short_id = options.id.strip_prefix("data-v-").unwrap_or(options.id);
+        compiler.collect_keyframes(short_id);
+    }
+
+    let mut code = String::with_capacity(options.source.len());
+    compiler.rewrite_rules(0, options.source.len(), &mut code);
+
+    StyleCompileResult {
+        code,
+        errors: compiler.errors,
+        modules: options.modules.as_ref().map(|_| compiler.classes),
+    }
+}
+
+/**
+ * Reports classes referenced in `:class` bindings through a css module binding, e.g. `$style.foo`,
+ * which are not defined in the stylesheet of that module. `modules` maps the binding names to the
+ * class names returned by `compile_style`.
+ */
+pub fn check_class_bindings(
+    tree: &ASTTree,
+    modules: &HashMap<String, BTreeMap<String, String>>,
+) -> Vec<CompilerDiagnostic> {
+    let references: Vec<(&String, &BTreeMap<String, String>, Regex)> = modules
+        .iter()
+        .map(|(binding, classes)| {
+            let re = Regex::new(&format!(
+                r#"(?:^|[^\w$.]){}(?:\.([A-Za-z_$][\w$]*)|\[\s*['"]([^'"]+)['"]\s*\])"#,
+                regex::escape(binding)
+            ))
+            .unwrap();
+            (binding, classes, re)
+        })
+        .collect();
+    let mut diagnostics = vec![];
+
+    for node_rc in tree.nodes() {
+        let node = node_rc.borrow();
+        let Some(class_binding) = &node.el.class_binding else {
+            continue;
+        };
+        let attr = ["v-bind:class", ":class"]
+            .into_iter()
+            .find(|name| node.el.raw_attrs_map.contains_key(name))
+            .unwrap_or(":class");
+
+        for (binding, classes, re) in &references {
+            for captures in re.captures_iter(class_binding) {
+                let class = captures.get(1).or_else(|| captures.get(2)).unwrap();
+                if !classes.contains_key(class.as_str()) {
+                    diagnostics.push(
+                        node.locate(
+                            CompilerDiagnostic::new(DiagnosticCode::UnknownCssModuleClass)
+                                .arg(binding.as_str())
+                                .arg(class.as_str())
+                                .attr(attr),
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// Scope id of a component, the same hash-sum vue-loader uses. `seed` is the file path relative to
+/// the project root, vue-loader appends a line break and the file content in production builds.
+pub fn scope_id(seed: &str) -> String {
+    format!("data-v-{}", hash_sum(seed))
+}
+
+fn hash_sum(text: &str) -> String {
+    let mut hash = 0;
+    for text in ["", "[object String]", "string", text] {
+        hash = fold(hash, text);
+    }
+
+    format!("{:0>8x}", hash)
+}
+
+// port of the fold function of hash-sum, js numbers are converted to int32 for the shift
+fn fold(mut hash: i64, text: &str) -> i64 {
+    if text.is_empty() {
+        return hash;
+    }
+
+    for code in text.encode_utf16() {
+        let shifted = i64::from((hash as u32 as i32).wrapping_shl(5));
+        hash = i64::from((shifted - hash + i64::from(code)) as u32 as i32);
+    }
+
+    if hash < 0 {
+        hash * -2
+    } else {
+        hash
+    }
+}
+
+struct StyleCompiler<'a> {
+    css: &'a str,
+    // scope attribute selector, only set for scoped styles
+    attr: Option<String>,
+    modules: Option<&'a CssModulesOptions<'a>>,
+    classes: BTreeMap<String, String>,
+    keyframes: HashMap<String, String>,
+    errors: Vec<String>,
+}
+
+impl StyleCompiler<'_> {
+    fn collect_keyframes(&mut self, short_id: &str) {
+        let bytes = self.css.as_bytes();
+        let mut i = 0;
+
+        while i < bytes.len() {
+            match bytes[i] {
+                b'/' if bytes.get(i + 1) == Some(&b'*') => i = skip_comment(bytes, i),
+                b'"' | b'\'' => i = skip_string(bytes, i),
+                b'@' => {
+                    if let Some(captures) = KEYFRAMES_RE.captures(&self.css[i..]) {
+                        let name = captures[3].to_string();
+                        let renamed = format!("{}-{}", name, short_id);
+                        self.keyframes.insert(name, renamed);
+                    }
+                    i += 1;
+                }
+                _ => i += 1,
+            }
+        }
+    }
+
+    fn rewrite_rules(&mut self, start: usize, end: usize, out: &mut String) {
+        let bytes = self.css.as_bytes();
+        let mut i = start;
+
+        while i < end {
+            let c = bytes[i];
+            if c.is_ascii_whitespace() || c == b'}' || c == b';' {
+                out.push(c as char);
+                i += 1;
+                continue;
+            }
+            if c == b'/' && bytes.get(i + 1) == Some(&b'*') {
+                let comment_end = skip_comment(bytes, i).min(end);
+                out.push_str(&self.css[i..comment_end]);
+                i = comment_end;
+                continue;
+            }
+
+            let prelude_end = find_top_level(bytes, i, end, b"{;}");
+            if prelude_end >= end || bytes[prelude_end] != b'{' {
+                // at-rule without a block, e.g. @import or @charset
+                out.push_str(&self.css[i..prelude_end]);
+                i = prelude_end;
+                continue;
+            }
+
+            let prelude = &self.css[i..prelude_end];
+            let block_end = find_top_level(bytes, prelude_end + 1, end, b"}");
+            let closed = block_end < end;
+            if !closed {
+                self.errors.push(format!(
+                    "Unclosed block at offset {}: {}",
+                    i,
+                    prelude.trim()
+                ));
+            }
+
+            if let Some(name) = prelude.strip_prefix('@') {
+                let name = name
+                    .split(|c: char| c.is_whitespace() || c == '(')
+                    .next()
+                    .unwrap_or_default()
+                    .to_ascii_lowercase();
+
+                if name.ends_with("keyframes") {
+                    out.push_str(&self.rename_keyframes(prelude));
+                    out.push_str(&self.css[prelude_end..block_end]);
+                } else if CONDITIONAL_AT_RULES.contains(&name.as_str()) {
+                    out.push_str(prelude);
+                    out.push('{');
+                    self.rewrite_rules(prelude_end + 1, block_end, out);
+                } else {
+                    out.push_str(prelude);
+                    out.push('{');
+                    out.push_str(&self.rewrite_declarations(prelude_end + 1, block_end));
+                }
+            } else {
+                out.push_str(&self.rewrite_selectors(prelude));
+                out.push('{');
+                out.push_str(&self.rewrite_declarations(prelude_end + 1, block_end));
+            }
+
+            if closed {
+                out.push('}');
+            }
+            i = block_end + 1;
+        }
+    }
+
+    fn rename_keyframes(&self, prelude: &str) -> String {
+        match KEYFRAMES_RE.captures(prelude) {
+            Some(captures) => {
+                let name = captures.get(3).unwrap();
+                match self.keyframes.get(name.as_str()) {
+                    Some(renamed) => format!(
+                        "{}{}{}",
+                        &prelude[..name.start()],
+                        renamed,
+                        &prelude[name.end()..]
+                    ),
+                    None => prelude.to_string(),
+                }
+            }
+            None => prelude.to_string(),
+        }
+    }
+
+    fn rewrite_selectors(&mut self, prelude: &str) -> String {
+        split_top_level(prelude, b',')
+            .into_iter()
+            .map(|selector| {
+                let core = selector.trim();
+                if core.is_empty() {
+                    return selector.to_string();
+                }
+
+                let leading = &selector[..selector.len() - selector.trim_start().len()];
+                let trailing = &selector[selector.trim_end().len()..];
+                let mut core = core.to_string();
+                if let Some(modules) = self.modules {
+                    core = self.localize_selector(&core, modules);
+                }
+                if let Some(attr) = &self.attr {
+                    core = scope_selector(&core, attr);
+                }
+
+                format!("{}{}{}", leading, core, trailing)
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    // renames the classes of a selector, `:global(...)` is unwrapped and kept as is
+    fn localize_selector(&mut self, selector: &str, modules: &CssModulesOptions) -> String {
+        let bytes = selector.as_bytes();
+        let mut out = String::with_capacity(selector.len());
+        let mut i = 0;
+
+        while i < bytes.len() {
+            let start = i;
+            match bytes[i] {
+                b'"' | b'\'' => i = skip_string(bytes, i),
+                b'/' if bytes.get(i + 1) == Some(&b'*') => i = skip_comment(bytes, i),
+                b'[' => i = find_top_level(bytes, i + 1, bytes.len(), b"]") + 1,
+                b'\\' => i = skip_escape(bytes, i),
+                b'.' if bytes.get(i + 1).is_some_and(|c| *c != b'.') => {
+                    i = skip_ident(bytes, i + 1);
+                    let local = &selector[start + 1..i];
+                    if !local.is_empty() {
+                        out.push('.');
+                        out.push_str(&self.hashed_class(local, modules));
+                        continue;
+                    }
+                }
+                b':' => {
+                    let name_end = skip_ident(bytes, i + 1);
+                    let name = &selector[i + 1..name_end];
+                    let is_global = name.eq_ignore_ascii_case("global");
+                    if (is_global || name.eq_ignore_ascii_case("local"))
+                        && bytes.get(name_end) == Some(&b'(')
+                    {
+                        let args_end = find_top_level(bytes, name_end + 1, bytes.len(), b")");
+                        let args = &selector[name_end + 1..args_end];
+                        if is_global {
+                            out.push_str(args);
+                        } else {
+                            out.push_str(&self.localize_selector(args, modules));
+                        }
+                        i = args_end + 1;
+                        continue;
+                    }
+                    i = name_end;
+                }
+                _ => i += 1,
+            }
+            i = i.min(bytes.len());
+            out.push_str(&selector[start..i]);
+        }
+
+        out
+    }
+
+    fn hashed_class(&mut self, local: &str, modules: &CssModulesOptions) -> String {
+        if let Some(hashed) = self.classes.get(local) {
+            return hashed.clone();
+        }
+
+        let file_name = modules
+            .filename
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or_default();
+        let name = file_name.split('.').next().unwrap_or_default();
+        let name: String = name
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let hash = hash_sum(&format!("{}+{}", modules.filename, local));
+
+        let mut hashed = String::new();
+        let mut pattern = modules.generate_scoped_name;
+        while let Some(start) = pattern.find('[') {
+            hashed.push_str(&pattern[..start]);
+            pattern = &pattern[start..];
+            let Some(end) = pattern.find(']') else {
+                break;
+            };
+
+            match &pattern[1..end] {
+                "local" => hashed.push_str(local),
+                "name" => hashed.push_str(&name),
+                "hash" => hashed.push_str(&hash),
+                placeholder => match placeholder
+                    .strip_prefix("hash:")
+                    .and_then(|length| length.parse::<usize>().ok())
+                {
+                    // the last characters of the fold change the low-order digits the most
+                    Some(length) => hashed.push_str(&hash[hash.len() - length.min(hash.len())..]),
+                    None => hashed.push_str(&pattern[..=end]),
+                },
+            }
+            pattern = &pattern[end + 1..];
+        }
+        hashed.push_str(pattern);
+
+        self.classes.insert(local.to_string(), hashed.clone());
+        hashed
+    }
+
+    fn rewrite_declarations(&self, start: usize, end: usize) -> String {
+        let block = &self.css[start..end];
+        if self.keyframes.is_empty() {
+            return block.to_string();
+        }
+
+        split_top_level(block, b';')
+            .into_iter()
+            .map(|declaration| {
+                let Some(colon) = declaration.find(':') else {
+                    return declaration.to_string();
+                };
+                let property = declaration[..colon].trim();
+                let raw_value = &declaration[colon + 1..];
+                let value = raw_value.trim();
+                let between = &raw_value[..raw_value.len() - raw_value.trim_start().len()];
+                let trailing = &raw_value[raw_value.trim_end().len()..];
+
+                let value = if ANIMATION_NAME_RE.is_match(property) {
+                    value
+                        .split(',')
+                        .map(|name| {
+                            let name = name.trim();
+                            self.keyframes
+                                .get(name)
+                                .map_or(name, |renamed| renamed.as_str())
+                        })
+                        .collect::<Vec<_>>()
+                        .join(",")
+                } else if ANIMATION_RE.is_match(property) {
+                    value
+                        .split(',')
+                        .map(|animation| {
+                            let mut values: Vec<&str> =
+                                WHITESPACE_RE.split(animation.trim()).collect();
+                            match values.iter().position(|v| self.keyframes.contains_key(*v)) {
+                                Some(index) => {
+                                    values[index] = &self.keyframes[values[index]];
+                                    values.join(" ")
+                                }
+                                None => animation.to_string(),
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .join(",")
+                } else {
+                    return declaration.to_string();
+                };
+
+                format!("{}{}{}{}", &declaration[..=colon], between, value, trailing)
+            })
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+}
+
+fn scope_selector(selector: &str, attr: &str) -> String {
+    let bytes = selector.as_bytes();
+    let mut insert_at: Option<usize> = None;
+    // range of the deep combinator and the selector passed as ::v-deep argument
+    let mut deep: Option<(usize, usize, &str)> = None;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if selector[i..].starts_with("/deep/") => {
+                deep = Some((i, i + 6, ""));
+                break;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => i = skip_comment(bytes, i),
+            b'>' if selector[i..].starts_with(">>>") => {
+                deep = Some((i, i + 3, ""));
+                break;
+            }
+            b'>' | b'+' | b'~' | b',' => i += 1,
+            c if c.is_ascii_whitespace() => i += 1,
+            b':' => {
+                let start = i;
+                i += 1;
+                if bytes.get(i) == Some(&b':') {
+                    i += 1;
+                }
+                i = skip_ident(bytes, i);
+                let name_end = i;
+                let mut args = "";
+                if bytes.get(i) == Some(&b'(') {
+                    i = find_top_level(bytes, i + 1, bytes.len(), b")");
+                    args = &selector[name_end + 1..i.min(bytes.len())];
+                    i += 1;
+                }
+
+                if selector[start..name_end].eq_ignore_ascii_case("::v-deep") {
+                    deep = Some((start, i.min(bytes.len()), args));
+                    break;
+                }
+            }
+            b'[' => {
+                i = find_top_level(bytes, i + 1, bytes.len(), b"]") + 1;
+                insert_at = Some(i.min(bytes.len()));
+            }
+            b'"' | b'\'' => i = skip_string(bytes, i),
+            b'(' => i = find_top_level(bytes, i + 1, bytes.len(), b")") + 1,
+            c => {
+                let start = i;
+                if c == b'.' || c == b'#' {
+                    i += 1;
+                }
+                i = skip_ident(bytes, i);
+                if i == start {
+                    // *, & or any other single character
+                    i += 1;
+                }
+                insert_at = Some(i);
+            }
+        }
+    }
+
+    let Some((deep_start, deep_end, args)) = deep else {
+        return match insert_at {
+            Some(at) => format!("{}{}{}", &selector[..at], attr, &selector[at..]),
+            None => format!("{}{}", attr, selector),
+        };
+    };
+
+    let before = selector[..deep_start].trim_end();
+    let scoped = match insert_at {
+        Some(at) => format!("{}{}{}", &before[..at], attr, &before[at..]),
+        None => format!("{}{}", attr, before),
+    };
+    let rest = format!("{}{}", args.trim(), &selector[deep_end..]);
+    let rest = rest.trim_start();
+
+    if rest.is_empty() {
+        scoped
+    } else {
+        format!("{} {}", scoped, rest)
+    }
+}
+
+/// Index of the first of `stops` at nesting level 0, outside of strings and comments.
+fn find_top_level(bytes: &[u8], start: usize, end: usize, stops: &[u8]) -> usize {
+    let mut depth = 0;
+    let mut i = start;
+
+    while i < end {
+        let c = bytes[i];
+        if depth == 0 && stops.contains(&c) {
+            return i;
+        }
+
+        match c {
+            b'"' | b'\'' => {
+                i = skip_string(bytes, i);
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = skip_comment(bytes, i);
+                continue;
+            }
+            b'\\' => {
+                i = skip_escape(bytes, i);
+                continue;
+            }
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth -= 1,
+            _ => {}
+        }
+        i += 1;
+    }
+
+    end
+}
+
+fn split_top_level(source: &str, separator: u8) -> Vec<&str> {
+    let bytes = source.as_bytes();
+    let mut parts = vec![];
+    let mut start = 0;
+
+    loop {
+        let end = find_top_level(bytes, start, bytes.len(), &[separator]);
+        parts.push(&source[start..end]);
+        if end >= bytes.len() {
+            return parts;
+        }
+        start = end + 1;
+    }
+}
+
+fn skip_string(bytes: &[u8], start: usize) -> usize {
+    let quote = bytes[start];
+    let mut i = start + 1;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i = skip_escape(bytes, i),
+            c if c == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+
+    bytes.len()
+}
+
+// skips a backslash and the whole utf-8 sequence of the escaped character
+fn skip_escape(bytes: &[u8], start: usize) -> usize {
+    let len = match bytes.get(start + 1) {
+        None => 0,
+        Some(c) if *c < 0x80 => 1,
+        Some(c) if *c < 0xe0 => 2,
+        Some(c) if *c < 0xf0 => 3,
+        Some(_) => 4,
+    };
+    (start + 1 + len).min(bytes.len())
+}
+
+fn skip_comment(bytes: &[u8], start: usize) -> usize {
+    bytes[start + 2..]
+        .windows(2)
+        .position(|window| window == b"*/")
+        .map(|index| start + 2 + index + 2)
+        .unwrap_or(bytes.len())
+}
+
+fn skip_ident(bytes: &[u8], start: usize) -> usize {
+    let mut i = start;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i = skip_escape(bytes, i),
+            c if c.is_ascii_alphanumeric() || c == b'-' || c == b'_' || c >= 0x80 => i += 1,
+            _ => break,
+        }
+    }
+
+    i.min(bytes.len())
+}
@@ -0,0 +1,3 @@
+pub mod codegen;
+mod modules;
+pub mod optimizer;
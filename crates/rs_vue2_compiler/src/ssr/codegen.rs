@@ -0,0 +1,328 @@
+use crate::ast_tree::{ASTElementKind, ASTNode, ASTTree, SSROptimizability};
+use crate::codegen::{gen_comment, gen_text, CodegenResult, CodegenState};
+use crate::helpers::to_json_string;
+use crate::ssr::modules::{
+    escape, gen_attr_segments, gen_class_segments, gen_dom_prop_segments, gen_style_segments,
+};
+use crate::warn_logger::WarnLogger;
+use crate::web::element::is_unary_tag;
+use crate::{print_line, CompilerOptions};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Piece of the markup string built by `_ssrNode`.
+#[derive(Debug)]
+pub(crate) enum Segment {
+    // markup, concatenated as a string literal
+    Raw(String),
+    // value which has to be html escaped at runtime
+    Interpolation(String),
+    // expression evaluating to markup
+    Expression(String),
+}
+
+/**
+ * Generates a render function for the server renderer. Subtrees marked by the ssr optimizer
+ * are rendered as string concatenations through `_ssrNode` instead of VNodes.
+ */
+pub fn generate(tree: &ASTTree, options: &CompilerOptions) -> CodegenResult {
+    let warn = options.warn.clone().unwrap_or_else(|| Box::new(print_line));
+
+    generate_with_warn(tree, options, warn)
+}
+
+pub(crate) fn generate_with_warn(
+    tree: &ASTTree,
+    options: &CompilerOptions,
+    warn: Box<dyn WarnLogger>,
+) -> CodegenResult {
+    let mut state = CodegenState::new(tree, options, warn);
+    let root = tree.wrapper.borrow().children.first().cloned();
+
+    let code = match root {
+        Some(root) => gen_ssr_element(&mut state, &root),
+        None => "_c(\"div\")".to_string(),
+    };
+
+    CodegenResult {
+        render: state.gen_render_fn(&code),
+        static_render_fns: state.static_render_fns,
+    }
+}
+
+fn gen_ssr_element(state: &mut CodegenState, node_rc: &Rc<RefCell<ASTNode>>) -> String {
+    let (gen_for, gen_if, is_template, optimizability) = {
+        let node = node_rc.borrow();
+        (
+            node.el.for_value.is_some() && !state.for_processed.contains(&node.id),
+            node.el.if_val.is_some() && !state.if_processed.contains(&node.id),
+            node.el.token.data.eq_ignore_ascii_case("template") && node.el.slot_target.is_none(),
+            node.el.ssr_optimizability,
+        )
+    };
+
+    if gen_for {
+        return state.gen_for(node_rc, Some(gen_ssr_element), None);
+    }
+    if gen_if {
+        return state.gen_if(node_rc, Some(gen_ssr_element), "_e()");
+    }
+    if is_template {
+        let node = node_rc.borrow();
+        return if optimizability == Some(SSROptimizability::Full) {
+            gen_children_as_string_node(state, &node)
+        } else {
+            gen_ssr_children(state, &node, false).unwrap_or_else(|| "void 0".to_string())
+        };
+    }
+
+    match optimizability {
+        Some(SSROptimizability::Full) => gen_string_element(state, node_rc),
+        Some(SSROptimizability::SelfOnly) => gen_string_element_with_children(state, node_rc),
+        Some(SSROptimizability::Children) => gen_normal_element(state, node_rc, true),
+        Some(SSROptimizability::Partial) => gen_normal_element(state, node_rc, false),
+        _ => state.gen_element(node_rc),
+    }
+}
+
+fn gen_normal_element(
+    state: &mut CodegenState,
+    node_rc: &Rc<RefCell<ASTNode>>,
+    stringify_children: bool,
+) -> String {
+    let (tag, plain) = {
+        let node = node_rc.borrow();
+        (node.el.token.data.to_string(), node.el.plain)
+    };
+    let data = if plain {
+        None
+    } else {
+        Some(state.gen_data(node_rc))
+    };
+
+    let node = node_rc.borrow();
+    let children = if stringify_children {
+        Some(format!("[{}]", gen_children_as_string_node(state, &node)))
+    } else {
+        gen_ssr_children(state, &node, true)
+    };
+
+    format!(
+        "_c('{}'{}{})",
+        tag,
+        data.map(|data| format!(",{}", data)).unwrap_or_default(),
+        children
+            .map(|children| format!(",{}", children))
+            .unwrap_or_default()
+    )
+}
+
+fn gen_ssr_children(state: &mut CodegenState, node: &ASTNode, check_skip: bool) -> Option<String> {
+    state.gen_children_with(node, check_skip, Some(gen_ssr_element), Some(gen_ssr_node))
+}
+
+fn gen_ssr_node(state: &mut CodegenState, node_rc: &Rc<RefCell<ASTNode>>) -> String {
+    let (kind, is_comment) = {
+        let node = node_rc.borrow();
+        (node.el.kind, node.el.is_comment)
+    };
+
+    match kind {
+        ASTElementKind::Element | ASTElementKind::Root => gen_ssr_element(state, node_rc),
+        ASTElementKind::Text if is_comment => gen_comment(&node_rc.borrow()),
+        _ => gen_text(&node_rc.borrow()),
+    }
+}
+
+fn gen_children_as_string_node(state: &mut CodegenState, node: &ASTNode) -> String {
+    if node.children.is_empty() {
+        return String::new();
+    }
+
+    format!(
+        "_ssrNode({})",
+        flatten_segments(nodes_to_segments(state, &node.children))
+    )
+}
+
+fn gen_string_element(state: &mut CodegenState, node_rc: &Rc<RefCell<ASTNode>>) -> String {
+    format!("_ssrNode({})", element_to_string(state, node_rc))
+}
+
+fn gen_string_element_with_children(
+    state: &mut CodegenState,
+    node_rc: &Rc<RefCell<ASTNode>>,
+) -> String {
+    let open_tag = flatten_segments(element_to_open_tag_segments(node_rc));
+    let node = node_rc.borrow();
+    let children = gen_ssr_children(state, &node, true);
+
+    format!(
+        "_ssrNode({},\"</{}>\"{})",
+        open_tag,
+        node.el.token.data,
+        children
+            .map(|children| format!(",{}", children))
+            .unwrap_or_default()
+    )
+}
+
+fn element_to_string(state: &mut CodegenState, node_rc: &Rc<RefCell<ASTNode>>) -> String {
+    format!(
+        "({})",
+        flatten_segments(element_to_segments(state, node_rc))
+    )
+}
+
+fn element_to_segments(state: &mut CodegenState, node_rc: &Rc<RefCell<ASTNode>>) -> Vec<Segment> {
+    let (gen_for, gen_if, is_template) = {
+        let node = node_rc.borrow();
+        (
+            node.el.for_value.is_some() && !state.for_processed.contains(&node.id),
+            node.el.if_val.is_some() && !state.if_processed.contains(&node.id),
+            node.el.token.data.eq_ignore_ascii_case("template"),
+        )
+    };
+
+    if gen_for {
+        return vec![Segment::Expression(state.gen_for(
+            node_rc,
+            Some(element_to_string),
+            Some("_ssrList"),
+        ))];
+    }
+    if gen_if {
+        return vec![Segment::Expression(state.gen_if(
+            node_rc,
+            Some(element_to_string),
+            "\"<!---->\"",
+        ))];
+    }
+    if is_template {
+        return children_to_segments(state, &node_rc.borrow());
+    }
+
+    let mut segments = element_to_open_tag_segments(node_rc);
+    let node = node_rc.borrow();
+    segments.extend(children_to_segments(state, &node));
+    if !is_unary_tag(&node.el.token.data) {
+        segments.push(Segment::Raw(format!("</{}>", node.el.token.data)));
+    }
+
+    segments
+}
+
+fn element_to_open_tag_segments(node_rc: &Rc<RefCell<ASTNode>>) -> Vec<Segment> {
+    let node = node_rc.borrow();
+
+    let mut segments = vec![Segment::Raw(format!("<{}", node.el.token.data))];
+    segments.extend(gen_attr_segments(&node.el.attrs));
+    segments.extend(gen_attr_segments(&node.el.dynamic_attrs));
+    segments.extend(gen_dom_prop_segments(&node));
+
+    if let Some(binding) = node.get_raw_attr_value("v-bind") {
+        segments.push(Segment::Expression(format!("_ssrAttrs({})", binding)));
+    }
+    if let Some(binding) = node.get_raw_attr_value("v-bind.prop") {
+        segments.push(Segment::Expression(format!("_ssrDOMProps({})", binding)));
+    }
+    if node.el.static_class.is_some() || node.el.class_binding.is_some() {
+        segments.push(gen_class_segments(
+            node.el.static_class.as_deref(),
+            node.el.class_binding.as_deref(),
+        ));
+    }
+
+    let v_show = node.get_raw_attr_value("v-show");
+    if node.el.static_style.is_some() || node.el.style_binding.is_some() || v_show.is_some() {
+        segments.push(gen_style_segments(
+            node.get_raw_attr_value("style"),
+            node.el.static_style.as_deref(),
+            node.el.style_binding.as_deref(),
+            v_show,
+        ));
+    }
+
+    segments.push(Segment::Raw(">".to_string()));
+    segments
+}
+
+fn children_to_segments(state: &mut CodegenState, node: &ASTNode) -> Vec<Segment> {
+    if let Some(binding) = node.get_raw_attr_value("v-html") {
+        return vec![Segment::Expression(format!("_s({})", binding))];
+    }
+    if let Some(binding) = node.get_raw_attr_value("v-text") {
+        return vec![Segment::Interpolation(format!("_s({})", binding))];
+    }
+    if node.el.token.data.eq_ignore_ascii_case("textarea") {
+        if let Some(binding) = node.get_raw_attr_value("v-model") {
+            return vec![Segment::Interpolation(format!("_s({})", binding))];
+        }
+    }
+
+    nodes_to_segments(state, &node.children)
+}
+
+fn nodes_to_segments(state: &mut CodegenState, children: &[Rc<RefCell<ASTNode>>]) -> Vec<Segment> {
+    let mut segments = vec![];
+
+    for child_rc in children {
+        let (kind, is_comment) = {
+            let child = child_rc.borrow();
+            (child.el.kind, child.el.is_comment)
+        };
+
+        match kind {
+            ASTElementKind::Element | ASTElementKind::Root => {
+                segments.extend(element_to_segments(state, child_rc));
+            }
+            ASTElementKind::Expression => {
+                let child = child_rc.borrow();
+                segments.push(Segment::Interpolation(
+                    child.el.expression.clone().unwrap_or_default(),
+                ));
+            }
+            ASTElementKind::Text => {
+                let text = escape(&child_rc.borrow().el.token.data);
+                segments.push(Segment::Raw(if is_comment {
+                    format!("<!--{}-->", text)
+                } else {
+                    text
+                }));
+            }
+        }
+    }
+
+    segments
+}
+
+fn flatten_segments(segments: Vec<Segment>) -> String {
+    let mut merged: Vec<String> = vec![];
+    let mut text_buffer = String::new();
+
+    for segment in segments {
+        match segment {
+            Segment::Raw(value) => {
+                text_buffer += &value;
+            }
+            Segment::Interpolation(value) => {
+                flush_text_buffer(&mut text_buffer, &mut merged);
+                merged.push(format!("_ssrEscape({})", value));
+            }
+            Segment::Expression(value) => {
+                flush_text_buffer(&mut text_buffer, &mut merged);
+                merged.push(format!("({})", value));
+            }
+        }
+    }
+    flush_text_buffer(&mut text_buffer, &mut merged);
+
+    merged.join("+")
+}
+
+fn flush_text_buffer(text_buffer: &mut String, merged: &mut Vec<String>) {
+    if !text_buffer.is_empty() {
+        merged.push(to_json_string(text_buffer));
+        text_buffer.clear();
+    }
+}
@@ -0,0 +1,160 @@
+use crate::ast_tree::{ASTNode, AttrItem};
+use crate::helpers::to_json_string;
+use crate::ssr::codegen::Segment;
+use crate::web::attrs::{is_boolean_attr, is_enumerated_attr, is_renderable_attr, prop_to_attr};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref PLAIN_STRING_RE: Regex =
+        Regex::new(r#"^"(?:[^"\\]|\\.)*"$|^'(?:[^'\\]|\\.)*'$"#).unwrap();
+}
+
+pub fn gen_attr_segments(attrs: &[AttrItem]) -> Vec<Segment> {
+    attrs.iter().map(gen_attr_segment).collect()
+}
+
+pub fn gen_dom_prop_segments(node: &ASTNode) -> Vec<Segment> {
+    let attrs = &node.el.attrs;
+    // v-model's value of a textarea is rendered as its content instead
+    let is_textarea_model = node.el.token.data.eq_ignore_ascii_case("textarea")
+        && node
+            .el
+            .directives
+            .iter()
+            .flatten()
+            .any(|dir| dir.name == "model");
+
+    node.el
+        .props
+        .iter()
+        .filter(|prop| prop.name != "innerHTML" && prop.name != "textContent")
+        .filter(|prop| !is_textarea_model || prop.name != "value")
+        .filter_map(|prop| {
+            let name = prop_to_attr(&prop.name);
+
+            // attributes win over dom props with the same name
+            if !is_renderable_attr(&name) || attrs.iter().any(|attr| attr.name == name) {
+                return None;
+            }
+
+            Some(gen_attr_segment(&AttrItem {
+                name,
+                ..prop.clone()
+            }))
+        })
+        .collect()
+}
+
+fn gen_attr_segment(attr: &AttrItem) -> Segment {
+    if attr.dynamic {
+        return Segment::Expression(format!(
+            "_ssrAttr({},{})",
+            attr.name,
+            gen_attr_expression(attr)
+        ));
+    }
+
+    let value = match &attr.value {
+        Some(value) if attr.literal => Some(value.to_string()),
+        Some(value) => parse_plain_string(value),
+        None => Some(String::new()),
+    };
+    let Some(value) = value else {
+        return Segment::Expression(format!(
+            "_ssrAttr({},{})",
+            to_json_string(&attr.name),
+            gen_attr_expression(attr)
+        ));
+    };
+
+    let name = &attr.name;
+    let value = if is_enumerated_attr(name) && value != "false" {
+        "true".to_string()
+    } else {
+        value
+    };
+
+    Segment::Raw(if is_boolean_attr(name) {
+        format!(" {}=\"{}\"", name, name)
+    } else if value.is_empty() {
+        format!(" {}", name)
+    } else {
+        format!(" {}=\"{}\"", name, escape(&value))
+    })
+}
+
+fn gen_attr_expression(attr: &AttrItem) -> String {
+    match &attr.value {
+        Some(value) if attr.literal => to_json_string(value),
+        Some(value) => value.to_string(),
+        None => "\"\"".to_string(),
+    }
+}
+
+// string literal expressions, e.g. :title="'foo'", are rendered as plain attributes
+fn parse_plain_string(value: &str) -> Option<String> {
+    if !PLAIN_STRING_RE.is_match(value) {
+        return None;
+    }
+
+    if value.starts_with('"') {
+        return serde_json::from_str(value).ok();
+    }
+
+    let inner = &value[1..value.len() - 1];
+    if inner.contains('\\') {
+        None
+    } else {
+        Some(inner.to_string())
+    }
+}
+
+pub fn gen_class_segments(static_class: Option<&str>, class_binding: Option<&str>) -> Segment {
+    match (static_class, class_binding) {
+        (Some(static_class), None) => Segment::Raw(format!(" class=\"{}\"", escape(static_class))),
+        _ => Segment::Expression(format!(
+            "_ssrClass({},{})",
+            static_class
+                .map(to_json_string)
+                .unwrap_or_else(|| "null".to_string()),
+            class_binding.unwrap_or("null")
+        )),
+    }
+}
+
+pub fn gen_style_segments(
+    static_style: Option<&str>,
+    parsed_static_style: Option<&str>,
+    style_binding: Option<&str>,
+    v_show: Option<&str>,
+) -> Segment {
+    match (static_style, style_binding, v_show) {
+        (Some(static_style), None, None) => {
+            Segment::Raw(format!(" style=\"{}\"", escape(static_style)))
+        }
+        _ => Segment::Expression(format!(
+            "_ssrStyle({},{}, {})",
+            parsed_static_style.unwrap_or("null"),
+            style_binding.unwrap_or("null"),
+            v_show
+                .map(|v_show| format!("{{ display: ({}) ? '' : 'none' }}", v_show))
+                .unwrap_or_else(|| "null".to_string())
+        )),
+    }
+}
+
+pub fn escape(text: &str) -> String {
+    let mut res = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => res.push_str("&lt;"),
+            '>' => res.push_str("&gt;"),
+            '"' => res.push_str("&quot;"),
+            '&' => res.push_str("&amp;"),
+            _ => res.push(c),
+        }
+    }
+
+    res
+}
@@ -0,0 +1,179 @@
+use crate::ast_tree::{
+    create_ast_element, ASTElementKind, ASTNode, ASTTree, SSROptimizability, Token,
+};
+use crate::web::element::is_reserved_tag;
+use rs_html_parser_tokens::TokenKind;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+const BUILT_IN_DIRECTIVES: [&str; 9] = [
+    "text", "html", "show", "on", "bind", "model", "pre", "cloak", "once",
+];
+
+/**
+ * Marks how much of every subtree the ssr codegen can render to plain strings.
+ * Consecutive children which can be fully rendered to a string are grouped into a `<template>`
+ * so they can be concatenated into a single `_ssrNode`.
+ */
+pub fn optimize(tree: &mut ASTTree) {
+    let root = tree.wrapper.borrow().children.first().cloned();
+
+    if let Some(root) = root {
+        walk(tree, &root, true);
+    }
+}
+
+fn walk(tree: &mut ASTTree, node_rc: &Rc<RefCell<ASTNode>>, is_root: bool) {
+    let (unoptimizable, self_unoptimizable, kind) = {
+        let node = node_rc.borrow();
+        (
+            is_unoptimizable_tree(&node),
+            is_root || has_custom_directive(&node),
+            node.el.kind,
+        )
+    };
+
+    if unoptimizable {
+        node_rc.borrow_mut().el.ssr_optimizability = Some(SSROptimizability::Unoptimizable);
+        return;
+    }
+    if kind != ASTElementKind::Element {
+        node_rc.borrow_mut().el.ssr_optimizability = Some(SSROptimizability::Full);
+        return;
+    }
+
+    // root node or nodes with custom directives should always be a VNode
+    let mut optimizability = self_unoptimizable.then_some(SSROptimizability::Children);
+    let (children, blocks) = {
+        let node = node_rc.borrow();
+        let blocks: Vec<_> = node
+            .el
+            .if_conditions
+            .iter()
+            .flatten()
+            .skip(1)
+            .filter_map(|condition| tree.get(condition.block_id).cloned())
+            .collect();
+
+        (node.children.clone(), blocks)
+    };
+
+    let walked = children
+        .iter()
+        .map(|child| (child, false))
+        .chain(blocks.iter().map(|block| (block, is_root)));
+    for (child, is_root) in walked {
+        walk(tree, child, is_root);
+
+        if child.borrow().el.ssr_optimizability != Some(SSROptimizability::Full) {
+            optimizability = Some(if self_unoptimizable {
+                SSROptimizability::Partial
+            } else {
+                SSROptimizability::SelfOnly
+            });
+        }
+    }
+
+    let renders_raw_content = {
+        let node = node_rc.borrow();
+        !is_root && (node.has_raw_attr("v-html") || node.has_raw_attr("v-text"))
+    };
+
+    match optimizability {
+        Some(optimizability) if !renders_raw_content => {
+            node_rc.borrow_mut().el.ssr_optimizability = Some(optimizability);
+            optimize_siblings(tree, node_rc);
+        }
+        _ => node_rc.borrow_mut().el.ssr_optimizability = Some(SSROptimizability::Full),
+    }
+}
+
+fn optimize_siblings(tree: &mut ASTTree, node_rc: &Rc<RefCell<ASTNode>>) {
+    let children = std::mem::take(&mut node_rc.borrow_mut().children);
+    let mut optimized_children = vec![];
+    let mut group = vec![];
+
+    for child in children {
+        if child.borrow().el.ssr_optimizability == Some(SSROptimizability::Full) {
+            group.push(child);
+        } else {
+            push_group(tree, node_rc, &mut group, &mut optimized_children);
+            optimized_children.push(child);
+        }
+    }
+    push_group(tree, node_rc, &mut group, &mut optimized_children);
+
+    node_rc.borrow_mut().children = optimized_children;
+}
+
+fn push_group(
+    tree: &mut ASTTree,
+    parent_rc: &Rc<RefCell<ASTNode>>,
+    group: &mut Vec<Rc<RefCell<ASTNode>>>,
+    optimized_children: &mut Vec<Rc<RefCell<ASTNode>>>,
+) {
+    if group.is_empty() {
+        return;
+    }
+
+    let mut element = create_ast_element(
+        Token {
+            kind: TokenKind::OpenTag,
+            data: "template".into(),
+            attrs: None,
+            is_implied: true,
+            start: None,
+            end: None,
+            raw_attrs: vec![],
+        },
+        ASTElementKind::Element,
+    );
+    element.plain = true;
+    element.ssr_optimizability = Some(SSROptimizability::Full);
+
+    let (parent_id, is_dev, warn) = {
+        let parent = parent_rc.borrow();
+        (parent.id, parent.is_dev, parent.warn.clone_box())
+    };
+    let template_rc = tree.create(element, parent_id, is_dev, warn);
+    let template_id = template_rc.borrow().id;
+
+    for child in group.iter() {
+        let mut child = child.borrow_mut();
+        child.parent = Some(Rc::downgrade(&template_rc));
+        child.parent_id = template_id;
+    }
+    template_rc.borrow_mut().children = std::mem::take(group);
+
+    tree.set(template_id, Rc::clone(&template_rc));
+    optimized_children.push(template_rc);
+}
+
+fn is_unoptimizable_tree(node: &ASTNode) -> bool {
+    if node.el.kind != ASTElementKind::Element {
+        return false;
+    }
+
+    let tag = &node.el.token.data;
+
+    // built-in (slot, component), custom components and <select v-model>,
+    // which requires a runtime check
+    tag.eq_ignore_ascii_case("slot")
+        || tag.eq_ignore_ascii_case("component")
+        || !is_reserved_tag(tag)
+        || node.el.component.is_some()
+        || (tag.eq_ignore_ascii_case("select") && has_directive(node, |name| name == "model"))
+}
+
+fn has_custom_directive(node: &ASTNode) -> bool {
+    node.el.kind == ASTElementKind::Element
+        && has_directive(node, |name| !BUILT_IN_DIRECTIVES.contains(&name))
+}
+
+fn has_directive(node: &ASTNode, predicate: impl Fn(&str) -> bool) -> bool {
+    node.el
+        .directives
+        .iter()
+        .flatten()
+        .any(|dir| predicate(&dir.name))
+}
@@ -0,0 +1,457 @@
+use crate::js_lexer::{matching, tokenize, JsToken, JsTokenKind};
+use std::collections::HashSet;
+
+const RENDER_PRELUDE: &str = "var _vm=this;var _h=_vm.$createElement;var _c=_vm._self._c||_h;";
+
+// globals which stay accessible without going through the component instance
+const GLOBALS: [&str; 27] = [
+    "Infinity",
+    "undefined",
+    "NaN",
+    "isFinite",
+    "isNaN",
+    "console",
+    "parseFloat",
+    "parseInt",
+    "decodeURI",
+    "decodeURIComponent",
+    "encodeURI",
+    "encodeURIComponent",
+    "Math",
+    "Number",
+    "Date",
+    "Array",
+    "Object",
+    "Boolean",
+    "String",
+    "RegExp",
+    "Map",
+    "Set",
+    "JSON",
+    "Intl",
+    "BigInt",
+    "require",
+    "arguments",
+];
+
+pub fn is_global(name: &str) -> bool {
+    GLOBALS.contains(&name)
+}
+
+/**
+ * Rewrites a `with(this){...}` render function body into one that reads every free identifier
+ * from `_vm`, same as stripWith in vue-template-es2015-compiler.
+ * Function parameters (v-for aliases, slot scopes, `$event`) and declared variables stay local.
+ * Code which can't be tokenized is returned unchanged.
+ */
+pub fn strip_with(render: &str) -> String {
+    let Some(body) = render
+        .strip_prefix("with(this){")
+        .and_then(|body| body.strip_suffix('}'))
+    else {
+        return render.to_string();
+    };
+
+    match prefix_identifiers(body) {
+        Some(body) => format!("{}{}", RENDER_PRELUDE, body),
+        None => render.to_string(),
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Frame {
+    Paren,
+    Bracket,
+    Block,
+    Object,
+    // `${` of a template literal
+    Substitution,
+}
+
+struct Scope {
+    names: HashSet<String>,
+    // number of open frames the scope belongs to
+    depth: usize,
+    // arrow function with an expression body, ends at the next `,` or `;` on its depth
+    expression: bool,
+}
+
+struct Prefixer<'a> {
+    source: &'a str,
+    tokens: Vec<JsToken>,
+    frames: Vec<Frame>,
+    scopes: Vec<Scope>,
+    // parameters of the function whose body starts at the next `{`
+    pending_params: Option<HashSet<String>>,
+    // tokens which declare a binding and must not be prefixed
+    declarations: HashSet<usize>,
+    // depth of the current var / let / const list
+    declaration_depth: Option<usize>,
+    // the code is an expression instead of statements, a leading `{` starts an object
+    expression: bool,
+}
+
+/// Prefixes the free identifiers of javascript statements with `_vm.`.
+pub fn prefix_identifiers(code: &str) -> Option<String> {
+    let mut prefixer = Prefixer::new(code, &["_vm", "_h", "_c"], false)?;
+
+    let mut res = String::with_capacity(code.len());
+    let mut last = 0;
+    for (index, shorthand) in prefixer.walk() {
+        let token = prefixer.tokens[index];
+        res += &code[last..token.start];
+        // shorthand property, `{ foo }` becomes `{ foo:_vm.foo }`
+        if shorthand {
+            res += token.text(code);
+            res.push(':');
+        }
+        res += "_vm.";
+        last = token.start;
+    }
+    res += &code[last..];
+
+    Some(res)
+}
+
+/// Identifier read from outside of the given code, e.g. from the component instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FreeIdentifier {
+    pub name: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Free identifiers of an expression, `locals` are already declared by the surrounding scope.
+pub(crate) fn free_identifiers(code: &str, locals: &[&str]) -> Option<Vec<FreeIdentifier>> {
+    let mut prefixer = Prefixer::new(code, locals, true)?;
+
+    Some(
+        prefixer
+            .walk()
+            .into_iter()
+            .map(|(index, _)| {
+                let token = prefixer.tokens[index];
+                FreeIdentifier {
+                    name: token.text(code).to_string(),
+                    start: token.start,
+                    end: token.end,
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Names bound by a parameter or destructuring pattern, `{ a, b: c }` binds `a` and `c`.
+pub(crate) fn pattern_bindings(pattern: &str) -> Vec<String> {
+    let Some(mut prefixer) = Prefixer::new(pattern, &[], true) else {
+        return vec![];
+    };
+    let end = prefixer.tokens.len();
+
+    prefixer.collect_params(0, end)
+}
+
+impl<'a> Prefixer<'a> {
+    fn new(source: &'a str, locals: &[&str], expression: bool) -> Option<Prefixer<'a>> {
+        Some(Prefixer {
+            source,
+            tokens: tokenize(source).ok()?,
+            frames: vec![],
+            scopes: vec![Scope {
+                names: locals.iter().map(|s| s.to_string()).collect(),
+                depth: 0,
+                expression: false,
+            }],
+            pending_params: None,
+            declarations: HashSet::new(),
+            declaration_depth: None,
+            expression,
+        })
+    }
+
+    fn text(&self, index: usize) -> &str {
+        self.tokens[index].text(self.source)
+    }
+
+    fn is_punctuator(&self, index: usize, punctuator: &str) -> bool {
+        self.tokens
+            .get(index)
+            .is_some_and(|token| token.is_punctuator(self.source, punctuator))
+    }
+
+    // index of the token closing the bracket opened at `open`
+    fn matching(&self, open: usize) -> Option<usize> {
+        matching(&self.tokens, self.source, open)
+    }
+
+    fn is_local(&self, name: &str) -> bool {
+        self.scopes.iter().any(|scope| scope.names.contains(name))
+    }
+
+    // returns the indexes of free identifier tokens, true for shorthand properties
+    fn walk(&mut self) -> Vec<(usize, bool)> {
+        let mut inserts = vec![];
+
+        for index in 0..self.tokens.len() {
+            let token = self.tokens[index];
+
+            match token.kind {
+                JsTokenKind::Punctuator => self.punctuator(index),
+                JsTokenKind::Template => {
+                    let text = token.text(self.source);
+                    if text.starts_with('}') {
+                        self.close_frame();
+                    }
+                    if text.ends_with("${") {
+                        self.frames.push(Frame::Substitution);
+                    }
+                }
+                JsTokenKind::Keyword => match self.text(index) {
+                    "function" => self.function(index),
+                    "var" | "let" | "const" => {
+                        self.declaration_depth = Some(self.frames.len());
+                    }
+                    _ => {}
+                },
+                JsTokenKind::Identifier => {
+                    if let Some(shorthand) = self.identifier(index) {
+                        inserts.push((index, shorthand));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        inserts
+    }
+
+    fn punctuator(&mut self, index: usize) {
+        match self.text(index) {
+            "(" => {
+                if let Some(close) = self.matching(index) {
+                    if self.is_punctuator(close + 1, "=>") {
+                        self.pending_params =
+                            Some(self.collect_params(index + 1, close).into_iter().collect());
+                    }
+                }
+                self.frames.push(Frame::Paren);
+            }
+            "[" => self.frames.push(Frame::Bracket),
+            "{" => {
+                let frame = if self.is_block(index) {
+                    Frame::Block
+                } else {
+                    Frame::Object
+                };
+                self.frames.push(frame);
+
+                if frame == Frame::Block {
+                    if let Some(mut names) = self.pending_params.take() {
+                        names.extend(self.collect_declarations(index));
+                        self.scopes.push(Scope {
+                            names,
+                            depth: self.frames.len(),
+                            expression: false,
+                        });
+                    }
+                }
+            }
+            ")" | "]" | "}" => self.close_frame(),
+            // expression body
+            "=>" if !self.is_punctuator(index + 1, "{") => {
+                if let Some(names) = self.pending_params.take() {
+                    self.scopes.push(Scope {
+                        names,
+                        depth: self.frames.len(),
+                        expression: true,
+                    });
+                }
+            }
+            "," | ";" => {
+                let depth = self.frames.len();
+                while self
+                    .scopes
+                    .last()
+                    .is_some_and(|scope| scope.expression && scope.depth == depth)
+                {
+                    self.scopes.pop();
+                }
+                if self.text(index) == ";" && self.declaration_depth == Some(depth) {
+                    self.declaration_depth = None;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn close_frame(&mut self) {
+        self.frames.pop();
+
+        let depth = self.frames.len();
+        while self.scopes.last().is_some_and(|scope| scope.depth > depth) {
+            self.scopes.pop();
+        }
+        if self.declaration_depth.is_some_and(|d| d > depth) {
+            self.declaration_depth = None;
+        }
+    }
+
+    fn is_block(&self, index: usize) -> bool {
+        if index == 0 {
+            return !self.expression;
+        }
+
+        let previous = self.tokens[index - 1];
+        match previous.kind {
+            JsTokenKind::Punctuator => match previous.text(self.source) {
+                ")" | "=>" | ";" | "}" => true,
+                "{" => self.frames.last() == Some(&Frame::Block),
+                _ => false,
+            },
+            JsTokenKind::Keyword => {
+                matches!(
+                    previous.text(self.source),
+                    "else" | "do" | "try" | "finally"
+                )
+            }
+            _ => false,
+        }
+    }
+
+    // function name? ( params ) { body }
+    fn function(&mut self, index: usize) {
+        let mut open = index + 1;
+        if self
+            .tokens
+            .get(open)
+            .is_some_and(|token| token.kind == JsTokenKind::Identifier)
+        {
+            self.declarations.insert(open);
+            open += 1;
+        }
+        if !self.is_punctuator(open, "(") {
+            return;
+        }
+
+        if let Some(close) = self.matching(open) {
+            self.pending_params = Some(self.collect_params(open + 1, close).into_iter().collect());
+        }
+    }
+
+    // binding names of a parameter list, destructuring patterns included
+    fn collect_params(&mut self, start: usize, end: usize) -> Vec<String> {
+        let mut names = vec![];
+        let mut depth = 0;
+        let mut default_depth: Option<usize> = None;
+
+        for index in start..end {
+            let token = self.tokens[index];
+            match token.kind {
+                JsTokenKind::Punctuator => match token.text(self.source) {
+                    "(" | "[" | "{" => depth += 1,
+                    ")" | "]" | "}" => {
+                        depth -= 1;
+                        if default_depth.is_some_and(|d| d > depth) {
+                            default_depth = None;
+                        }
+                    }
+                    "=" if default_depth.is_none() => default_depth = Some(depth),
+                    "," if default_depth == Some(depth) => default_depth = None,
+                    _ => {}
+                },
+                JsTokenKind::Identifier if default_depth.is_none() => {
+                    self.declarations.insert(index);
+                    // `{ key: binding }`
+                    if !self.is_punctuator(index + 1, ":") {
+                        names.push(token.text(self.source).to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        names
+    }
+
+    // names declared with var / let / const directly in the block starting at `open`
+    fn collect_declarations(&self, open: usize) -> Vec<String> {
+        let Some(close) = self.matching(open) else {
+            return vec![];
+        };
+        let mut names = vec![];
+        let mut depth = 0;
+        let mut declaration_depth = None;
+
+        for index in open + 1..close {
+            let token = self.tokens[index];
+            match token.kind {
+                JsTokenKind::Keyword
+                    if matches!(token.text(self.source), "var" | "let" | "const") =>
+                {
+                    declaration_depth = Some(depth);
+                    if let Some(name) = self.tokens.get(index + 1) {
+                        if name.kind == JsTokenKind::Identifier {
+                            names.push(name.text(self.source).to_string());
+                        }
+                    }
+                }
+                JsTokenKind::Punctuator => match token.text(self.source) {
+                    "(" | "[" | "{" => depth += 1,
+                    ")" | "]" | "}" => depth -= 1,
+                    ";" if declaration_depth == Some(depth) => declaration_depth = None,
+                    "," if declaration_depth == Some(depth) => {
+                        if let Some(name) = self.tokens.get(index + 1) {
+                            if name.kind == JsTokenKind::Identifier {
+                                names.push(name.text(self.source).to_string());
+                            }
+                        }
+                    }
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+
+        names
+    }
+
+    fn identifier(&mut self, index: usize) -> Option<bool> {
+        if self.declarations.contains(&index) {
+            return None;
+        }
+
+        let name = self.text(index).to_string();
+        let previous = index.checked_sub(1).map(|previous| self.text(previous));
+
+        // property access
+        if matches!(previous, Some("." | "?.")) {
+            return None;
+        }
+
+        // single parameter arrow function
+        if self.is_punctuator(index + 1, "=>") {
+            self.pending_params = Some(HashSet::from([name]));
+            return None;
+        }
+
+        // declared by var / let / const
+        if self.declaration_depth == Some(self.frames.len())
+            && matches!(previous, Some("var" | "let" | "const" | ","))
+        {
+            return None;
+        }
+
+        let in_key_position =
+            self.frames.last() == Some(&Frame::Object) && matches!(previous, Some("{" | ","));
+        if in_key_position {
+            // `{ key: value }` and `{ method() {} }`
+            if self.is_punctuator(index + 1, ":") || self.is_punctuator(index + 1, "(") {
+                return None;
+            }
+            // shorthand property
+            return (!self.is_local(&name) && !is_global(&name)).then_some(true);
+        }
+
+        (!self.is_local(&name) && !is_global(&name)).then_some(false)
+    }
+}
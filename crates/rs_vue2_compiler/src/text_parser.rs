@@ -1,7 +1,6 @@
 use crate::filter_parser::parse_filters;
 use lazy_static::lazy_static;
 use regex::Regex;
-use rs_html_parser_tokenizer_tokens::QuoteType;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
@@ -13,15 +12,15 @@ lazy_static! {
 
 fn build_regex(delimiters: &(String, String)) -> Arc<Regex> {
     let mut cache = REGEX_CACHE.lock().unwrap();
-    if let Some(regex) = cache.get(&delimiters) {
+    if let Some(regex) = cache.get(delimiters) {
         return Arc::clone(regex);
     }
     let (open, close) = &delimiters;
     let regex = Arc::new(
         Regex::new(&format!(
             "{}((?:.|\\n)+?){}",
-            regex::escape(&open),
-            regex::escape(&close)
+            regex::escape(open),
+            regex::escape(close)
         ))
         .unwrap(),
     );
@@ -51,10 +50,7 @@ pub fn parse_text(
             raw_tokens.push(token_value.clone());
             tokens.push(format!(r#""{}""#, token_value));
         }
-        let exp = parse_filters(&(
-            cap[1].trim().to_string().into_boxed_str(),
-            QuoteType::NoValue,
-        ));
+        let exp = parse_filters(cap[1].trim());
         tokens.push(format!("_s({})", exp));
         raw_tokens.push(format!("@binding: {}", exp));
         last_index = cap.get(0).unwrap().end();
@@ -1,14 +1,11 @@
+use crate::ast_tree::Token;
 use crate::MODIFIER_RE;
 use rs_html_parser_tokenizer_tokens::QuoteType;
-use rs_html_parser_tokens::Token;
 use std::collections::BTreeMap;
 
 pub fn has_attribute(token: &Token, str: &str) -> bool {
     if let Some(attrs) = &token.attrs {
-        return match attrs.get(str) {
-            Some(_) => true,
-            None => false,
-        };
+        return attrs.get(str).is_some();
     }
 
     false
@@ -27,11 +24,11 @@ pub fn get_attribute_value(token: &Token, str: &str) -> Option<Option<(Box<str>,
 }
 
 pub fn prepend_modifier_marker(symbol: char, name: &str, dynamic: bool) -> String {
-    return if dynamic {
-        format!("_p({}, \"{}\")", name, symbol)
+    if dynamic {
+        format!("_p({},\"{}\")", name, symbol)
     } else {
         format!("{}{}", symbol, name)
-    };
+    }
 }
 
 pub fn modifier_regex_replace_all_matches(input: &str) -> String {
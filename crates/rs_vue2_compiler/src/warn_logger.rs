@@ -1,18 +1,22 @@
+use crate::diagnostics::{CompilerDiagnostic, Severity};
+use std::cell::RefCell;
+use std::rc::Rc;
+
 pub trait WarnLogger {
     fn call(&mut self, msg: &str);
     fn clone_box(&self) -> Box<dyn WarnLogger>;
-}
 
-pub trait CloneableWarnLogger: WarnLogger {
-    fn clone_box(&self) -> Box<dyn CloneableWarnLogger>;
-}
+    // tips are hints that don't indicate a broken template,
+    // plain loggers report them like any other warning
+    fn tip(&mut self, msg: &str) {
+        self.call(msg)
+    }
 
-impl<T> CloneableWarnLogger for T
-where
-    T: WarnLogger + Clone + 'static,
-{
-    fn clone_box(&self) -> Box<dyn CloneableWarnLogger> {
-        Box::new(self.clone())
+    fn diagnostic(&mut self, diagnostic: CompilerDiagnostic) {
+        match diagnostic.severity {
+            Severity::Tip => self.tip(&diagnostic.message()),
+            Severity::Error | Severity::Warn => self.call(&diagnostic.message()),
+        }
     }
 }
 
@@ -33,3 +37,33 @@ impl Clone for Box<dyn WarnLogger> {
         self.clone_box()
     }
 }
+
+/// Collects warnings and tips separately, used by `compile`.
+#[derive(Clone, Default)]
+pub struct CollectingWarnLogger {
+    pub errors: Rc<RefCell<Vec<String>>>,
+    pub tips: Rc<RefCell<Vec<String>>>,
+    pub diagnostics: Rc<RefCell<Vec<CompilerDiagnostic>>>,
+}
+
+impl WarnLogger for CollectingWarnLogger {
+    fn call(&mut self, msg: &str) {
+        self.errors.borrow_mut().push(msg.to_string());
+    }
+
+    fn clone_box(&self) -> Box<dyn WarnLogger> {
+        Box::new(self.clone())
+    }
+
+    fn tip(&mut self, msg: &str) {
+        self.tips.borrow_mut().push(msg.to_string());
+    }
+
+    fn diagnostic(&mut self, diagnostic: CompilerDiagnostic) {
+        match diagnostic.severity {
+            Severity::Tip => self.tip(&diagnostic.message()),
+            Severity::Error | Severity::Warn => self.call(&diagnostic.message()),
+        }
+        self.diagnostics.borrow_mut().push(diagnostic);
+    }
+}
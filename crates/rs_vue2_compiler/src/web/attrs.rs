@@ -1,4 +1,55 @@
+use lazy_static::lazy_static;
 use rs_html_parser_tokenizer_tokens::QuoteType;
+use std::collections::HashSet;
+
+lazy_static! {
+    static ref BOOLEAN_ATTRS: HashSet<&'static str> = "allowfullscreen,async,autofocus,\
+        autoplay,checked,compact,controls,declare,default,defaultchecked,defaultmuted,\
+        defaultselected,defer,disabled,enabled,formnovalidate,hidden,indeterminate,inert,\
+        ismap,itemscope,loop,multiple,muted,nohref,noresize,noshade,novalidate,nowrap,open,\
+        pauseonexit,readonly,required,reversed,scoped,seamless,selected,sortable,\
+        truespeed,typemustmatch,visible"
+        .split(',')
+        .collect();
+    // attributes the server renderer outputs for dom props
+    static ref RENDERABLE_ATTRS: HashSet<&'static str> = "accept,accept-charset,accesskey,\
+        action,align,alt,async,autocomplete,autofocus,autoplay,autosave,bgcolor,border,\
+        buffered,challenge,charset,checked,cite,class,code,codebase,color,cols,colspan,\
+        content,contenteditable,contextmenu,controls,coords,data,datetime,default,defer,dir,\
+        dirname,disabled,download,draggable,dropzone,enctype,for,form,formaction,headers,\
+        height,hidden,high,href,hreflang,http-equiv,icon,id,ismap,itemprop,keytype,kind,\
+        label,lang,language,list,loop,low,manifest,max,maxlength,media,method,GET,POST,min,\
+        multiple,email,file,muted,name,novalidate,open,optimum,pattern,ping,placeholder,\
+        poster,preload,radiogroup,readonly,rel,required,reversed,rows,rowspan,sandbox,scope,\
+        scoped,seamless,selected,shape,size,type,text,password,sizes,span,spellcheck,src,\
+        srcdoc,srclang,srcset,start,step,style,summary,tabindex,target,title,usemap,value,\
+        width,wrap"
+        .split(',')
+        .collect();
+}
+
+pub fn is_boolean_attr(name: &str) -> bool {
+    BOOLEAN_ATTRS.contains(name)
+}
+
+pub fn is_enumerated_attr(name: &str) -> bool {
+    matches!(name, "contenteditable" | "draggable" | "spellcheck")
+}
+
+pub fn is_renderable_attr(name: &str) -> bool {
+    RENDERABLE_ATTRS.contains(name) || name.starts_with("data-") || name.starts_with("aria-")
+}
+
+// dom property names which differ from their attribute
+pub fn prop_to_attr(name: &str) -> String {
+    match name {
+        "acceptCharset" => "accept-charset".to_string(),
+        "className" => "class".to_string(),
+        "htmlFor" => "for".to_string(),
+        "httpEquiv" => "http-equiv".to_string(),
+        _ => name.to_lowercase(),
+    }
+}
 
 fn accept_value(tag: &str) -> bool {
     matches!(tag, "input" | "textarea" | "option" | "select" | "progress")
@@ -13,7 +64,7 @@ pub fn must_use_prop(
         && accept_value(tag)
         && type_attribute
             .as_ref()
-            .is_some_and(|x1| x1.as_ref().is_some_and(|x2| x2.0.as_ref() != "button"))
+            .is_none_or(|x1| x1.as_ref().is_none_or(|x2| x2.0.as_ref() != "button"))
     {
         return true;
     }
@@ -30,5 +81,5 @@ pub fn must_use_prop(
         return true;
     }
 
-    return false;
+    false
 }
@@ -1,3 +1,4 @@
 pub mod class;
+pub mod directives;
 pub mod model;
 pub mod style;
@@ -1,4 +1,6 @@
 use crate::ast_tree::{ASTNode, ASTTree};
+use crate::diagnostics::{CompilerDiagnostic, DiagnosticCode};
+use crate::helpers::to_json_string;
 use crate::text_parser::parse_text;
 use crate::{CompilerOptions, ModuleApi};
 use std::cell::RefCell;
@@ -14,24 +16,30 @@ impl ModuleApi for ClassModule {
 
         if let Some(static_class) = &static_class {
             if let Some(static_class_val) = &static_class.value {
-                node.el.static_class = Some(static_class_val.replace(" ", "").to_string());
+                node.el.static_class = Some(
+                    static_class_val
+                        .split_whitespace()
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                );
 
                 if node.is_dev {
                     let parsed = parse_text(static_class_val, &options.delimiters);
 
                     if parsed.is_some() {
-                        node.warn.call(&format!(
-                            "class=\"{}\": Interpolation inside attributes has been removed. \
-                Use v-bind or the colon shorthand instead. For example, \
-                instead of <div class=\"{{ val }}\">, use <div :class=\"val\">.",
-                            static_class_val
-                        ));
+                        let diagnostic =
+                            CompilerDiagnostic::new(DiagnosticCode::InterpolationInAttribute)
+                                .arg("class")
+                                .arg(static_class_val.as_str())
+                                .arg("class")
+                                .attr("class");
+                        node.report(diagnostic);
                     }
                 }
             }
         }
 
-        let class_binding = node.get_binding_attr("class", true);
+        let class_binding = node.get_binding_attr("class", false);
 
         if !class_binding.is_empty() {
             node.el.class_binding = Some(class_binding);
@@ -42,7 +50,7 @@ impl ModuleApi for ClassModule {
         let mut data = String::new();
 
         if let Some(static_class) = &node.el.static_class {
-            data += &format!("staticClass:{},", static_class);
+            data += &format!("staticClass:{},", to_json_string(static_class));
         }
 
         if let Some(class_binding) = &node.el.class_binding {
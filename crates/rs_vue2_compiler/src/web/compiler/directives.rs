@@ -0,0 +1,14 @@
+use crate::ast_tree::{ASTNode, Directive};
+
+pub mod model;
+
+// compile-time directive that manipulates the AST,
+// returns true if it also needs a runtime counterpart
+pub type PlatformDirective = fn(node: &mut ASTNode, dir: &Directive) -> bool;
+
+pub fn get_platform_directive(name: &str) -> Option<PlatformDirective> {
+    match name {
+        "model" => Some(model::model),
+        _ => None,
+    }
+}
@@ -0,0 +1,220 @@
+use crate::ast_tree::{ASTNode, Directive};
+use crate::diagnostics::{CompilerDiagnostic, DiagnosticCode};
+use crate::directives_model::{gen_assignment_code, gen_component_model};
+use crate::helpers::to_json_string;
+use crate::web::element::is_reserved_tag;
+use rs_html_parser_tokenizer_tokens::QuoteType;
+
+// in some cases, the event used has to be determined at runtime
+// so we used some reserved tokens during compile.
+pub const RANGE_TOKEN: &str = "__r";
+
+pub fn model(node: &mut ASTNode, dir: &Directive) -> bool {
+    let value = dir.value.as_deref().unwrap_or_default();
+    let tag = node.el.token.data.to_string();
+    let input_type = node.get_raw_attr_value("type").map(|t| t.to_string());
+
+    // inputs with type="file" are read only and setting the input's
+    // value will throw an error.
+    if node.is_dev && tag == "input" && input_type.as_deref() == Some("file") {
+        node.report(
+            CompilerDiagnostic::new(DiagnosticCode::VModelOnFileInput)
+                .arg(tag.as_str())
+                .arg(value)
+                .attr(dir.raw_name.as_str()),
+        );
+    }
+
+    if node.el.component.is_some() {
+        gen_component_model(node, value, &dir.modifiers);
+        // component v-model doesn't need extra runtime
+        return false;
+    } else if tag == "select" {
+        gen_select(node, value, dir);
+    } else if tag == "input" && input_type.as_deref() == Some("checkbox") {
+        gen_checkbox_model(node, value, dir);
+    } else if tag == "input" && input_type.as_deref() == Some("radio") {
+        gen_radio_model(node, value, dir);
+    } else if tag == "input" || tag == "textarea" {
+        gen_default_model(node, value, dir, input_type.as_deref());
+    } else if !is_reserved_tag(&tag) {
+        gen_component_model(node, value, &dir.modifiers);
+        // component v-model doesn't need extra runtime
+        return false;
+    } else if node.is_dev {
+        node.report(
+            CompilerDiagnostic::new(DiagnosticCode::VModelUnsupportedElement)
+                .arg(tag.as_str())
+                .arg(value)
+                .attr(dir.raw_name.as_str()),
+        );
+    }
+
+    // ensure runtime directive metadata
+    true
+}
+
+// same as getBindingAttr, static values are turned into string literals
+fn get_binding_value(node: &mut ASTNode, name: &'static str) -> Option<String> {
+    let is_literal = node.is_literal_binding(name);
+    let value = node.get_binding_attr(name, true);
+
+    if is_literal {
+        Some(to_json_string(&value))
+    } else if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+fn gen_checkbox_model(node: &mut ASTNode, value: &str, dir: &Directive) {
+    let number = dir.modifiers.contains("number");
+    let value_binding = get_binding_value(node, "value").unwrap_or_else(|| "null".to_string());
+    let true_value_binding =
+        get_binding_value(node, "true-value").unwrap_or_else(|| "true".to_string());
+    let false_value_binding =
+        get_binding_value(node, "false-value").unwrap_or_else(|| "false".to_string());
+
+    node.insert_into_props(
+        "checked",
+        Some(format!(
+            "Array.isArray({})?_i({},{})>-1{}",
+            value,
+            value,
+            value_binding,
+            if true_value_binding == "true" {
+                format!(":({})", value)
+            } else {
+                format!(":_q({},{})", value, true_value_binding)
+            }
+        )),
+        QuoteType::NoValue,
+        false,
+    );
+    node.add_handler(
+        "change",
+        &format!(
+            "var $$a={},$$el=$event.target,$$c=$$el.checked?({}):({});\
+            if(Array.isArray($$a)){{var $$v={},$$i=_i($$a,$$v);\
+            if($$el.checked){{$$i<0&&({})}}\
+            else{{$$i>-1&&({})}}\
+            }}else{{{}}}",
+            value,
+            true_value_binding,
+            false_value_binding,
+            if number {
+                format!("_n({})", value_binding)
+            } else {
+                value_binding
+            },
+            gen_assignment_code(value, "$$a.concat([$$v])"),
+            gen_assignment_code(value, "$$a.slice(0,$$i).concat($$a.slice($$i+1))"),
+            gen_assignment_code(value, "$$c")
+        ),
+        None,
+        true,
+        false,
+    );
+}
+
+fn gen_radio_model(node: &mut ASTNode, value: &str, dir: &Directive) {
+    let number = dir.modifiers.contains("number");
+    let mut value_binding = get_binding_value(node, "value").unwrap_or_else(|| "null".to_string());
+    if number {
+        value_binding = format!("_n({})", value_binding);
+    }
+
+    node.insert_into_props(
+        "checked",
+        Some(format!("_q({},{})", value, value_binding)),
+        QuoteType::NoValue,
+        false,
+    );
+    node.add_handler(
+        "change",
+        &gen_assignment_code(value, &value_binding),
+        None,
+        true,
+        false,
+    );
+}
+
+fn gen_select(node: &mut ASTNode, value: &str, dir: &Directive) {
+    let number = dir.modifiers.contains("number");
+    let selected_val = format!(
+        "Array.prototype.filter.call($event.target.options,function(o){{return o.selected}})\
+        .map(function(o){{var val = \"_value\" in o ? o._value : o.value;return {}}})",
+        if number { "_n(val)" } else { "val" }
+    );
+
+    let assignment = "$event.target.multiple ? $$selectedVal : $$selectedVal[0]";
+    let code = format!(
+        "var $$selectedVal = {}; {}",
+        selected_val,
+        gen_assignment_code(value, assignment)
+    );
+    node.add_handler("change", &code, None, true, false);
+}
+
+fn gen_default_model(node: &mut ASTNode, value: &str, dir: &Directive, input_type: Option<&str>) {
+    // warn if v-bind:value conflicts with v-model
+    // except for inputs with v-bind:type
+    if node.is_dev {
+        let binding = if node.has_raw_attr("v-bind:value") {
+            Some("v-bind:value")
+        } else if node.has_raw_attr(":value") {
+            Some(":value")
+        } else {
+            None
+        };
+        let has_type_binding = node.has_raw_attr("v-bind:type") || node.has_raw_attr(":type");
+
+        if let Some(binding) = binding {
+            if !has_type_binding {
+                let binding_value = node.get_raw_attr_value(binding).unwrap_or_default();
+                let diagnostic = CompilerDiagnostic::new(DiagnosticCode::VModelValueConflict)
+                    .arg(binding)
+                    .arg(binding_value)
+                    .attr(binding);
+                node.report(diagnostic);
+            }
+        }
+    }
+
+    let lazy = dir.modifiers.contains("lazy");
+    let number = dir.modifiers.contains("number");
+    let trim = dir.modifiers.contains("trim");
+    let need_composition_guard = !lazy && input_type != Some("range");
+    let event = if lazy {
+        "change"
+    } else if input_type == Some("range") {
+        RANGE_TOKEN
+    } else {
+        "input"
+    };
+
+    let mut value_expression = "$event.target.value".to_string();
+    if trim {
+        value_expression = "$event.target.value.trim()".to_string();
+    }
+    if number {
+        value_expression = format!("_n({})", value_expression);
+    }
+
+    let mut code = gen_assignment_code(value, &value_expression);
+    if need_composition_guard {
+        code = format!("if($event.target.composing)return;{}", code);
+    }
+
+    node.insert_into_props(
+        "value",
+        Some(format!("({})", value)),
+        QuoteType::NoValue,
+        false,
+    );
+    node.add_handler(event, &code, None, true, false);
+    if trim || number {
+        node.add_handler("blur", "$forceUpdate()", None, false, false);
+    }
+}
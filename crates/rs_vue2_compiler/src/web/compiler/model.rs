@@ -1,28 +1,30 @@
-use crate::ast_tree::{create_ast_element, ASTElementKind, ASTNode, ASTTree, IfCondition};
+use crate::ast_tree::{
+    create_ast_element, ASTElementKind, ASTNode, ASTTree, IfCondition, TokenAttrs,
+};
+use crate::diagnostics::{CompilerDiagnostic, DiagnosticCode};
 use crate::{CompilerOptions, ModuleApi};
 use rs_html_parser_tokenizer_tokens::QuoteType;
-use rs_html_parser_tokens::Token;
 use std::cell::RefCell;
 use std::rc::Rc;
-use unicase_collections::unicase_btree_map::UniCaseBTreeMap;
 
 pub struct ModelModule {}
 
 fn node_copy(node: &ASTNode, tree: &ASTTree) -> Rc<RefCell<ASTNode>> {
-    tree.create(
-        create_ast_element(
-            Token {
-                data: node.el.token.data.clone(),
-                attrs: node.el.token.attrs.clone(),
-                kind: node.el.token.kind,
-                is_implied: node.el.token.is_implied,
-            },
-            ASTElementKind::Element,
-        ),
+    let copy = tree.create(
+        create_ast_element(node.el.token.clone(), ASTElementKind::Element),
         node.parent_id,
         node.is_dev,
         node.warn.clone_box(),
-    )
+    );
+
+    {
+        let mut copy_node = copy.borrow_mut();
+        copy_node.el.start = node.el.start;
+        copy_node.el.end = node.el.end;
+        copy_node.el.raw_attrs_map = node.el.raw_attrs_map.clone();
+    }
+
+    copy
 }
 
 impl ModuleApi for ModelModule {
@@ -54,12 +56,8 @@ impl ModuleApi for ModelModule {
                 }
                 if type_binding.is_none() && !map.contains_key("type") && map.contains_key("v-bind")
                 {
-                    let v_bind_attr = map.get("v-bind");
-
-                    if let Some(v_bind_attr) = v_bind_attr {
-                        if let Some((v_bind_attr, _)) = v_bind_attr {
-                            type_binding = Some(format!("({}).type", v_bind_attr));
-                        }
+                    if let Some(Some((v_bind_attr, _))) = map.get("v-bind") {
+                        type_binding = Some(format!("({}).type", v_bind_attr));
                     }
                 }
 
@@ -88,7 +86,7 @@ impl ModuleApi for ModelModule {
                             .el
                             .token
                             .attrs
-                            .get_or_insert(UniCaseBTreeMap::new())
+                            .get_or_insert_with(TokenAttrs::new)
                             .insert(
                                 "type",
                                 Some(("checkbox".to_string().into_boxed_str(), QuoteType::Double)),
@@ -114,7 +112,7 @@ impl ModuleApi for ModelModule {
                             .el
                             .token
                             .attrs
-                            .get_or_insert(UniCaseBTreeMap::new())
+                            .get_or_insert_with(TokenAttrs::new)
                             .insert(
                                 "type",
                                 Some(("radio".to_string().into_boxed_str(), QuoteType::Double)),
@@ -137,7 +135,7 @@ impl ModuleApi for ModelModule {
                             .el
                             .token
                             .attrs
-                            .get_or_insert(UniCaseBTreeMap::new())
+                            .get_or_insert_with(TokenAttrs::new)
                             .insert(
                                 ":type",
                                 Some((
@@ -157,7 +155,10 @@ impl ModuleApi for ModelModule {
                             if let Some(else_if_val) = else_if_condition.value {
                                 branch0.el.else_if_val = Some(else_if_val);
                             } else {
-                                node.warn.call("empty v-else-if condition");
+                                node.report(
+                                    CompilerDiagnostic::new(DiagnosticCode::EmptyVElseIfCondition)
+                                        .attr("v-else-if"),
+                                );
                             }
                         }
                     }
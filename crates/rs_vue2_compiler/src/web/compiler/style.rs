@@ -1,4 +1,5 @@
 use crate::ast_tree::{ASTNode, ASTTree};
+use crate::diagnostics::{CompilerDiagnostic, DiagnosticCode};
 use crate::text_parser::parse_text;
 use crate::util::parse_style_text;
 use crate::{CompilerOptions, ModuleApi};
@@ -16,12 +17,13 @@ impl ModuleApi for StyleModule {
                 if node.is_dev {
                     let res = parse_text(static_style, &options.delimiters);
                     if res.is_some() {
-                        node.warn.call(&format!(
-                            "style=\"{}\": Interpolation inside attributes has been removed. \
-                        Use v-bind or the colon shorthand instead. For example, \
-                        instead of <div style=\"{{ val }}\">, use <div :style=\"val\">.",
-                            static_style
-                        ));
+                        let diagnostic =
+                            CompilerDiagnostic::new(DiagnosticCode::InterpolationInAttribute)
+                                .arg("style")
+                                .arg(static_style.as_str())
+                                .arg("style")
+                                .attr("style");
+                        node.report(diagnostic);
                     }
                 }
                 let result = serde_json::to_string(&parse_style_text(static_style));
@@ -31,7 +33,10 @@ impl ModuleApi for StyleModule {
                     }
                     Err(err) => {
                         node.el.static_style = None;
-                        node.warn.call(&format!("Failed to parse style: {}", err));
+                        let diagnostic = CompilerDiagnostic::new(DiagnosticCode::StyleParseFailed)
+                            .arg(err.to_string())
+                            .attr("style");
+                        node.report(diagnostic);
                     }
                 }
             }
@@ -1,27 +1,75 @@
 use lazy_static::lazy_static;
-use unicase_collections::unicase_btree_set::UniCaseBTreeSet;
+use std::collections::HashSet;
 
+// tag names are matched case-sensitively like vue does, so `<Button>` or `<Col>` are
+// components and not html elements. Only svg tags are matched ignoring case
 lazy_static! {
-    static ref SVG_TAGS: UniCaseBTreeSet = {
-        let mut set = UniCaseBTreeSet::new();
-        let words = "svg,animate,circle,clippath,cursor,defs,desc,ellipse,filter,font-face,\
-            foreignobject,g,glyph,image,line,marker,mask,missing-glyph,path,pattern,\
-            polygon,polyline,rect,switch,symbol,text,textpath,tspan,use,view";
-        for word in words.split(',') {
-            set.insert(word.to_string());
-        }
-        set
-    };
+    static ref HTML_TAGS: HashSet<&'static str> = "html,body,base,head,link,meta,style,title,\
+        address,article,aside,footer,header,h1,h2,h3,h4,h5,h6,hgroup,nav,section,\
+        div,dd,dl,dt,figcaption,figure,picture,hr,img,li,main,ol,p,pre,ul,\
+        a,b,abbr,bdi,bdo,br,cite,code,data,dfn,em,i,kbd,mark,q,rp,rt,rtc,ruby,\
+        s,samp,small,span,strong,sub,sup,time,u,var,wbr,area,audio,map,track,video,\
+        embed,object,param,source,canvas,script,noscript,del,ins,\
+        caption,col,colgroup,table,thead,tbody,td,th,tr,\
+        button,datalist,fieldset,form,input,label,legend,meter,optgroup,option,\
+        output,progress,select,textarea,\
+        details,dialog,menu,menuitem,summary,\
+        content,element,shadow,template,blockquote,iframe,tfoot"
+        .split(',')
+        .collect();
+    static ref UNARY_TAGS: HashSet<&'static str> = "area,base,br,col,embed,frame,hr,img,input,\
+        isindex,keygen,link,meta,param,source,track,wbr"
+        .split(',')
+        .collect();
+    // elements whose end tag can be omitted, closed by an opening sibling of the same type.
+    // "options" is kept as in vue, sibling <option>s are nested like the js compiler does
+    static ref CAN_BE_LEFT_OPEN_TAGS: HashSet<&'static str> =
+        "colgroup,dd,dt,li,options,p,td,tfoot,th,thead,tr,source"
+            .split(',')
+            .collect();
+    // elements that can't be placed inside a <p>, their start tag closes an open one
+    static ref NON_PHRASING_TAGS: HashSet<&'static str> = "address,article,aside,base,\
+        blockquote,body,caption,col,colgroup,dd,details,dialog,div,dl,dt,fieldset,figcaption,\
+        figure,footer,form,h1,h2,h3,h4,h5,h6,head,header,hgroup,hr,html,legend,li,menuitem,\
+        meta,optgroup,option,param,rp,rt,source,style,summary,tbody,td,tfoot,th,thead,title,\
+        tr,track"
+        .split(',')
+        .collect();
+    static ref SVG_TAGS: HashSet<&'static str> = "svg,animate,circle,clippath,cursor,defs,desc,\
+        ellipse,filter,font-face,foreignobject,g,glyph,image,line,marker,mask,missing-glyph,\
+        path,pattern,polygon,polyline,rect,switch,symbol,text,textpath,tspan,use,view"
+        .split(',')
+        .collect();
+}
+
+pub fn is_unary_tag(tag: &str) -> bool {
+    UNARY_TAGS.contains(tag)
+}
+
+pub fn can_be_left_open_tag(tag: &str) -> bool {
+    CAN_BE_LEFT_OPEN_TAGS.contains(tag)
+}
+
+pub fn is_non_phrasing_tag(tag: &str) -> bool {
+    NON_PHRASING_TAGS.contains(tag)
+}
+
+pub fn is_html_tag(tag: &str) -> bool {
+    HTML_TAGS.contains(tag)
 }
 
 pub fn is_svg_tag(tag: &str) -> bool {
-    SVG_TAGS.contains(tag)
+    SVG_TAGS.contains(tag.to_ascii_lowercase().as_str())
+}
+
+pub fn is_reserved_tag(tag: &str) -> bool {
+    is_html_tag(tag) || is_svg_tag(tag)
 }
 
 pub fn get_namespace(tag: &str) -> Option<&'static str> {
     if is_svg_tag(tag) {
         Some("svg")
-    } else if tag.eq_ignore_ascii_case("math") {
+    } else if tag == "math" {
         Some("math")
     } else {
         None
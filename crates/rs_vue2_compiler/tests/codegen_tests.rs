@@ -0,0 +1,578 @@
+#[cfg(test)]
+mod tests {
+    use rs_vue2_compiler::codegen::generate;
+    use rs_vue2_compiler::optimizer::optimize;
+    use rs_vue2_compiler::web::compiler::class::ClassModule;
+    use rs_vue2_compiler::web::compiler::model::ModelModule;
+    use rs_vue2_compiler::web::compiler::style::StyleModule;
+    use rs_vue2_compiler::{CompilerOptions, VueParser, WhitespaceHandling};
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::rc::Rc;
+
+    fn create_options(warnings: &Rc<RefCell<Vec<String>>>) -> CompilerOptions {
+        let warnings_clone = Rc::clone(warnings);
+
+        CompilerOptions {
+            dev: true,
+            is_ssr: false,
+            v_bind_prop_short_hand: false,
+            preserve_comments: false,
+            whitespace_handling: WhitespaceHandling::Condense,
+            new_slot_syntax: true,
+            is_pre_tag: None,
+            get_namespace: None,
+            warn: Some(Box::new(move |msg: &str| {
+                warnings_clone.borrow_mut().push(msg.to_string());
+            })),
+            delimiters: None,
+            key_codes: None,
+            scope_id: None,
+            strip_with: false,
+            modules: Some(vec![
+                Box::new(ClassModule {}),
+                Box::new(ModelModule {}),
+                Box::new(StyleModule {}),
+            ]),
+        }
+    }
+
+    fn assert_codegen(template: &str, expected: &str) -> Rc<RefCell<Vec<String>>> {
+        let warnings = Rc::new(RefCell::new(Vec::new()));
+        let options = create_options(&warnings);
+        assert_codegen_with_options(template, expected, &options);
+
+        warnings
+    }
+
+    fn assert_codegen_with_options(template: &str, expected: &str, options: &CompilerOptions) {
+        let mut parser = VueParser::new(options);
+        let ast = parser.parse(template);
+
+        let res = generate(&ast, options);
+        assert_eq!(res.render, expected);
+    }
+
+    #[test]
+    fn generate_directive() {
+        assert_codegen(
+            "<p v-custom1:arg1.modifier=\"value1\"></p>",
+            "with(this){return _c('p',{directives:[{name:\"custom1\",rawName:\"v-custom1:arg1.modifier\",value:(value1),expression:\"value1\",arg:\"arg1\",modifiers:{\"modifier\":true}}]})}",
+        );
+        assert_codegen(
+            "<p v-custom2></p>",
+            "with(this){return _c('p',{directives:[{name:\"custom2\",rawName:\"v-custom2\"}]})}",
+        );
+    }
+
+    #[test]
+    fn generate_twice() {
+        let warnings = Rc::new(RefCell::new(Vec::new()));
+        let options = create_options(&warnings);
+        let mut parser = VueParser::new(&options);
+        let ast = parser.parse(
+            "<div><p v-if=\"ok\"><b>static</b></p><span v-else-if=\"a\"></span><li v-for=\"item in items\" :key=\"item\"><b v-once>{{ item }}</b></li><input v-model=\"msg\" @input=\"onInput\"></div>",
+        );
+        optimize(&ast, &options);
+
+        let first = generate(&ast, &options);
+        let second = generate(&ast, &options);
+        assert_eq!(first.render, second.render);
+        assert_eq!(first.static_render_fns, second.static_render_fns);
+        assert!(first.render.contains("(ok)?"));
+        assert!(first.render.contains("_l((items)"));
+    }
+
+    #[test]
+    fn generate_filters() {
+        assert_codegen(
+            "<div :id=\"a | b | c\">{{ d | e | f }}</div>",
+            "with(this){return _c('div',{attrs:{\"id\":_f(\"c\")(_f(\"b\")(a))}},[_v(_s(_f(\"f\")(_f(\"e\")(d))))])}",
+        );
+    }
+
+    #[test]
+    fn generate_v_for() {
+        assert_codegen(
+            "<ul><li v-for=\"item in items\">{{ item }}</li></ul>",
+            "with(this){return _c('ul',_l((items),function(item){return _c('li',[_v(_s(item))])}),0)}",
+        );
+        assert_codegen(
+            "<ul><li v-for=\"(item, index) in items\">{{ item }}-{{ index }}</li></ul>",
+            "with(this){return _c('ul',_l((items),function(item,index){return _c('li',[_v(_s(item)+\"-\"+_s(index))])}),0)}",
+        );
+        assert_codegen(
+            "<div><div v-for=\"(value, key, index) in items\">{{ value }}</div></div>",
+            "with(this){return _c('div',_l((items),function(value,key,index){return _c('div',[_v(_s(value))])}),0)}",
+        );
+        assert_codegen(
+            "<div><template v-for=\"item in items\"><span></span></template></div>",
+            "with(this){return _c('div',[_l((items),function(item){return [_c('span')]})],2)}",
+        );
+    }
+
+    #[test]
+    fn generate_v_if() {
+        assert_codegen(
+            "<p v-if=\"show\">hello</p>",
+            "with(this){return (show)?_c('p',[_v(\"hello\")]):_e()}",
+        );
+        assert_codegen(
+            "<div><p v-if=\"show\">hello</p><p v-else-if=\"other\">world</p><p v-else>bye</p></div>",
+            "with(this){return _c('div',[(show)?_c('p',[_v(\"hello\")]):(other)?_c('p',[_v(\"world\")]):_c('p',[_v(\"bye\")])])}",
+        );
+    }
+
+    #[test]
+    fn generate_key_and_ref() {
+        assert_codegen(
+            "<div><p v-for=\"item in items\" :key=\"item.uid\" ref=\"p\"></p></div>",
+            "with(this){return _c('div',_l((items),function(item){return _c('p',{key:item.uid,ref:\"p\",refInFor:true})}),0)}",
+        );
+    }
+
+    #[test]
+    fn generate_attrs_and_dynamic_attrs() {
+        assert_codegen(
+            "<p :name=\"name\"></p>",
+            "with(this){return _c('p',{attrs:{\"name\":name}})}",
+        );
+        assert_codegen(
+            "<p :[name]=\"value\"></p>",
+            "with(this){return _c('p',_b({},\"p\",_d({},[name,value])))}",
+        );
+    }
+
+    #[test]
+    fn generate_v_bind_and_v_on_objects() {
+        assert_codegen(
+            "<p v-bind=\"test\"></p>",
+            "with(this){return _c('p',_b({},'p',test,false))}",
+        );
+        assert_codegen(
+            "<p v-on=\"listeners\"></p>",
+            "with(this){return _c('p',_g({},listeners))}",
+        );
+    }
+
+    #[test]
+    fn generate_slot_target() {
+        assert_codegen(
+            "<p slot=\"one\">hello world</p>",
+            "with(this){return _c('p',{attrs:{\"slot\":\"one\"},slot:\"one\"},[_v(\"hello world\")])}",
+        );
+    }
+
+    #[test]
+    fn generate_component() {
+        assert_codegen(
+            "<component is=\"component1\"></component>",
+            "with(this){return _c(\"component1\",{tag:\"component\"})}",
+        );
+        assert_codegen(
+            "<component :is=\"component1\"></component>",
+            "with(this){return _c(component1,{tag:\"component\"})}",
+        );
+    }
+
+    #[test]
+    fn generate_template_children() {
+        assert_codegen(
+            "<div><template><p>{{hello}}</p></template></div>",
+            "with(this){return _c('div',[[_c('p',[_v(_s(hello))])]],2)}",
+        );
+    }
+
+    #[test]
+    fn generate_inline_template() {
+        assert_codegen(
+            "<my-component inline-template><p><span>hello world</span></p></my-component>",
+            "with(this){return _c('my-component',{inlineTemplate:{render:function(){with(this){return _c('p',[_c('span',[_v(\"hello world\")])])}},staticRenderFns:[]}})}",
+        );
+
+        let warnings = assert_codegen(
+            "<my-component inline-template><p></p><p></p></my-component>",
+            "with(this){return _c('my-component',{inlineTemplate:{render:function(){with(this){return _c('p')}},staticRenderFns:[]}})}",
+        );
+        assert!(warnings.borrow().contains(
+            &"Inline-template components must have exactly one child element.".to_string()
+        ));
+    }
+
+    #[test]
+    fn generate_components_named_like_html_tags() {
+        assert_codegen(
+            "<div><Button>hi</Button><p>x</p></div>",
+            "with(this){return _c('div',[_c('Button',[_v(\"hi\")]),_c('p',[_v(\"x\")])],1)}",
+        );
+        assert_codegen(
+            "<Row><Col span=\"12\">content</Col></Row>",
+            "with(this){return _c('Row',[_c('Col',{attrs:{\"span\":\"12\"}},[_v(\"content\")])],1)}",
+        );
+        assert_codegen(
+            "<Table><Tr><Td>a</Td></Tr></Table>",
+            "with(this){return _c('Table',[_c('Tr',[_c('Td',[_v(\"a\")])],1)],1)}",
+        );
+    }
+
+    #[test]
+    fn generate_component_list_without_key_warning() {
+        let warnings = assert_codegen(
+            "<div><my-item v-for=\"item in items\"></my-item></div>",
+            "with(this){return _c('div',_l((items),function(item){return _c('my-item')}),1)}",
+        );
+        assert_eq!(warnings.borrow().len(), 1);
+    }
+
+    #[test]
+    fn generate_attributes_in_source_order() {
+        assert_codegen(
+            "<div v-foo=\"x\" :z=\"z\" v-bar id=\"a\" :b=\"b\" @b=\"f\" @a=\"g\"></div>",
+            "with(this){return _c('div',{directives:[{name:\"foo\",rawName:\"v-foo\",value:(x),expression:\"x\"},{name:\"bar\",rawName:\"v-bar\"}],attrs:{\"z\":z,\"id\":\"a\",\"b\":b},on:{\"b\":f,\"a\":g}})}",
+        );
+    }
+
+    #[test]
+    fn generate_events() {
+        assert_codegen(
+            "<input @input=\"onInput\">",
+            "with(this){return _c('input',{on:{\"input\":onInput}})}",
+        );
+        assert_codegen(
+            "<input @input=\"onInput($event);\">",
+            "with(this){return _c('input',{on:{\"input\":function($event){return onInput($event);}}})}",
+        );
+        assert_codegen(
+            "<input @input=\"current++\">",
+            "with(this){return _c('input',{on:{\"input\":function($event){current++}}})}",
+        );
+        assert_codegen(
+            "<input @input=\"e => current++\">",
+            "with(this){return _c('input',{on:{\"input\":e => current++}})}",
+        );
+        assert_codegen(
+            "<input @input=\"one\" v-on:input=\"two\">",
+            "with(this){return _c('input',{on:{\"input\":[one,two]}})}",
+        );
+    }
+
+    #[test]
+    fn generate_events_with_modifiers() {
+        assert_codegen(
+            "<input @input.stop=\"onInput\">",
+            "with(this){return _c('input',{on:{\"input\":function($event){$event.stopPropagation();return onInput.apply(null, arguments)}}})}",
+        );
+        assert_codegen(
+            "<input @input.self=\"function(e) { onInput(e) }\">",
+            "with(this){return _c('input',{on:{\"input\":function($event){if($event.target !== $event.currentTarget)return null;return (function(e) { onInput(e) }).apply(null, arguments)}}})}",
+        );
+        assert_codegen(
+            "<input @click.ctrl.exact=\"onClick\">",
+            "with(this){return _c('input',{on:{\"click\":function($event){if(!$event.ctrlKey)return null;if($event.shiftKey||$event.altKey||$event.metaKey)return null;return onClick.apply(null, arguments)}}})}",
+        );
+        assert_codegen(
+            "<input @click.left=\"onClick\">",
+            "with(this){return _c('input',{on:{\"click\":function($event){if(!$event.type.indexOf('key')&&_k($event.keyCode,\"left\",37,$event.key,[\"Left\",\"ArrowLeft\"]))return null;if('button' in $event && $event.button !== 0)return null;return onClick.apply(null, arguments)}}})}",
+        );
+        assert_codegen(
+            "<input @click.once.capture=\"onClick\">",
+            "with(this){return _c('input',{on:{\"~!click\":function($event){return onClick.apply(null, arguments)}}})}",
+        );
+    }
+
+    #[test]
+    fn generate_events_with_modifiers_in_source_order() {
+        // .prevent.self prevents every click, .self.prevent only clicks on the element itself
+        assert_codegen(
+            "<div @click.prevent.self=\"onClick\"></div>",
+            "with(this){return _c('div',{on:{\"click\":function($event){$event.preventDefault();if($event.target !== $event.currentTarget)return null;return onClick.apply(null, arguments)}}})}",
+        );
+        assert_codegen(
+            "<div @click.self.prevent=\"onClick\"></div>",
+            "with(this){return _c('div',{on:{\"click\":function($event){if($event.target !== $event.currentTarget)return null;$event.preventDefault();return onClick.apply(null, arguments)}}})}",
+        );
+        // removing .right keeps the order of the others
+        assert_codegen(
+            "<div @click.right.stop.prevent=\"onClick\"></div>",
+            "with(this){return _c('div',{on:{\"contextmenu\":function($event){$event.stopPropagation();$event.preventDefault();return onClick.apply(null, arguments)}}})}",
+        );
+    }
+
+    #[test]
+    fn generate_events_with_key_modifiers() {
+        assert_codegen(
+            "<input @keyup.enter=\"onEnter\">",
+            "with(this){return _c('input',{on:{\"keyup\":function($event){if(!$event.type.indexOf('key')&&_k($event.keyCode,\"enter\",13,$event.key,\"Enter\"))return null;return onEnter.apply(null, arguments)}}})}",
+        );
+        assert_codegen(
+            "<input @keyup.13=\"onEnter\">",
+            "with(this){return _c('input',{on:{\"keyup\":function($event){if(!$event.type.indexOf('key')&&$event.keyCode!==13)return null;return onEnter.apply(null, arguments)}}})}",
+        );
+        assert_codegen(
+            "<input @keydown.delete=\"onDelete\">",
+            "with(this){return _c('input',{on:{\"keydown\":function($event){if(!$event.type.indexOf('key')&&_k($event.keyCode,\"delete\",[8,46],$event.key,[\"Backspace\",\"Delete\",\"Del\"]))return null;return onDelete.apply(null, arguments)}}})}",
+        );
+        assert_codegen(
+            "<input @keydown.page-down=\"onPageDown\">",
+            "with(this){return _c('input',{on:{\"keydown\":function($event){if(!$event.type.indexOf('key')&&_k($event.keyCode,\"page-down\",undefined,$event.key,undefined))return null;return onPageDown.apply(null, arguments)}}})}",
+        );
+        // key filters run before other modifiers
+        assert_codegen(
+            "<input @keyup.esc.stop=\"onEsc\">",
+            "with(this){return _c('input',{on:{\"keyup\":function($event){if(!$event.type.indexOf('key')&&_k($event.keyCode,\"esc\",27,$event.key,[\"Esc\",\"Escape\"]))return null;$event.stopPropagation();return onEsc.apply(null, arguments)}}})}",
+        );
+    }
+
+    #[test]
+    fn generate_events_with_user_key_codes() {
+        let warnings = Rc::new(RefCell::new(Vec::new()));
+        let mut options = create_options(&warnings);
+        options.key_codes = Some(HashMap::from([
+            ("f1".to_string(), vec![112]),
+            ("enter".to_string(), vec![13, 108]),
+        ]));
+
+        assert_codegen_with_options(
+            "<input @keyup.f1=\"onF1\">",
+            "with(this){return _c('input',{on:{\"keyup\":function($event){if(!$event.type.indexOf('key')&&_k($event.keyCode,\"f1\",112,$event.key,undefined))return null;return onF1.apply(null, arguments)}}})}",
+            &options,
+        );
+        assert_codegen_with_options(
+            "<input @keyup.enter=\"onEnter\">",
+            "with(this){return _c('input',{on:{\"keyup\":function($event){if(!$event.type.indexOf('key')&&_k($event.keyCode,\"enter\",[13,108],$event.key,\"Enter\"))return null;return onEnter.apply(null, arguments)}}})}",
+            &options,
+        );
+    }
+
+    #[test]
+    fn generate_dynamic_and_native_events() {
+        assert_codegen(
+            "<a @[event]=\"onClick\"></a>",
+            "with(this){return _c('a',{on:_d({},[event,onClick])})}",
+        );
+        assert_codegen(
+            "<a @[event].once=\"onClick\"></a>",
+            "with(this){return _c('a',{on:_d({},[_p(event,\"~\"),function($event){return onClick.apply(null, arguments)}])})}",
+        );
+        assert_codegen(
+            "<my-component @click.native=\"onClick\"></my-component>",
+            "with(this){return _c('my-component',{nativeOn:{\"click\":function($event){return onClick.apply(null, arguments)}}})}",
+        );
+    }
+
+    #[test]
+    fn generate_v_model_on_text_input() {
+        assert_codegen(
+            "<input v-model=\"msg\">",
+            "with(this){return _c('input',{directives:[{name:\"model\",rawName:\"v-model\",value:(msg),expression:\"msg\"}],domProps:{\"value\":(msg)},on:{\"input\":function($event){if($event.target.composing)return;msg=$event.target.value}}})}",
+        );
+        assert_codegen(
+            "<textarea v-model.lazy=\"obj.text\"></textarea>",
+            "with(this){return _c('textarea',{directives:[{name:\"model\",rawName:\"v-model.lazy\",value:(obj.text),expression:\"obj.text\",modifiers:{\"lazy\":true}}],domProps:{\"value\":(obj.text)},on:{\"change\":function($event){return $set(obj, \"text\", $event.target.value)}}})}",
+        );
+        assert_codegen(
+            "<input v-model.number.trim=\"num\">",
+            "with(this){return _c('input',{directives:[{name:\"model\",rawName:\"v-model.number.trim\",value:(num),expression:\"num\",modifiers:{\"number\":true,\"trim\":true}}],domProps:{\"value\":(num)},on:{\"input\":function($event){if($event.target.composing)return;num=_n($event.target.value.trim())},\"blur\":function($event){return $forceUpdate()}}})}",
+        );
+    }
+
+    #[test]
+    fn generate_v_model_on_checkbox_and_radio() {
+        assert_codegen(
+            "<input type=\"checkbox\" v-model=\"checked\">",
+            "with(this){return _c('input',{directives:[{name:\"model\",rawName:\"v-model\",value:(checked),expression:\"checked\"}],attrs:{\"type\":\"checkbox\"},domProps:{\"checked\":Array.isArray(checked)?_i(checked,null)>-1:(checked)},on:{\"change\":function($event){var $$a=checked,$$el=$event.target,$$c=$$el.checked?(true):(false);if(Array.isArray($$a)){var $$v=null,$$i=_i($$a,$$v);if($$el.checked){$$i<0&&(checked=$$a.concat([$$v]))}else{$$i>-1&&(checked=$$a.slice(0,$$i).concat($$a.slice($$i+1)))}}else{checked=$$c}}}})}",
+        );
+        assert_codegen(
+            "<input type=\"radio\" v-model=\"picked\" value=\"one\">",
+            "with(this){return _c('input',{directives:[{name:\"model\",rawName:\"v-model\",value:(picked),expression:\"picked\"}],attrs:{\"type\":\"radio\",\"value\":\"one\"},domProps:{\"checked\":_q(picked,\"one\")},on:{\"change\":function($event){picked=\"one\"}}})}",
+        );
+    }
+
+    #[test]
+    fn generate_v_model_on_select() {
+        assert_codegen(
+            "<select v-model.number=\"selected\"></select>",
+            "with(this){return _c('select',{directives:[{name:\"model\",rawName:\"v-model.number\",value:(selected),expression:\"selected\",modifiers:{\"number\":true}}],on:{\"change\":function($event){var $$selectedVal = Array.prototype.filter.call($event.target.options,function(o){return o.selected}).map(function(o){var val = \"_value\" in o ? o._value : o.value;return _n(val)}); selected=$event.target.multiple ? $$selectedVal : $$selectedVal[0]}}})}",
+        );
+    }
+
+    #[test]
+    fn generate_component_v_model() {
+        assert_codegen(
+            "<my-input v-model=\"msg\"></my-input>",
+            "with(this){return _c('my-input',{model:{value:(msg),callback:function ($$v) {msg=$$v},expression:\"msg\"}})}",
+        );
+        assert_codegen(
+            "<my-input v-model.trim=\"obj.msg\"></my-input>",
+            "with(this){return _c('my-input',{model:{value:(obj.msg),callback:function ($$v) {$set(obj, \"msg\", (typeof $$v === 'string'? $$v.trim(): $$v))},expression:\"obj.msg\"}})}",
+        );
+        assert_codegen(
+            "<component :is=\"comp\" v-model.number=\"list[index]\"></component>",
+            "with(this){return _c(comp,{tag:\"component\",model:{value:(list[index]),callback:function ($$v) {$set(list, index, _n($$v))},expression:\"list[index]\"}})}",
+        );
+
+        // components named like form elements get the component model
+        let warnings = assert_codegen(
+            "<div><Select v-model=\"x\"></Select><Input v-model=\"y\"></Input></div>",
+            "with(this){return _c('div',[_c('Select',{model:{value:(x),callback:function ($$v) {x=$$v},expression:\"x\"}}),_c('Input',{model:{value:(y),callback:function ($$v) {y=$$v},expression:\"y\"}})],1)}",
+        );
+        assert_eq!(warnings.borrow().len(), 0);
+    }
+
+    #[test]
+    fn generate_v_model_warnings() {
+        let warnings = assert_codegen(
+            "<div v-model=\"x\"></div>",
+            "with(this){return _c('div',{directives:[{name:\"model\",rawName:\"v-model\",value:(x),expression:\"x\"}]})}",
+        );
+        assert_eq!(warnings.borrow().len(), 1);
+        assert!(warnings.borrow()[0]
+            .starts_with("<div v-model=\"x\">: v-model is not supported on this element type."));
+
+        let warnings = assert_codegen(
+            "<input :value=\"y\" v-model=\"x\">",
+            "with(this){return _c('input',{directives:[{name:\"model\",rawName:\"v-model\",value:(x),expression:\"x\"}],domProps:{\"value\":y,\"value\":(x)},on:{\"input\":function($event){if($event.target.composing)return;x=$event.target.value}}})}",
+        );
+        assert_eq!(
+            warnings.borrow()[0],
+            ":value=\"y\" conflicts with v-model on the same element because the latter already expands to a value binding internally"
+        );
+    }
+
+    #[test]
+    fn generate_empty_render() {
+        assert_codegen("", "with(this){return _c(\"div\")}");
+    }
+
+    #[test]
+    fn generate_scoped_slots() {
+        assert_codegen(
+            "<foo><template v-slot:header=\"{a}\"><p>{{a}}</p></template></foo>",
+            "with(this){return _c('foo',{scopedSlots:_u([{key:\"header\",fn:function({a}){return [_c('p',[_v(_s(a))])]}}])})}",
+        );
+        assert_codegen(
+            "<foo><div slot=\"x\" slot-scope=\"p\">{{p}}</div></foo>",
+            "with(this){return _c('foo',{scopedSlots:_u([{key:\"x\",fn:function(p){return _c('div',{},[_v(_s(p))])}}])})}",
+        );
+        assert_codegen(
+            "<foo><template #a>x</template></foo>",
+            "with(this){return _c('foo',{scopedSlots:_u([{key:\"a\",fn:function(){return [_v(\"x\")]},proxy:true}])})}",
+        );
+    }
+
+    #[test]
+    fn generate_scoped_slots_in_source_order() {
+        assert_codegen(
+            "<foo><template #b>x</template><template #a>y</template></foo>",
+            "with(this){return _c('foo',{scopedSlots:_u([{key:\"b\",fn:function(){return [_v(\"x\")]},proxy:true},{key:\"a\",fn:function(){return [_v(\"y\")]},proxy:true}])})}",
+        );
+        assert_codegen(
+            "<foo><template #header>x</template><template #Header>y</template></foo>",
+            "with(this){return _c('foo',{scopedSlots:_u([{key:\"header\",fn:function(){return [_v(\"x\")]},proxy:true},{key:\"Header\",fn:function(){return [_v(\"y\")]},proxy:true}])})}",
+        );
+    }
+
+    #[test]
+    fn generate_dynamic_scoped_slots() {
+        assert_codegen(
+            "<foo><template #[n]=\"p\">x</template></foo>",
+            "with(this){return _c('foo',{scopedSlots:_u([{key:n,fn:function(p){return [_v(\"x\")]}}],null,true)})}",
+        );
+        assert_codegen(
+            "<foo><template #a=\"p\" v-if=\"x\">x</template><template #a=\"p\" v-else>y</template></foo>",
+            "with(this){return _c('foo',{scopedSlots:_u([(x)?{key:\"a\",fn:function(p){return [_v(\"x\")]}}:{key:\"a\",fn:function(p){return [_v(\"y\")]}}],null,true)})}",
+        );
+        assert_codegen(
+            "<foo><template v-for=\"i in l\" #[i]=\"p\">x</template></foo>",
+            "with(this){return _c('foo',{scopedSlots:_u([_l((l),function(i){return {key:i,fn:function(p){return [_v(\"x\")]}}})],null,true)})}",
+        );
+        assert_codegen(
+            "<foo><template slot=\"a\" slot-scope=\"p\" v-if=\"x\">x</template></foo>",
+            "with(this){return _c('foo',{scopedSlots:_u([{key:\"a\",fn:function(p){return (x)?[_v(\"x\")]:undefined}}],null,true)})}",
+        );
+    }
+
+    #[test]
+    fn generate_scoped_slots_key_inside_v_if() {
+        assert_codegen(
+            "<div v-if=\"c\"><foo><template #a=\"p\">{{p}}</template></foo></div>",
+            "with(this){return (c)?_c('div',[_c('foo',{scopedSlots:_u([{key:\"a\",fn:function(p){return [_v(_s(p))]}}],null,false,3477062717)})],1):_e()}",
+        );
+    }
+
+    #[test]
+    fn generate_slot_outlet() {
+        assert_codegen(
+            "<div><slot></slot></div>",
+            "with(this){return _c('div',[_t(\"default\")],2)}",
+        );
+        assert_codegen(
+            "<div><slot name=\"header\"><p>fallback</p></slot></div>",
+            "with(this){return _c('div',[_t(\"header\",function(){return [_c('p',[_v(\"fallback\")])]})],2)}",
+        );
+        assert_codegen(
+            "<div><slot :name=\"n\">fallback</slot></div>",
+            "with(this){return _c('div',[_t(n,function(){return [_v(\"fallback\")]})],2)}",
+        );
+    }
+
+    #[test]
+    fn generate_slot_outlet_props() {
+        assert_codegen(
+            "<div><slot :item-id=\"x\"></slot></div>",
+            "with(this){return _c('div',[_t(\"default\",null,{\"itemId\":x})],2)}",
+        );
+        assert_codegen(
+            "<div><slot v-bind=\"obj\"></slot></div>",
+            "with(this){return _c('div',[_t(\"default\",null,null,obj)],2)}",
+        );
+        assert_codegen(
+            "<div><slot :item=\"x\" v-bind=\"obj\">fallback</slot></div>",
+            "with(this){return _c('div',[_t(\"default\",function(){return [_v(\"fallback\")]},{\"item\":x},obj)],2)}",
+        );
+        assert_codegen(
+            "<div><slot :[key]=\"x\"></slot></div>",
+            "with(this){return _c('div',[_t(\"default\",null,_d({},[key,x]))],2)}",
+        );
+    }
+
+    #[test]
+    fn generate_strip_with() {
+        let warnings = Rc::new(RefCell::new(Vec::new()));
+        let options = CompilerOptions {
+            strip_with: true,
+            ..create_options(&warnings)
+        };
+
+        assert_codegen_with_options(
+            "<div :class=\"{ active: isActive, foo }\" @click=\"go(1, $event)\">{{ msg | cap }} {{ Math.max(a.b, `${c}px`) }}</div>",
+            "var _vm=this;var _h=_vm.$createElement;var _c=_vm._self._c||_h;return _c('div',{class:{ active: _vm.isActive, foo:_vm.foo },on:{\"click\":function($event){return _vm.go(1, $event)}}},[_vm._v(_vm._s(_vm._f(\"cap\")(_vm.msg))+\" \"+_vm._s(Math.max(_vm.a.b, `${_vm.c}px`)))])",
+            &options,
+        );
+    }
+
+    #[test]
+    fn generate_strip_with_locals() {
+        let warnings = Rc::new(RefCell::new(Vec::new()));
+        let options = CompilerOptions {
+            strip_with: true,
+            ..create_options(&warnings)
+        };
+
+        assert_codegen_with_options(
+            "<ul><li v-for=\"({ id, name: n }, i) in items\" :key=\"id\" @click=\"x => pick(x, i)\">{{ n + other }}</li></ul>",
+            "var _vm=this;var _h=_vm.$createElement;var _c=_vm._self._c||_h;return _c('ul',_vm._l((_vm.items),function({ id, name: n },i){return _c('li',{key:id,on:{\"click\":x => _vm.pick(x, i)}},[_vm._v(_vm._s(n + _vm.other))])}),0)",
+            &options,
+        );
+        assert_codegen_with_options(
+            "<comp><template #default=\"{ item }\">{{ item.label }}</template></comp>",
+            "var _vm=this;var _h=_vm.$createElement;var _c=_vm._self._c||_h;return _c('comp',{scopedSlots:_vm._u([{key:\"default\",fn:function({ item }){return [_vm._v(_vm._s(item.label))]}}])})",
+            &options,
+        );
+        assert_codegen_with_options(
+            "<input type=\"checkbox\" v-model=\"checked\">",
+            "var _vm=this;var _h=_vm.$createElement;var _c=_vm._self._c||_h;return _c('input',{directives:[{name:\"model\",rawName:\"v-model\",value:(_vm.checked),expression:\"checked\"}],attrs:{\"type\":\"checkbox\"},domProps:{\"checked\":Array.isArray(_vm.checked)?_vm._i(_vm.checked,null)>-1:(_vm.checked)},on:{\"change\":function($event){var $$a=_vm.checked,$$el=$event.target,$$c=$$el.checked?(true):(false);if(Array.isArray($$a)){var $$v=null,$$i=_vm._i($$a,$$v);if($$el.checked){$$i<0&&(_vm.checked=$$a.concat([$$v]))}else{$$i>-1&&(_vm.checked=$$a.slice(0,$$i).concat($$a.slice($$i+1)))}}else{_vm.checked=$$c}}}})",
+            &options,
+        );
+    }
+}
@@ -0,0 +1,336 @@
+#[cfg(test)]
+mod tests {
+    use rs_vue2_compiler::codeframe::generate_code_frame;
+    use rs_vue2_compiler::diagnostics::{DiagnosticCode, Severity};
+    use rs_vue2_compiler::web::compiler::class::ClassModule;
+    use rs_vue2_compiler::web::compiler::model::ModelModule;
+    use rs_vue2_compiler::web::compiler::style::StyleModule;
+    use rs_vue2_compiler::{compile, CompilerOptions};
+
+    fn create_options() -> CompilerOptions {
+        CompilerOptions {
+            dev: true,
+            modules: Some(vec![
+                Box::new(ClassModule {}),
+                Box::new(ModelModule {}),
+                Box::new(StyleModule {}),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn compile_template() {
+        let res = compile(
+            "<div class=\"wrapper\"><p v-if=\"show\">{{ msg }}</p></div>",
+            &create_options(),
+        );
+
+        assert_eq!(
+            res.render,
+            "with(this){return _c('div',{staticClass:\"wrapper\"},[(show)?_c('p',[_v(_s(msg))]):_e()])}"
+        );
+        assert!(res.static_render_fns.is_empty());
+        assert!(res.errors.is_empty());
+        assert!(res.tips.is_empty());
+
+        let wrapper = res.ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.token.data, Box::from("div"));
+        assert_eq!(root.el.static_class.as_ref().unwrap(), "wrapper");
+    }
+
+    #[test]
+    fn compile_collects_errors() {
+        let res = compile("<div><p v-else>hello</p></div>", &create_options());
+
+        assert_eq!(
+            res.errors,
+            vec!["v-else used on element <p> without corresponding v-if."]
+        );
+        assert!(res.tips.is_empty());
+    }
+
+    #[test]
+    fn compile_collects_tips() {
+        let res = compile(
+            "<div><my-item v-for=\"item in items\"></my-item></div>",
+            &create_options(),
+        );
+
+        assert!(res.errors.is_empty());
+        assert_eq!(
+            res.tips,
+            vec!["<my-item v-for=\"item in items\">: component lists rendered with v-for should have explicit keys. See https://vuejs.org/guide/list.html#key for more info."]
+        );
+    }
+
+    #[test]
+    fn compile_collects_diagnostics() {
+        let res = compile(
+            "<div><p v-else>hello</p><my-item v-for=\"item in items\"></my-item></div>",
+            &create_options(),
+        );
+
+        assert_eq!(res.diagnostics.len(), 2);
+
+        let else_diagnostic = &res.diagnostics[0];
+        assert_eq!(else_diagnostic.code, DiagnosticCode::ElseWithoutIf);
+        assert_eq!(else_diagnostic.severity, Severity::Error);
+        assert_eq!(else_diagnostic.args, vec!["else", "p"]);
+        assert_eq!(else_diagnostic.tag.as_deref(), Some("p"));
+        assert_eq!(else_diagnostic.attr.as_deref(), Some("v-else"));
+        assert_eq!(else_diagnostic.message(), res.errors[0]);
+
+        let key_diagnostic = &res.diagnostics[1];
+        assert_eq!(key_diagnostic.code, DiagnosticCode::ComponentListWithoutKey);
+        assert_eq!(key_diagnostic.severity, Severity::Tip);
+        assert_eq!(key_diagnostic.message(), res.tips[0]);
+    }
+
+    #[test]
+    fn compile_diagnostic_messages_name_the_element() {
+        let res = compile("<div><style>a{}</style></div>", &create_options());
+        assert_eq!(res.diagnostics[0].code, DiagnosticCode::ForbiddenTag);
+        assert_eq!(
+            res.errors,
+            vec!["Templates should only be responsible for mapping the state to the UI. Avoid placing tags with side-effects in your templates, such as <style>, as they will not be parsed."]
+        );
+
+        let res = compile(
+            "<foo><bar v-for=\"i in list\" :key=\"i\" slot-scope=\"x\"></bar></foo>",
+            &create_options(),
+        );
+        assert_eq!(
+            res.tips,
+            vec!["Ambiguous combined usage of slot-scope and v-for on <bar> (v-for takes higher priority). Use a wrapper <template> for the scoped slot to make it clearer."]
+        );
+    }
+
+    #[test]
+    fn compile_v_on_without_value() {
+        let res = compile("<div @click></div>", &create_options());
+
+        assert_eq!(
+            res.render,
+            "with(this){return _c('div',{on:{\"click\":function($event){}}})}"
+        );
+        assert!(res.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn compile_diagnostic_ranges() {
+        let template = "<div>\n  <p v-for=\"item\">{{ item }}</p>\n</div>";
+        let res = compile(template, &create_options());
+
+        let diagnostic = &res.diagnostics[0];
+        assert_eq!(diagnostic.code, DiagnosticCode::InvalidVForExpression);
+        assert_eq!(
+            &template[diagnostic.start.unwrap()..diagnostic.end.unwrap()],
+            "v-for=\"item\""
+        );
+        assert_eq!(diagnostic.line_column(template), Some((2, 6)));
+    }
+
+    #[test]
+    fn compile_diagnostic_code_frame() {
+        let template = "<div>\n  <p v-for=\"item\">{{ item }}</p>\n</div>";
+        let res = compile(template, &create_options());
+
+        assert_eq!(
+            res.diagnostics[0].render(template),
+            "Invalid v-for expression\n\n\
+            1  |  <div>\n\
+            2  |    <p v-for=\"item\">{{ item }}</p>\n\
+            \x20  |       ^^^^^^^^^^^^\n\
+            3  |  </div>"
+        );
+    }
+
+    #[test]
+    fn compile_invalid_expression() {
+        let template = "<div :title=\"foo bar\" v-if=\"a &&\">{{ msg + }}</div>";
+        let res = compile(template, &create_options());
+
+        let codes: Vec<_> = res.diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(
+            codes,
+            vec![
+                DiagnosticCode::InvalidExpression,
+                DiagnosticCode::InvalidExpression,
+                DiagnosticCode::InvalidExpression
+            ]
+        );
+        assert_eq!(
+            res.errors[0],
+            "invalid expression: Unexpected identifier 'bar' in\n\n    foo bar\n\n  Raw expression: :title=\"foo bar\"\n"
+        );
+
+        // the offending token is underlined, the end of the value for incomplete expressions
+        let title = &res.diagnostics[0];
+        assert_eq!(title.attr.as_deref(), Some(":title"));
+        assert_eq!(&template[title.start.unwrap()..title.end.unwrap()], "bar");
+        let v_if = &res.diagnostics[1];
+        assert_eq!(v_if.start, v_if.end);
+        assert_eq!(v_if.start, template.find("\">"));
+
+        // interpolations are reported as a whole
+        let interpolation = &res.diagnostics[2];
+        assert_eq!(
+            &template[interpolation.start.unwrap()..interpolation.end.unwrap()],
+            "{{ msg + }}"
+        );
+    }
+
+    #[test]
+    fn compile_keyword_and_unary_operator_as_property_name() {
+        let template = "<div @click=\"delete(item)\" :class=\"{ a: class }\"></div>";
+        let res = compile(template, &create_options());
+
+        assert_eq!(
+            res.errors,
+            vec![
+                "avoid using JavaScript unary operator as property name: \"delete(item)\" in expression @click=\"delete(item)\"",
+                "avoid using JavaScript keyword as property name: \"class\"\n  Raw expression: :class=\"{ a: class }\""
+            ]
+        );
+        let keyword = &res.diagnostics[1];
+        assert_eq!(keyword.code, DiagnosticCode::KeywordAsPropertyName);
+        assert_eq!(
+            &template[keyword.start.unwrap()..keyword.end.unwrap()],
+            "class"
+        );
+
+        // methods named like operators are fine
+        let res = compile(
+            "<div @click=\"$delete(items, 0); list.delete(item)\"></div>",
+            &create_options(),
+        );
+        assert!(res.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn compile_invalid_v_for_and_slot_params() {
+        let template =
+            "<foo><p v-for=\"(item, 1) in items\"></p><template #item=\"{ a }, b,\"></template></foo>";
+        let res = compile(template, &create_options());
+
+        assert_eq!(
+            res.errors,
+            vec![
+                "invalid v-for iterator \"1\" in expression: v-for=\"(item, 1) in items\"",
+                "invalid function parameter expression: Unexpected end of input in\n\n    { a }, b,\n\n  Raw expression: #item=\"{ a }, b,\"\n"
+            ]
+        );
+        let iterator = &res.diagnostics[0];
+        assert_eq!(iterator.code, DiagnosticCode::InvalidIdentifier);
+        assert_eq!(
+            &template[iterator.start.unwrap()..iterator.end.unwrap()],
+            "1"
+        );
+        assert_eq!(
+            res.diagnostics[1].code,
+            DiagnosticCode::InvalidFunctionParameterExpression
+        );
+    }
+
+    #[test]
+    fn compile_valid_expressions() {
+        let res = compile(
+            "<div :class=\"{ a: b, [c]: d, e }\" :style=\"[a, { color }]\" @click=\"(e) => foo(e, `x${y}`)\" @keyup=\"a++; b = $event.target.value\">\
+            <p v-for=\"({ a, b }, i) in list\" :key=\"i\" :title=\"a?.b ?? (typeof c === 'string' ? c : /x+/g.test(d))\">{{ a | f(1) }}</p>\
+            <foo v-slot=\"{ item = 1 }, ...rest\" :cb=\"function () { return x in y }\"></foo>\
+            <pre v-pre>{{ not js }} <b :a=\"not js\"></b></pre>\
+            </div>",
+            &create_options(),
+        );
+
+        assert!(res.diagnostics.is_empty(), "{:?}", res.errors);
+    }
+
+    #[test]
+    fn compile_duplicate_attributes() {
+        let template =
+            "<div class=\"a\" :foo=\"x\" class=\"b\" v-bind:foo.camel=\"y\" @x=\"a\" v-on:x=\"b\"></div>";
+        let res = compile(template, &create_options());
+
+        assert_eq!(
+            res.errors,
+            vec![
+                "duplicate attribute: class",
+                "duplicate attribute: v-bind:foo.camel"
+            ]
+        );
+
+        // the duplicate is reported, the first occurrence is the related range
+        let class = &res.diagnostics[0];
+        assert_eq!(class.code, DiagnosticCode::DuplicateAttribute);
+        assert_eq!(
+            &template[class.start.unwrap()..class.end.unwrap()],
+            "class=\"b\""
+        );
+        let (start, end) = class.related.unwrap();
+        assert_eq!(&template[start..end], "class=\"a\"");
+
+        let (start, end) = res.diagnostics[1].related.unwrap();
+        assert_eq!(&template[start..end], ":foo=\"x\"");
+
+        // `.foo` is only a binding with the prop shorthand enabled
+        let options = CompilerOptions {
+            v_bind_prop_short_hand: true,
+            ..create_options()
+        };
+        let res = compile("<div :foo=\"x\" .foo=\"y\"></div>", &options);
+        assert_eq!(res.errors, vec!["duplicate attribute: .foo"]);
+        let res = compile("<div :foo=\"x\" .foo=\"y\"></div>", &create_options());
+        assert!(res.errors.is_empty());
+    }
+
+    #[test]
+    fn compile_missing_end_tag() {
+        let template = "<div>\n  <span class=\"a\">text\n</div>";
+        let res = compile(template, &create_options());
+
+        assert_eq!(res.errors, vec!["tag <span> has no matching end tag."]);
+        let diagnostic = &res.diagnostics[0];
+        assert_eq!(diagnostic.code, DiagnosticCode::MissingEndTag);
+        assert_eq!(
+            &template[diagnostic.start.unwrap()..diagnostic.end.unwrap()],
+            "<span class=\"a\">"
+        );
+    }
+
+    #[test]
+    fn code_frame_multi_line_range() {
+        let source = "a\nb\n<div\n  id=\"x\">\n</div>\nc\nd\ne";
+        let start = source.find("<div").unwrap();
+        let end = source.find("</div>").unwrap();
+
+        assert_eq!(
+            generate_code_frame(source, start, end),
+            "1  |  a\n\
+            2  |  b\n\
+            3  |  <div\n\
+            \x20  |  ^^^^\n\
+            4  |    id=\"x\">\n\
+            \x20  |  ^^^^^^^^^\n\
+            5  |  </div>\n\
+            6  |  c"
+        );
+    }
+
+    #[test]
+    fn code_frame_tabs_and_unicode() {
+        let source = "<div>\n\t\t<p title=\"héllo\">ünïcode</p>\n</div>";
+        let start = source.find("ünïcode").unwrap();
+        let end = start + "ünïcode".len();
+
+        assert_eq!(
+            generate_code_frame(source, start, end),
+            "1  |  <div>\n\
+            2  |  \t\t<p title=\"héllo\">ünïcode</p>\n\
+            \x20  |  \t\t                 ^^^^^^^\n\
+            3  |  </div>"
+        );
+    }
+}
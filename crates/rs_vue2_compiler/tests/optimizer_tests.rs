@@ -0,0 +1,208 @@
+#[cfg(test)]
+mod tests {
+    use rs_vue2_compiler::ast_tree::ASTTree;
+    use rs_vue2_compiler::optimizer::optimize;
+    use rs_vue2_compiler::web::compiler::class::ClassModule;
+    use rs_vue2_compiler::web::compiler::model::ModelModule;
+    use rs_vue2_compiler::web::compiler::style::StyleModule;
+    use rs_vue2_compiler::{compile, CompilerOptions, VueParser};
+
+    fn create_options() -> CompilerOptions {
+        CompilerOptions {
+            dev: true,
+            modules: Some(vec![
+                Box::new(ClassModule {}),
+                Box::new(ModelModule {}),
+                Box::new(StyleModule {}),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    fn parse_and_optimize(template: &str) -> ASTTree {
+        let options = create_options();
+        let mut parser = VueParser::new(&options);
+        let ast = parser.parse(template);
+        optimize(&ast, &options);
+
+        ast
+    }
+
+    #[test]
+    fn static_node() {
+        let ast = parse_and_optimize("<h1 id=\"section1\"><span>hello world</span></h1>");
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+
+        assert!(root.el.is_static);
+        assert!(root.el.static_root);
+        assert!(root.children[0].borrow().el.is_static);
+        assert!(!root.children[0].borrow().el.static_root);
+    }
+
+    #[test]
+    fn static_node_with_module_static_keys() {
+        let ast = parse_and_optimize("<div class=\"a\" style=\"color: red\"><p>hello</p></div>");
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+
+        assert!(root.el.is_static);
+        assert!(root.el.static_root);
+    }
+
+    #[test]
+    fn single_text_child_is_not_static_root() {
+        let ast = parse_and_optimize("<h1>hello world</h1>");
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+
+        assert!(root.el.is_static);
+        assert!(!root.el.static_root);
+    }
+
+    #[test]
+    fn interpolation_and_bindings() {
+        let ast = parse_and_optimize("<div><p>{{ msg }}</p><span :id=\"id\"><b>a</b></span></div>");
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+
+        assert!(!root.el.is_static);
+        assert!(!root.children[0].borrow().el.is_static);
+        assert!(!root.children[0].borrow().children[0].borrow().el.is_static);
+        assert!(!root.children[1].borrow().el.is_static);
+        assert!(root.children[1].borrow().children[0].borrow().el.is_static);
+    }
+
+    #[test]
+    fn v_if_and_v_else() {
+        let ast =
+            parse_and_optimize("<div><p v-if=\"show\"><b>a</b></p><p v-else><b>b</b></p></div>");
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let if_node = root.children[0].borrow();
+        let else_id = if_node.el.if_conditions.as_ref().unwrap()[1].block_id;
+        let else_node = ast.get(else_id).unwrap().borrow();
+
+        assert!(!root.el.is_static);
+        assert!(!if_node.el.is_static);
+        assert!(if_node.children[0].borrow().el.is_static);
+        // the v-else marker keeps the branch itself dynamic
+        assert!(!else_node.el.is_static);
+        assert!(else_node.children[0].borrow().el.is_static);
+    }
+
+    #[test]
+    fn v_for_and_static_in_for() {
+        let ast = parse_and_optimize(
+            "<ul><li v-for=\"item in items\" :key=\"item\"><span><b>a</b></span></li></ul>",
+        );
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let li = root.children[0].borrow();
+        let span = li.children[0].borrow();
+
+        assert!(!li.el.is_static);
+        assert!(span.el.is_static);
+        assert!(span.el.static_root);
+        assert!(span.el.static_in_for);
+    }
+
+    #[test]
+    fn components_and_slots() {
+        let ast = parse_and_optimize(
+            "<div><my-component><p><b>a</b></p></my-component><slot><p>b</p></slot><component is=\"a\"></component></div>",
+        );
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+
+        assert!(!root.el.is_static);
+        for child in &root.children {
+            assert!(!child.borrow().el.is_static);
+        }
+        // component slot content is never marked static
+        let component = root.children[0].borrow();
+        assert!(!component.children[0].borrow().el.is_static);
+    }
+
+    #[test]
+    fn components_named_like_html_tags() {
+        let ast = parse_and_optimize("<div><Button><p><b>a</b></p></Button><p>x</p></div>");
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+
+        assert!(!root.el.is_static);
+        let button = root.children[0].borrow();
+        assert!(!button.el.is_static);
+        assert!(!button.children[0].borrow().el.static_root);
+
+        let res = compile("<div><Button>hi</Button><p>x</p></div>", &create_options());
+        assert_eq!(
+            res.render,
+            "with(this){return _c('div',[_c('Button',[_v(\"hi\")]),_c('p',[_v(\"x\")])],1)}"
+        );
+        assert!(res.static_render_fns.is_empty());
+    }
+
+    #[test]
+    fn v_pre() {
+        let ast = parse_and_optimize("<div v-pre><p :id=\"a\">{{ msg }}</p></div>");
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+
+        assert!(root.el.is_static);
+        assert!(root.el.static_root);
+    }
+
+    #[test]
+    fn compile_hoists_static_trees() {
+        let res = compile(
+            "<div><p class=\"a\">hi <b>x</b></p><span>{{ a }}</span></div>",
+            &create_options(),
+        );
+
+        assert_eq!(
+            res.render,
+            "with(this){return _c('div',[_m(0),_c('span',[_v(_s(a))])])}"
+        );
+        assert_eq!(
+            res.static_render_fns,
+            vec![
+                "with(this){return _c('p',{staticClass:\"a\"},[_v(\"hi \"),_c('b',[_v(\"x\")])])}"
+            ]
+        );
+    }
+
+    #[test]
+    fn compile_static_in_for_and_v_once() {
+        let res = compile(
+            "<div><p v-for=\"i in list\" :key=\"i\"><span><b>a</b></span><b v-once>{{ i }}</b></p></div>",
+            &create_options(),
+        );
+
+        assert_eq!(
+            res.render,
+            "with(this){return _c('div',_l((list),function(i){return _c('p',{key:i},[_m(0,true),_o(_c('b',[_v(_s(i))]),0,i)])}),0)}"
+        );
+        assert_eq!(
+            res.static_render_fns,
+            vec!["with(this){return _c('span',[_c('b',[_v(\"a\")])])}"]
+        );
+    }
+
+    #[test]
+    fn compile_v_once_without_keyed_v_for() {
+        let res = compile(
+            "<div><p v-for=\"i in list\"><b v-once>{{ i }}</b></p></div>",
+            &create_options(),
+        );
+
+        assert_eq!(
+            res.render,
+            "with(this){return _c('div',_l((list),function(i){return _c('p',[_c('b',[_v(_s(i))])])}),0)}"
+        );
+        assert_eq!(
+            res.errors,
+            vec!["v-once can only be used inside v-for that is keyed. "]
+        );
+    }
+}
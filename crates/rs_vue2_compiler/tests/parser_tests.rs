@@ -2,7 +2,7 @@
 mod tests {
     use rs_html_parser_tokenizer_tokens::QuoteType;
     use rs_html_parser_tokens::TokenKind;
-    use rs_vue2_compiler::ast_tree::{ASTElementKind, ASTTree};
+    use rs_vue2_compiler::ast_tree::{ASTElementKind, ASTNode, ASTTree};
     use rs_vue2_compiler::web::compiler::class::ClassModule;
     use rs_vue2_compiler::web::compiler::model::ModelModule;
     use rs_vue2_compiler::web::compiler::style::StyleModule;
@@ -28,6 +28,9 @@ mod tests {
                 warnings_clone.borrow_mut().push(msg.to_string());
             })),
             delimiters: None,
+            key_codes: None,
+            scope_id: None,
+            strip_with: false,
             modules: Some(vec![
                 Box::new(ClassModule {}),
                 Box::new(ModelModule {}),
@@ -40,7 +43,7 @@ mod tests {
     }
 
     fn parse_with_options(template: &str, options: &CompilerOptions) -> ASTTree {
-        let mut parser = VueParser::new(&options);
+        let mut parser = VueParser::new(options);
 
         parser.parse(template)
     }
@@ -52,7 +55,7 @@ mod tests {
         let wrapper = ast.wrapper.borrow();
         let root = wrapper.children[0].borrow();
         assert_eq!(root.el.token.data, Box::from("h1"));
-        assert_eq!(root.el.plain, true);
+        assert!(root.el.plain);
         assert_eq!(
             root.children[0].borrow().el.token.data,
             Box::from("hello world")
@@ -66,7 +69,7 @@ mod tests {
         let wrapper = ast.wrapper.borrow();
         let root = wrapper.children[0].borrow();
         assert_eq!(root.el.token.data, Box::from("h1"));
-        assert_eq!(root.el.plain, true);
+        assert!(root.el.plain);
         assert_eq!(
             root.children[0].borrow().el.expression.clone().unwrap(),
             String::from("_s(msg)")
@@ -80,18 +83,18 @@ mod tests {
         let wrapper = ast.wrapper.borrow();
         let root = wrapper.children[0].borrow();
         assert_eq!(root.el.token.data, Box::from("ul"));
-        assert_eq!(root.el.plain, true);
+        assert!(root.el.plain);
 
         let child = root.children[0].borrow();
         assert_eq!(child.el.token.data, Box::from("li"));
-        assert_eq!(child.el.plain, true);
+        assert!(child.el.plain);
         assert_eq!(
             child.children[0].borrow().el.token.data,
             Box::from("hello world")
         );
 
         let parent = child.parent.as_ref().unwrap().upgrade().unwrap();
-        assert_eq!(Rc::ptr_eq(&parent, &wrapper.children[0]), true);
+        assert!(Rc::ptr_eq(&parent, &wrapper.children[0]));
     }
 
     #[test]
@@ -101,7 +104,7 @@ mod tests {
         let wrapper = ast.wrapper.borrow();
         let root = wrapper.children[0].borrow();
         assert_eq!(root.el.token.data, Box::from("hr"));
-        assert_eq!(root.el.plain, true);
+        assert!(root.el.plain);
         assert_eq!(root.children.len(), 0);
     }
 
@@ -113,7 +116,7 @@ mod tests {
         let root = wrapper.children[0].borrow();
         assert_eq!(root.el.token.data, Box::from("svg"));
         assert_eq!(root.el.ns, Some("svg"));
-        assert_eq!(root.el.plain, true);
+        assert!(root.el.plain);
 
         let child = root.children[0].borrow();
         assert_eq!(child.el.token.data, Box::from("text"));
@@ -123,7 +126,7 @@ mod tests {
         );
 
         let parent = child.parent.as_ref().unwrap().upgrade().unwrap();
-        assert_eq!(Rc::ptr_eq(&parent, &wrapper.children[0]), true);
+        assert!(Rc::ptr_eq(&parent, &wrapper.children[0]));
     }
 
     #[test]
@@ -133,18 +136,18 @@ mod tests {
         let wrapper = ast.wrapper.borrow();
         let root = wrapper.children[0].borrow();
         assert_eq!(root.el.token.data, Box::from("MyComponent"));
-        assert_eq!(root.el.plain, true);
+        assert!(root.el.plain);
 
         let child = root.children[0].borrow();
         assert_eq!(child.el.token.data, Box::from("p"));
-        assert_eq!(child.el.plain, true);
+        assert!(child.el.plain);
         assert_eq!(
             child.children[0].borrow().el.token.data,
             Box::from("hello world")
         );
 
         let parent = child.parent.as_ref().unwrap().upgrade().unwrap();
-        assert_eq!(Rc::ptr_eq(&parent, &wrapper.children[0]), true);
+        assert!(Rc::ptr_eq(&parent, &wrapper.children[0]));
     }
 
     #[test]
@@ -155,8 +158,8 @@ mod tests {
         let wrapper = style_ast.wrapper.borrow();
         let root = wrapper.children[0].borrow();
         assert_eq!(root.el.token.data, Box::from("style"));
-        assert_eq!(root.el.plain, true);
-        assert_eq!(root.el.forbidden, true);
+        assert!(root.el.plain);
+        assert!(root.el.forbidden);
         assert_eq!(
             root.children[0].borrow().el.token.data,
             Box::from("error { color: red; }")
@@ -169,8 +172,8 @@ mod tests {
         let wrapper = script_ast.wrapper.borrow();
         let root = wrapper.children[0].borrow();
         assert_eq!(root.el.token.data, Box::from("script"));
-        assert_eq!(root.el.plain, false);
-        assert_eq!(root.el.forbidden, true);
+        assert!(!root.el.plain);
+        assert!(root.el.forbidden);
         assert_eq!(
             root.children[0].borrow().el.token.data,
             Box::from("alert(\"hello world!\")")
@@ -259,8 +262,8 @@ mod tests {
         let wrapper = style_ast.wrapper.borrow();
         let root = wrapper.children[0].borrow();
         assert_eq!(root.el.token.data, Box::from("style"));
-        assert_eq!(root.el.plain, true);
-        assert_eq!(root.el.forbidden, true);
+        assert!(root.el.plain);
+        assert!(root.el.forbidden);
         assert_eq!(
             root.children[0].borrow().el.token.data,
             Box::from("error { color: red; }")
@@ -273,8 +276,8 @@ mod tests {
         let wrapper = script_ast.wrapper.borrow();
         let root = wrapper.children[0].borrow();
         assert_eq!(root.el.token.data, Box::from("script"));
-        assert_eq!(root.el.plain, false);
-        assert_eq!(root.el.forbidden, true);
+        assert!(!root.el.plain);
+        assert!(root.el.forbidden);
         assert_eq!(
             root.children[0].borrow().el.token.data,
             Box::from("alert(\"hello world!\")")
@@ -569,7 +572,7 @@ mod tests {
 
         let wrapper = ast.wrapper.borrow();
         let root = wrapper.children[0].borrow();
-        assert_eq!(root.el.pre, true);
+        assert!(root.el.pre);
         assert_eq!(root.el.attrs[0].name, "id");
         assert_eq!(root.el.attrs[0].value, Some("message1".to_string()));
         assert_eq!(
@@ -586,7 +589,7 @@ mod tests {
 
         let wrapper = ast.wrapper.borrow();
         let root = wrapper.children[0].borrow();
-        assert_eq!(root.el.pre, true);
+        assert!(root.el.pre);
         assert_eq!(root.el.attrs.len(), 1); // should be 1 but we can probably deal with it later
         assert_eq!(root.el.attrs[0].name, "id");
         assert_eq!(root.el.attrs[0].value, Some("message1".to_string()));
@@ -895,7 +898,7 @@ mod tests {
 
         let wrapper = ast.wrapper.borrow();
         let root = wrapper.children[0].borrow();
-        assert_eq!(root.el.once, true);
+        assert!(root.el.once);
     }
 
     #[test]
@@ -954,7 +957,27 @@ mod tests {
 
         let wrapper = ast.wrapper.borrow();
         let root = wrapper.children[0].borrow();
-        assert_eq!(root.el.inline_template, true);
+        assert!(root.el.inline_template);
+    }
+
+    #[test]
+    fn consumed_attributes_are_processed_once() {
+        let (ast, warnings) = parse("<div class=\"{{a}}\" style=\"{{b}}\" title=\"c\"></div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let names: Vec<_> = root
+            .el
+            .attrs
+            .iter()
+            .map(|attr| attr.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["title"]);
+
+        // one warning each from the class and style modules, none from process_attrs
+        assert_eq!(warnings.borrow().len(), 2);
+        assert!(warnings.borrow()[0].starts_with("class=\"{{a}}\": Interpolation"));
+        assert!(warnings.borrow()[1].starts_with("style=\"{{b}}\": Interpolation"));
     }
 
     #[test]
@@ -973,8 +996,8 @@ mod tests {
 
         // interpolation warning
         let (_ast3, warnings3) = parse("<p class=\"{{error}}\">hello world</p>");
-        assert_eq!(warnings3.borrow().len(), 2);
-        assert_eq!(warnings3.borrow()[0], "class=\"{{error}}\": Interpolation inside attributes has been removed. Use v-bind or the colon shorthand instead. For example, instead of <div class=\"{ val }\">, use <div :class=\"val\">.");
+        assert_eq!(warnings3.borrow().len(), 1);
+        assert_eq!(warnings3.borrow()[0], "class=\"{{error}}\": Interpolation inside attributes has been removed. Use v-bind or the colon shorthand instead. For example, instead of <div class=\"{{ val }}\">, use <div :class=\"val\">.");
     }
 
     #[test]
@@ -998,6 +1021,20 @@ mod tests {
         assert_eq!(input_ast.el.props[0].value.as_ref().unwrap(), "msg");
     }
 
+    #[test]
+    fn v_model_transform() {
+        let (ast, _warnings) = parse("<input v-model=\"msg\">");
+        let wrapper = ast.wrapper.borrow();
+        let input = wrapper.children[0].borrow();
+
+        assert_eq!(input.el.props.len(), 1);
+        assert_eq!(input.el.props[0].name, "value");
+        assert_eq!(input.el.props[0].value.as_deref(), Some("(msg)"));
+        let events = input.el.events.as_ref().unwrap();
+        assert_eq!(events.get("input").unwrap().len(), 1);
+        assert!(input.el.directives.as_ref().unwrap()[0].needs_runtime);
+    }
+
     #[test]
     fn empty_v_bind_expression() {
         let (_ast, warnings) = parse("<div :empty-msg=\"\"></div>");
@@ -1024,6 +1061,9 @@ mod tests {
                 warnings_clone.borrow_mut().push(msg.to_string());
             })),
             delimiters: None,
+            key_codes: None,
+            scope_id: None,
+            strip_with: false,
             modules: Some(vec![
                 Box::new(ClassModule {}),
                 Box::new(ModelModule {}),
@@ -1114,6 +1154,7 @@ mod tests {
             .unwrap()
             .clone()
             .into_iter()
+            .map(|(name, value)| (UniCase::new(name), value))
             .collect();
         let attrs_map_data: HashMap<UniCase<String>, Option<(Box<str>, QuoteType)>> = slot_data
             .el
@@ -1123,6 +1164,7 @@ mod tests {
             .unwrap()
             .clone()
             .into_iter()
+            .map(|(name, value)| (UniCase::new(name), value))
             .collect();
         let attrs_map_bar: HashMap<UniCase<String>, Option<(Box<str>, QuoteType)>> = slot_bar
             .el
@@ -1132,6 +1174,7 @@ mod tests {
             .unwrap()
             .clone()
             .into_iter()
+            .map(|(name, value)| (UniCase::new(name), value))
             .collect();
 
         assert_eq!(
@@ -1208,17 +1251,242 @@ mod tests {
 
         let wrapper1 = ast1.wrapper.borrow();
         let root1 = wrapper1.children[0].borrow();
-        assert_eq!(root1.el.attrs[2].name, "name");
-        assert_eq!(root1.el.attrs[2].value, Some("field1".to_string()));
-        assert_eq!(root1.el.attrs[1].name, "type");
-        assert_eq!(root1.el.attrs[1].value, Some("text".to_string()));
-        assert_eq!(root1.el.attrs[0].name, "value");
-        assert_eq!(root1.el.attrs[0].value, Some("hello world".to_string()));
+        assert_eq!(root1.el.attrs[0].name, "type");
+        assert_eq!(root1.el.attrs[0].value, Some("text".to_string()));
+        assert_eq!(root1.el.attrs[1].name, "name");
+        assert_eq!(root1.el.attrs[1].value, Some("field1".to_string()));
+        assert_eq!(root1.el.attrs[2].name, "value");
+        assert_eq!(root1.el.attrs[2].value, Some("hello world".to_string()));
 
         let (_ast2, warnings2) = parse("<input type=\"text\" name=\"field1\" value=\"{{msg}}\">");
 
         assert_eq!(warnings2.borrow().len(), 1);
-        assert_eq!(warnings2.borrow()[0], "value=\"{{msg}}\": Interpolation inside attributes has been removed. Use v-bind or the colon shorthand instead. For example, instead of <div id=\"{ val }\">, use <div :id=\"val\">.");
+        assert_eq!(warnings2.borrow()[0], "value=\"{{msg}}\": Interpolation inside attributes has been removed. Use v-bind or the colon shorthand instead. For example, instead of <div id=\"{{ val }}\">, use <div :id=\"val\">.");
+    }
+
+    #[test]
+    fn case_sensitive_attributes() {
+        let (ast, warnings) = parse(
+            "<div><svg viewBox=\"0 0 1 1\"></svg><comp :fooBar=\"a\" :foobar=\"b\"></comp></div>",
+        );
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let svg = root.children[0].borrow();
+        assert_eq!(svg.el.attrs[0].name, "viewBox");
+
+        let comp = root.children[1].borrow();
+        let names: Vec<_> = comp
+            .el
+            .attrs
+            .iter()
+            .map(|attr| attr.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["fooBar", "foobar"]);
+        assert_eq!(comp.el.attrs[1].value, Some("b".to_string()));
+        assert_eq!(
+            comp.el.raw_attrs_map.get(":foobar").unwrap().name,
+            ":foobar"
+        );
+        assert_eq!(
+            comp.el.raw_attrs_map.get(":FOOBAR").unwrap().name,
+            ":fooBar"
+        );
+
+        assert_eq!(warnings.borrow().len(), 1);
+        assert_eq!(warnings.borrow()[0], "Attribute \":foobar\" on <comp> only differs in case from \":fooBar\". HTML attributes are case-insensitive and in-DOM templates are lowercased by the browser, camelCased props have to use their kebab-case equivalent \":foo-bar\" there.");
+    }
+
+    #[test]
+    fn consumed_attributes_are_case_sensitive() {
+        let (ast, _warnings) = parse("<div :Key=\"a\" :key=\"b\"></div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.key.as_deref(), Some("b"));
+        // only `:key` was consumed, `:Key` is still bound as an attribute
+        assert_eq!(root.el.attrs.len(), 1);
+        assert_eq!(root.el.attrs[0].name, "Key");
+        assert_eq!(root.el.attrs[0].value.as_deref(), Some("a"));
+        assert!(!root.el.plain);
+    }
+
+    #[test]
+    fn case_sensitive_attribute_lookup() {
+        let (ast, _warnings) = parse("<comp :fooBar=\"a\" :foobar=\"b\" Title=\"c\"></comp>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.get_raw_attr_value(":fooBar"), Some("a"));
+        assert_eq!(root.get_raw_attr_value(":foobar"), Some("b"));
+        assert_eq!(root.get_raw_attr_value(":FOOBAR"), Some("a"));
+        assert_eq!(root.get_raw_attr_value("title"), Some("c"));
+    }
+
+    #[test]
+    fn components_named_like_html_tags_keep_children() {
+        let (ast, warnings) = parse(
+            "<div><Row><Col>a</Col><Col>b</Col></Row><ul><Li>c<Li>d</Li></Li></ul>\
+            <P>e<div>f</div></P></div>",
+        );
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let tags = |node: &ASTNode| -> Vec<String> {
+            node.children
+                .iter()
+                .map(|child| child.borrow().el.token.data.to_string())
+                .collect()
+        };
+        assert_eq!(tags(&root), vec!["Row", "ul", "P"]);
+
+        let row = root.children[0].borrow();
+        assert_eq!(tags(&row), vec!["Col", "Col"]);
+        assert_eq!(
+            row.children[0].borrow().children[0]
+                .borrow()
+                .el
+                .token
+                .data
+                .as_ref(),
+            "a"
+        );
+
+        // a nested <Li> isn't closed by its sibling rule
+        let ul = root.children[1].borrow();
+        let li = ul.children[0].borrow();
+        assert_eq!(tags(&ul), vec!["Li"]);
+        assert_eq!(li.children[1].borrow().el.token.data.as_ref(), "Li");
+
+        // <div> doesn't close <P> either
+        let p = root.children[2].borrow();
+        assert_eq!(p.children[1].borrow().el.token.data.as_ref(), "div");
+        assert_eq!(warnings.borrow().len(), 0);
+    }
+
+    #[test]
+    fn warn_missing_end_tags() {
+        let (ast, warnings) = parse("<div><span><b>a</div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let span = root.children[0].borrow();
+        assert_eq!(span.el.token.data.as_ref(), "span");
+        assert_eq!(span.children[0].borrow().el.token.data.as_ref(), "b");
+
+        assert_eq!(
+            *warnings.borrow(),
+            vec![
+                "tag <b> has no matching end tag.",
+                "tag <span> has no matching end tag."
+            ]
+        );
+
+        let (_ast, warnings) = parse("<div><p>unclosed");
+        assert_eq!(
+            *warnings.borrow(),
+            vec![
+                "tag <p> has no matching end tag.",
+                "tag <div> has no matching end tag."
+            ]
+        );
+
+        // stray end tags are dropped
+        let (_ast, warnings) = parse("<div></span></DIV>");
+        assert_eq!(warnings.borrow().len(), 0);
+    }
+
+    #[test]
+    fn implied_end_tags() {
+        let (ast, warnings) = parse(
+            "<div><p>a<div>b</div></p><ul><li>c<li>d</li></ul><comp/><hr><span></span></div>",
+        );
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let tags: Vec<_> = root
+            .children
+            .iter()
+            .map(|child| child.borrow().el.token.data.to_string())
+            .collect();
+        // the <div> closes the <p>, the stray </p> creates an empty one
+        assert_eq!(tags, vec!["p", "div", "p", "ul", "comp", "hr", "span"]);
+        assert_eq!(root.children[0].borrow().children.len(), 1);
+        assert_eq!(root.children[2].borrow().children.len(), 0);
+
+        let ul = root.children[3].borrow();
+        assert_eq!(ul.children.len(), 2);
+        assert_eq!(ul.children[0].borrow().children.len(), 1);
+        assert!(root.children[4].borrow().children.is_empty());
+
+        assert_eq!(warnings.borrow().len(), 0);
+    }
+
+    #[test]
+    fn maybe_component() {
+        let (ast, warnings) = parse(
+            "<div><my-button></my-button><button></button><component :is=\"c\"></component>\
+            <div is=\"span\"></div><table><tr is=\"row\"></tr></table><svg></svg></div>",
+        );
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let maybe_components: Vec<_> = root
+            .children
+            .iter()
+            .map(|child| child.borrow().is_maybe_component())
+            .collect();
+        // a static `is` binds the component once the element is processed
+        assert_eq!(
+            maybe_components,
+            vec![true, false, true, true, false, false]
+        );
+
+        let table = root.children[4].borrow();
+        assert!(table.children[0].borrow().is_maybe_component());
+        assert_eq!(warnings.borrow().len(), 0);
+    }
+
+    #[test]
+    fn maybe_component_is_case_sensitive() {
+        let (ast, warnings) = parse(
+            "<div><Button></Button><Col></Col><Select></Select><Table></Table>\
+            <svg><clipPath></clipPath><ClipPath></ClipPath></svg></div>",
+        );
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let maybe_components: Vec<_> = root
+            .children
+            .iter()
+            .map(|child| child.borrow().is_maybe_component())
+            .collect();
+        assert_eq!(maybe_components, vec![true, true, true, true, false]);
+
+        // only svg tags ignore case
+        let svg = root.children[4].borrow();
+        assert!(!svg.children[0].borrow().is_maybe_component());
+        assert!(!svg.children[1].borrow().is_maybe_component());
+        assert_eq!(warnings.borrow().len(), 0);
+    }
+
+    #[test]
+    fn v_slot_on_reserved_tags() {
+        let (_ast, warnings) = parse(
+            "<div><my-list v-slot=\"a\"></my-list><tr is=\"row\" v-slot=\"b\"></tr>\
+            <div is=\"span\" v-slot=\"c\"></div></div>",
+        );
+        assert_eq!(
+            *warnings.borrow(),
+            vec!["v-slot can only be used on components or <template>."]
+        );
+
+        let (_ast, warnings) =
+            parse("<div><template v-slot:header=\"props\">header</template></div>");
+        assert_eq!(
+            *warnings.borrow(),
+            vec!["<template v-slot> can only appear at the root level inside the receiving component."]
+        );
     }
 
     #[test]
@@ -1349,7 +1617,10 @@ mod tests {
         assert_eq!(root.el.token.data, Box::from("p"));
         assert_eq!(root.children.len(), 1);
         assert_eq!(root.children[0].borrow().el.token.kind, TokenKind::Text);
-        assert_eq!(root.children[0].borrow().el.token.data, Box::from("1 < 2 < 3"));
+        assert_eq!(
+            root.children[0].borrow().el.token.data,
+            Box::from("1 < 2 < 3")
+        );
     }
 
     #[test]
@@ -1409,4 +1680,76 @@ mod tests {
             Box::from("<!--comment-->")
         );
     }
+
+    #[test]
+    fn source_range() {
+        let template =
+            "<p :u=\"1\" v-foo:[a]=\"2\" @bar.stop='3' checked><span>{{ msg }}</span></p>";
+        let (ast, _warnings) = parse(template);
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.start, Some(0));
+        assert_eq!(root.el.end, Some(template.len()));
+
+        let raw_attrs = &root.el.raw_attrs_map;
+        let range = |name: &str| {
+            let attr = raw_attrs.get(name).unwrap();
+            &template[attr.start..attr.end]
+        };
+        assert_eq!(range(":u"), ":u=\"1\"");
+        assert_eq!(range("v-foo:[a]"), "v-foo:[a]=\"2\"");
+        assert_eq!(range("@bar.stop"), "@bar.stop='3'");
+        assert_eq!(range("checked"), "checked");
+        assert_eq!(raw_attrs.get(":u").unwrap().value.as_deref(), Some("1"));
+        assert_eq!(raw_attrs.get("checked").unwrap().value, None);
+
+        let span = root.children[0].borrow();
+        assert_eq!(
+            &template[span.el.start.unwrap()..span.el.end.unwrap()],
+            "<span>{{ msg }}</span>"
+        );
+        let text = span.children[0].borrow();
+        assert_eq!(
+            &template[text.el.start.unwrap()..text.el.end.unwrap()],
+            "{{ msg }}"
+        );
+    }
+
+    #[test]
+    fn source_range_of_implicitly_closed_elements() {
+        let template = "<ul><li>a &amp; b<li>c<br></ul>";
+        let (ast, _warnings) = parse(template);
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let source =
+            |start: Option<usize>, end: Option<usize>| &template[start.unwrap()..end.unwrap()];
+
+        let first = root.children[0].borrow();
+        assert_eq!(source(first.el.start, first.el.end), "<li>a &amp; b");
+        let text = first.children[0].borrow();
+        assert_eq!(source(text.el.start, text.el.end), "a &amp; b");
+
+        let second = root.children[1].borrow();
+        assert_eq!(source(second.el.start, second.el.end), "<li>c<br>");
+        let br = second.children[1].borrow();
+        assert_eq!(source(br.el.start, br.el.end), "<br>");
+    }
+
+    #[test]
+    fn source_range_with_stray_end_tags() {
+        let template = "<div></span><span title=\"a\">b</span></div>";
+        let (ast, _warnings) = parse(template);
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let span = root.children[0].borrow();
+        assert_eq!(
+            &template[span.el.start.unwrap()..span.el.end.unwrap()],
+            "<span title=\"a\">b</span>"
+        );
+        let title = span.el.raw_attrs_map.get("title").unwrap();
+        assert_eq!(&template[title.start..title.end], "title=\"a\"");
+    }
 }
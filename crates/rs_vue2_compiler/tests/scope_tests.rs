@@ -0,0 +1,147 @@
+#[cfg(test)]
+mod tests {
+    use rs_vue2_compiler::ast_tree::{ASTNode, ASTTree};
+    use rs_vue2_compiler::scope::{
+        expression_scopes, scope_bindings, ExpressionScope, ScopeBindingKind,
+    };
+    use rs_vue2_compiler::{CompilerOptions, VueParser};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn parse(template: &str) -> ASTTree {
+        let options = CompilerOptions {
+            dev: true,
+            new_slot_syntax: true,
+            ..Default::default()
+        };
+
+        VueParser::new(&options).parse(template)
+    }
+
+    fn find(tree: &ASTTree, predicate: impl Fn(&ASTNode) -> bool) -> Rc<RefCell<ASTNode>> {
+        tree.nodes()
+            .into_iter()
+            .find(|node| predicate(&node.borrow()))
+            .cloned()
+            .unwrap()
+    }
+
+    fn find_tag(tree: &ASTTree, tag: &str) -> Rc<RefCell<ASTNode>> {
+        find(tree, |node| node.el.token.data.as_ref() == tag)
+    }
+
+    fn scope<'a>(scopes: &'a [ExpressionScope], source: &str) -> &'a ExpressionScope {
+        scopes.iter().find(|scope| scope.source == source).unwrap()
+    }
+
+    #[test]
+    fn scope_v_for_bindings() {
+        let tree = parse(
+            "<ul><li v-for=\"({ id, name: n }, i) in items\"><b v-for=\"(value, key, index) in n\"></b></li></ul>",
+        );
+
+        let bindings = scope_bindings(&find_tag(&tree, "b").borrow());
+        let names: Vec<_> = bindings
+            .iter()
+            .map(|binding| (binding.name.as_str(), binding.kind))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("value", ScopeBindingKind::ForAlias),
+                ("key", ScopeBindingKind::ForIterator),
+                ("index", ScopeBindingKind::ForIterator),
+                ("id", ScopeBindingKind::ForAlias),
+                ("n", ScopeBindingKind::ForAlias),
+                ("i", ScopeBindingKind::ForIterator),
+            ]
+        );
+        assert!(scope_bindings(&find_tag(&tree, "ul").borrow()).is_empty());
+    }
+
+    #[test]
+    fn scope_slot_bindings() {
+        let tree = parse(
+            "<comp><template #item=\"{ row, index = 0 }\"><span>{{ row.title + suffix }}</span></template><p slot-scope=\"props\"></p></comp>",
+        );
+
+        let span = find_tag(&tree, "span");
+        let bindings = scope_bindings(&span.borrow());
+        let names: Vec<_> = bindings
+            .iter()
+            .map(|binding| binding.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["row", "index"]);
+        assert!(bindings
+            .iter()
+            .all(|binding| binding.kind == ScopeBindingKind::SlotScope));
+
+        let text = span.borrow().children[0].clone();
+        let scopes = expression_scopes(&text.borrow());
+        assert_eq!(scopes.len(), 1);
+        assert_eq!(scopes[0].source, "{{}}");
+        assert_eq!(scopes[0].locals, vec!["row"]);
+        assert_eq!(scopes[0].instance_reads, vec!["suffix"]);
+
+        let p = find_tag(&tree, "p");
+        let names: Vec<_> = scope_bindings(&p.borrow())
+            .into_iter()
+            .map(|binding| binding.name)
+            .collect();
+        assert_eq!(names, vec!["props"]);
+    }
+
+    #[test]
+    fn scope_expression_reads() {
+        let tree = parse(
+            "<ul><li v-for=\"(item, i) in items\" v-if=\"item.shown && !hidden\" :key=\"item.id\" :title=\"Math.max(i, max)\" :class=\"{ active: i === current }\" @click=\"select(item, $event)\" v-custom:[arg]=\"x => x + item\">{{ item.name | upper }}</li></ul>",
+        );
+
+        let li = find_tag(&tree, "li");
+        let scopes = expression_scopes(&li.borrow());
+
+        let v_for = scope(&scopes, "v-for");
+        assert!(v_for.locals.is_empty());
+        assert_eq!(v_for.instance_reads, vec!["items"]);
+
+        let v_if = scope(&scopes, "v-if");
+        assert_eq!(v_if.locals, vec!["item"]);
+        assert_eq!(v_if.instance_reads, vec!["hidden"]);
+
+        assert_eq!(scope(&scopes, ":key").locals, vec!["item"]);
+        assert_eq!(scope(&scopes, ":title").locals, vec!["i"]);
+        assert_eq!(scope(&scopes, ":title").instance_reads, vec!["max"]);
+        assert_eq!(scope(&scopes, ":class").instance_reads, vec!["current"]);
+
+        let click = scope(&scopes, "@click");
+        assert_eq!(click.locals, vec!["item", "$event"]);
+        assert_eq!(click.instance_reads, vec!["select"]);
+
+        let custom: Vec<_> = scopes
+            .iter()
+            .filter(|scope| scope.source == "v-custom:[arg]")
+            .collect();
+        assert_eq!(custom.len(), 2);
+        assert_eq!(custom[0].locals, vec!["item"]);
+        assert!(custom[0].instance_reads.is_empty());
+        assert_eq!(custom[1].instance_reads, vec!["arg"]);
+
+        let text = li.borrow().children[0].clone();
+        let scopes = expression_scopes(&text.borrow());
+        assert_eq!(scopes[0].locals, vec!["item"]);
+        assert!(scopes[0].instance_reads.is_empty());
+    }
+
+    #[test]
+    fn scope_event_outside_handlers() {
+        let tree = parse("<div :title=\"$event\" @input=\"value = $event\"></div>");
+
+        let div = find_tag(&tree, "div");
+        let scopes = expression_scopes(&div.borrow());
+        assert_eq!(scope(&scopes, ":title").instance_reads, vec!["$event"]);
+
+        let input = scope(&scopes, "@input");
+        assert_eq!(input.locals, vec!["$event"]);
+        assert_eq!(input.instance_reads, vec!["value"]);
+    }
+}
@@ -0,0 +1,92 @@
+#![cfg(feature = "serde")]
+
+#[cfg(test)]
+mod tests {
+    use rs_vue2_compiler::web::compiler::class::ClassModule;
+    use rs_vue2_compiler::web::compiler::model::ModelModule;
+    use rs_vue2_compiler::web::compiler::style::StyleModule;
+    use rs_vue2_compiler::{compile, CompilerOptions};
+    use serde_json::{json, to_value};
+
+    fn create_options() -> CompilerOptions {
+        CompilerOptions {
+            dev: true,
+            modules: Some(vec![
+                Box::new(ClassModule {}),
+                Box::new(ModelModule {}),
+                Box::new(StyleModule {}),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn serialize_element() {
+        let res = compile(
+            "<div id=\"app\" :title=\"msg\" @click.stop=\"go\">{{ msg }}!</div>",
+            &create_options(),
+        );
+
+        assert_eq!(
+            to_value(&res.ast).unwrap(),
+            json!({
+                "type": 1,
+                "tag": "div",
+                "attrsList": [
+                    {"name": "id", "value": "app", "start": 5, "end": 13},
+                    {"name": ":title", "value": "msg", "start": 14, "end": 26},
+                    {"name": "@click.stop", "value": "go", "start": 27, "end": 43}
+                ],
+                "attrsMap": {"id": "app", ":title": "msg", "@click.stop": "go"},
+                "rawAttrsMap": {
+                    "id": {"name": "id", "value": "app", "start": 5, "end": 13},
+                    ":title": {"name": ":title", "value": "msg", "start": 14, "end": 26},
+                    "@click.stop": {"name": "@click.stop", "value": "go", "start": 27, "end": 43}
+                },
+                "children": [{
+                    "type": 2,
+                    "expression": "_s(msg)+\"!\"",
+                    "tokens": [{"@binding": "msg"}, "!"],
+                    "text": "{{ msg }}!",
+                    "start": 44,
+                    "end": 54,
+                    "static": false
+                }],
+                "start": 0,
+                "end": 60,
+                "hasBindings": true,
+                "attrs": [
+                    {"name": "id", "value": "\"app\""},
+                    {"name": "title", "value": "msg", "dynamic": false}
+                ],
+                "events": {"click": {"value": "go", "dynamic": false, "modifiers": {"stop": true}}},
+                "static": false,
+                "staticRoot": false
+            })
+        );
+    }
+
+    #[test]
+    fn serialize_if_conditions_and_scoped_slots() {
+        let res = compile(
+            "<comp><p v-if=\"a\">a</p><p v-else>b</p><template #item=\"{ x }\">{{ x }}</template></comp>",
+            &CompilerOptions {
+                new_slot_syntax: true,
+                ..create_options()
+            },
+        );
+        let ast = to_value(&res.ast).unwrap();
+
+        // the first condition refers to the element itself and has no block
+        let p = &ast["children"][0];
+        assert_eq!(p["if"], "a");
+        assert_eq!(p["ifConditions"][0], json!({"exp": "a"}));
+        assert_eq!(p["ifConditions"][1]["block"]["else"], true);
+        assert_eq!(p["ifConditions"][1]["block"]["children"][0]["text"], "b");
+
+        let slot = &ast["scopedSlots"]["\"item\""];
+        assert_eq!(slot["slotScope"], "{ x }");
+        assert_eq!(slot["children"][0]["expression"], "_s(x)");
+        assert!(slot.get("parent").is_none());
+    }
+}
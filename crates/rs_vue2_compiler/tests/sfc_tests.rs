@@ -0,0 +1,263 @@
+#[cfg(test)]
+mod tests {
+    use rs_vue2_compiler::diagnostics::DiagnosticCode;
+    use rs_vue2_compiler::sfc::parse_component;
+    use rs_vue2_compiler::sfc::style::{
+        check_class_bindings, compile_style, scope_id, CssModulesOptions, StyleCompileOptions,
+        DEFAULT_SCOPED_NAME,
+    };
+    use rs_vue2_compiler::web::compiler::class::ClassModule;
+    use rs_vue2_compiler::CompilerOptions;
+    use std::collections::HashMap;
+
+    const SFC: &str = "<template functional>\n  <div><template v-if=\"ok\"><p>{{ msg }}</p></template></div>\n</template>\n\n<script lang=\"ts\">\nexport default { data: () => ({ msg: '</div>' }) }\n</script>\n\n<style scoped lang=\"scss\">\n.a { color: red; }\n</style>\n<style module=\"$css\" src=\"./b.css\"></style>\n<style module>.b {}</style>\n\n<docs>\n# Hello <b>\n</docs>\n";
+
+    #[test]
+    fn parse_component_blocks() {
+        let descriptor = parse_component(SFC);
+        assert!(descriptor.errors.is_empty());
+
+        let template = descriptor.template.as_ref().unwrap();
+        assert!(template.functional);
+        assert_eq!(
+            template.content,
+            "\n  <div><template v-if=\"ok\"><p>{{ msg }}</p></template></div>\n"
+        );
+        assert_eq!(&SFC[template.start..template.end], template.content);
+
+        let script = descriptor.script.as_ref().unwrap();
+        assert_eq!(script.lang.as_deref(), Some("ts"));
+        assert_eq!(
+            script.content,
+            "\nexport default { data: () => ({ msg: '</div>' }) }\n"
+        );
+
+        assert_eq!(descriptor.styles.len(), 3);
+        assert!(descriptor.styles[0].scoped);
+        assert_eq!(descriptor.styles[0].lang.as_deref(), Some("scss"));
+        assert_eq!(descriptor.styles[0].content, "\n.a { color: red; }\n");
+        assert_eq!(descriptor.styles[1].module.as_deref(), Some("$css"));
+        assert_eq!(descriptor.styles[1].src.as_deref(), Some("./b.css"));
+        assert_eq!(descriptor.styles[1].content, "");
+        assert_eq!(descriptor.styles[2].module.as_deref(), Some("$style"));
+        assert!(!descriptor.styles[2].scoped);
+
+        assert_eq!(descriptor.custom_blocks.len(), 1);
+        assert_eq!(descriptor.custom_blocks[0].block_type, "docs");
+        assert_eq!(descriptor.custom_blocks[0].content, "\n# Hello <b>\n");
+    }
+
+    #[test]
+    fn parse_component_errors() {
+        let descriptor =
+            parse_component("<template><div/></template>\n<template><p/></template>\n<style>");
+
+        assert_eq!(descriptor.template.unwrap().content, "<div/>");
+        assert_eq!(descriptor.errors.len(), 2);
+        assert_eq!(descriptor.errors[0].code, DiagnosticCode::DuplicateBlock);
+        assert_eq!(
+            descriptor.errors[0].message(),
+            "Single file component can contain only one <template> element."
+        );
+        assert_eq!(descriptor.errors[1].code, DiagnosticCode::MissingEndTag);
+        assert_eq!(descriptor.styles.len(), 1);
+    }
+
+    #[test]
+    fn parse_component_template_ranges() {
+        let descriptor = parse_component(SFC);
+        let options = CompilerOptions {
+            dev: true,
+            ..Default::default()
+        };
+
+        let ast = descriptor.parse_template(&options).unwrap();
+        let root = ast.get(1).unwrap().borrow();
+        assert_eq!(root.el.token.data.as_ref(), "div");
+        assert_eq!(
+            &SFC[root.el.start.unwrap()..root.el.start.unwrap() + 5],
+            "<div>"
+        );
+
+        let res = descriptor.compile_template(&options).unwrap();
+        assert_eq!(
+            res.render,
+            "with(this){return _c('div',[(ok)?[_c('p',[_v(_s(msg))])]:_e()],2)}"
+        );
+    }
+
+    fn compile_scoped(source: &str) -> String {
+        let res = compile_style(&StyleCompileOptions {
+            source,
+            id: "data-v-469af010",
+            scoped: true,
+            modules: None,
+        });
+        assert!(res.errors.is_empty());
+
+        res.code
+    }
+
+    #[test]
+    fn scope_id_matches_vue_loader() {
+        assert_eq!(scope_id("src/components/HelloWorld.vue"), "data-v-469af010");
+    }
+
+    #[test]
+    fn compile_scoped_style_selectors() {
+        assert_eq!(
+            compile_scoped(".a, .b:hover > p::before, [x=\"a,b\"] { color: red }"),
+            ".a[data-v-469af010], .b:hover > p[data-v-469af010]::before, [x=\"a,b\"][data-v-469af010] { color: red }"
+        );
+        assert_eq!(
+            compile_scoped("@media (max-width: 600px) { h1:not(.x), * { color: blue } }"),
+            "@media (max-width: 600px) { h1[data-v-469af010]:not(.x), *[data-v-469af010] { color: blue } }"
+        );
+    }
+
+    #[test]
+    fn compile_scoped_style_deep_selectors() {
+        assert_eq!(
+            compile_scoped(".a >>> .b {}\n.a /deep/ .b .c {}\n.a ::v-deep .b {}\n::v-deep .b {}"),
+            ".a[data-v-469af010] .b {}\n.a[data-v-469af010] .b .c {}\n.a[data-v-469af010] .b {}\n[data-v-469af010] .b {}"
+        );
+    }
+
+    #[test]
+    fn compile_scoped_style_keyframes() {
+        assert_eq!(
+            compile_scoped(
+                ".a { animation: fade 1s, spin 2s; animation-name: fade }\n@keyframes fade { from { opacity: 0 } }"
+            ),
+            ".a[data-v-469af010] { animation: fade-469af010 1s, spin 2s; animation-name: fade-469af010 }\n@keyframes fade-469af010 { from { opacity: 0 } }"
+        );
+    }
+
+    #[test]
+    fn compile_template_with_scope_id() {
+        let descriptor = parse_component(
+            "<template><div id=\"app\"><my-comp></my-comp><slot></slot></div></template><style scoped>.a {}</style>",
+        );
+        assert!(descriptor.styles[0].scoped);
+
+        let options = CompilerOptions {
+            scope_id: Some(scope_id("src/App.vue")),
+            ..Default::default()
+        };
+        let res = descriptor.compile_template(&options).unwrap();
+        assert_eq!(
+            res.render,
+            format!(
+                "with(this){{return _c('div',{{attrs:{{\"id\":\"app\",\"{0}\":\"\"}}}},[_c('my-comp',{{attrs:{{\"{0}\":\"\"}}}}),_t(\"default\")],2)}}",
+                scope_id("src/App.vue")
+            )
+        );
+    }
+
+    #[test]
+    fn compile_css_modules() {
+        let res = compile_style(&StyleCompileOptions {
+            source: ".red, .a:not(.red) > p { color: red }\n:global(.app) .blue, [class=\".x\"] {}",
+            id: "data-v-469af010",
+            scoped: false,
+            modules: Some(CssModulesOptions {
+                filename: "src/components/Button.vue",
+                generate_scoped_name: "[local]_[hash:5]",
+            }),
+        });
+
+        let modules = res.modules.unwrap();
+        let red = &modules["red"];
+        let blue = &modules["blue"];
+        assert_eq!(modules.len(), 3);
+        assert!(red.starts_with("red_") && red.len() == 9);
+        assert_eq!(
+            res.code,
+            format!(
+                ".{0}, .{1}:not(.{0}) > p {{ color: red }}\n.app .{2}, [class=\".x\"] {{}}",
+                red, modules["a"], blue
+            )
+        );
+
+        let res = compile_style(&StyleCompileOptions {
+            source: ".red {}",
+            id: "data-v-469af010",
+            scoped: true,
+            modules: Some(CssModulesOptions {
+                filename: "src/components/Button.vue",
+                generate_scoped_name: DEFAULT_SCOPED_NAME,
+            }),
+        });
+        let red = &res.modules.as_ref().unwrap()["red"];
+        assert!(red.starts_with("Button__red___"));
+        assert_eq!(res.code, format!(".{}[data-v-469af010] {{}}", red));
+    }
+
+    #[test]
+    fn css_module_hashes_differ() {
+        let res = compile_style(&StyleCompileOptions {
+            source: ".a {} .b {} .c {} .d {}",
+            id: "data-v-469af010",
+            scoped: false,
+            modules: Some(CssModulesOptions {
+                filename: "src/components/Button.vue",
+                generate_scoped_name: "h[hash:5]",
+            }),
+        });
+
+        let modules = res.modules.unwrap();
+        let mut names: Vec<_> = modules.values().collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), 4);
+    }
+
+    #[test]
+    fn compile_styles_with_escaped_non_ascii() {
+        let res = compile_style(&StyleCompileOptions {
+            source: "#\\éa, .b\\é { content: \"\\é\" }",
+            id: "data-v-469af010",
+            scoped: true,
+            modules: Some(CssModulesOptions {
+                filename: "src/components/Button.vue",
+                generate_scoped_name: "[local]_x",
+            }),
+        });
+
+        assert_eq!(
+            res.code,
+            "#\\éa[data-v-469af010], .b\\é_x[data-v-469af010] { content: \"\\é\" }"
+        );
+    }
+
+    #[test]
+    fn check_css_module_class_bindings() {
+        let source = "<template>\n<div :class=\"[$style.red, $style['blue'], $style.missing]\"></div>\n</template>\n<style module>.red {} .blue {}</style>";
+        let descriptor = parse_component(source);
+        let style = &descriptor.styles[0];
+        let res = compile_style(&StyleCompileOptions {
+            source: &style.content,
+            id: "",
+            scoped: false,
+            modules: Some(CssModulesOptions {
+                filename: "App.vue",
+                generate_scoped_name: DEFAULT_SCOPED_NAME,
+            }),
+        });
+
+        let options = CompilerOptions {
+            modules: Some(vec![Box::new(ClassModule {})]),
+            ..Default::default()
+        };
+        let ast = descriptor.parse_template(&options).unwrap();
+        let modules = HashMap::from([(style.module.clone().unwrap(), res.modules.unwrap())]);
+        let diagnostics = check_class_bindings(&ast, &modules);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message(),
+            "Class \"missing\" referenced as $style.missing is not defined in the css module \"$style\"."
+        );
+        assert_eq!(diagnostics[0].line_column(source), Some((2, 6)));
+    }
+}
@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod tests {
+    use rs_vue2_compiler::ast_tree::SSROptimizability;
+    use rs_vue2_compiler::web::compiler::class::ClassModule;
+    use rs_vue2_compiler::web::compiler::model::ModelModule;
+    use rs_vue2_compiler::web::compiler::style::StyleModule;
+    use rs_vue2_compiler::{compile, CompilerOptions};
+
+    fn create_options() -> CompilerOptions {
+        CompilerOptions {
+            dev: true,
+            is_ssr: true,
+            modules: Some(vec![
+                Box::new(ClassModule {}),
+                Box::new(ModelModule {}),
+                Box::new(StyleModule {}),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    fn assert_render(template: &str, expected: &str) {
+        let res = compile(template, &create_options());
+
+        assert_eq!(res.render, expected);
+        assert!(res.static_render_fns.is_empty());
+        assert!(res.errors.is_empty());
+    }
+
+    #[test]
+    fn ssr_static_children() {
+        assert_render(
+            "<div id=\"app\" class=\"a  b\"><span title=\"x&quot;y\">hi &lt;</span><!-- no --></div>",
+            "with(this){return _c('div',{staticClass:\"a b\",attrs:{\"id\":\"app\"}},[_ssrNode(\"<span title=\\\"x&quot;y\\\">hi &lt;</span>\")])}",
+        );
+    }
+
+    #[test]
+    fn ssr_interpolation() {
+        assert_render(
+            "<div><p>{{ msg }}</p></div>",
+            "with(this){return _c('div',[_ssrNode(\"<p>\"+_ssrEscape(_s(msg))+\"</p>\")])}",
+        );
+    }
+
+    #[test]
+    fn ssr_dynamic_attrs_class_and_style() {
+        assert_render(
+            "<div><p :title=\"t\" :id=\"'foo'\" disabled class=\"a\" :class=\"cls\" style=\"color:red\" v-show=\"s\"></p></div>",
+            "with(this){return _c('div',[_ssrNode(\"<p\"+(_ssrAttr(\"title\",t))+\" id=\\\"foo\\\" disabled=\\\"disabled\\\"\"+(_ssrClass(\"a\",cls))+(_ssrStyle({\"color\":\"red\"},null, { display: (s) ? '' : 'none' }))+\"></p>\")])}",
+        );
+    }
+
+    #[test]
+    fn ssr_v_model() {
+        assert_render(
+            "<div><input v-model=\"msg\"><textarea v-model=\"text\"></textarea></div>",
+            "with(this){return _c('div',[_ssrNode(\"<input\"+(_ssrAttr(\"value\",(msg)))+\"><textarea>\"+_ssrEscape(_s(text))+\"</textarea>\")])}",
+        );
+    }
+
+    #[test]
+    fn ssr_v_for_and_v_if() {
+        assert_render(
+            "<div><p v-for=\"i in list\">{{ i }}</p><b v-if=\"a\">x</b><i v-else>y</i></div>",
+            "with(this){return _c('div',[_ssrNode((_ssrList((list),function(i){return (\"<p>\"+_ssrEscape(_s(i))+\"</p>\")}))+((a)?(\"<b>x</b>\"):(\"<i>y</i>\")))])}",
+        );
+    }
+
+    #[test]
+    fn ssr_v_html_and_v_text() {
+        assert_render(
+            "<div><p v-html=\"html\">ignored</p><p v-text=\"text\"></p></div>",
+            "with(this){return _c('div',[_ssrNode(\"<p>\"+(_s(html))+\"</p><p>\"+_ssrEscape(_s(text))+\"</p>\")])}",
+        );
+    }
+
+    #[test]
+    fn ssr_components_break_string_groups() {
+        assert_render(
+            "<div><b>a</b><my-comp></my-comp><p><comp></comp></p></div>",
+            "with(this){return _c('div',[_ssrNode(\"<b>a</b>\"),_c('my-comp'),_ssrNode(\"<p>\",\"</p>\",[_c('comp')],1)],2)}",
+        );
+    }
+
+    #[test]
+    fn ssr_custom_directive_keeps_vnode() {
+        assert_render(
+            "<div><span v-foo=\"a\">x<b>y</b></span></div>",
+            "with(this){return _c('div',[_c('span',{directives:[{name:\"foo\",rawName:\"v-foo\",value:(a),expression:\"a\"}]},[_ssrNode(\"x<b>y</b>\")])])}",
+        );
+    }
+
+    #[test]
+    fn ssr_optimizability() {
+        let res = compile("<div><p>a</p><comp></comp></div>", &create_options());
+
+        let wrapper = res.ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.ssr_optimizability, Some(SSROptimizability::Partial));
+        // the static <p> is grouped into a template
+        let group = root.children[0].borrow();
+        assert_eq!(group.el.token.data, Box::from("template"));
+        assert_eq!(group.el.ssr_optimizability, Some(SSROptimizability::Full));
+        assert_eq!(group.children[0].borrow().el.token.data, Box::from("p"));
+        assert_eq!(
+            root.children[1].borrow().el.ssr_optimizability,
+            Some(SSROptimizability::Unoptimizable)
+        );
+    }
+}